@@ -0,0 +1,169 @@
+use super::interface::{ActionInterface, InputSpec};
+use super::is_release;
+use crate::config::ConfigError;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const BUNDLED: &str = include_str!("bundled.toml");
+
+// Interfaces of remote actions, which we can't read the action.yml of without
+// going to the network.
+#[derive(Debug, Default)]
+pub struct Catalogue {
+    entries: Vec<CatalogueEntry>,
+}
+
+#[derive(Debug)]
+struct CatalogueEntry {
+    uses: String,
+    versions: Vec<String>,
+    interface: ActionInterface,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CatalogueFile {
+    #[serde(default)]
+    action: Vec<RawEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawEntry {
+    uses: String,
+    versions: Vec<String>,
+    #[serde(default)]
+    inputs: BTreeMap<String, RawInput>,
+    #[serde(default)]
+    outputs: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawInput {
+    #[serde(default)]
+    required: bool,
+    default: Option<String>,
+    deprecated: Option<String>,
+}
+
+impl Catalogue {
+    pub fn bundled() -> Catalogue {
+        Catalogue::parse(BUNDLED).expect("bundled action catalogue is valid")
+    }
+
+    // a single catalogue file or a directory of them
+    pub fn load(path: impl AsRef<Path>) -> Result<Catalogue, ConfigError> {
+        let path = path.as_ref();
+        let mut catalogue = Catalogue::default();
+
+        if path.is_dir() {
+            let listing = std::fs::read_dir(path).map_err(|e| ConfigError::new(path, e))?;
+            let mut files: Vec<_> = listing
+                .filter_map(Result::ok)
+                .map(|e| e.path())
+                .filter(|p| p.extension().map_or(false, |ext| ext == "toml"))
+                .collect();
+            files.sort();
+
+            for f in files {
+                catalogue.extend(Catalogue::load(f)?);
+            }
+        } else {
+            let raw = std::fs::read_to_string(path).map_err(|e| ConfigError::new(path, e))?;
+            catalogue.extend(Catalogue::parse(&raw).map_err(|e| ConfigError::new(path, e))?);
+        }
+
+        Ok(catalogue)
+    }
+
+    fn parse(raw: &str) -> Result<Catalogue, toml::de::Error> {
+        let file: CatalogueFile = toml::from_str(raw)?;
+        let entries = file
+            .action
+            .into_iter()
+            .map(|raw| {
+                let inputs = raw
+                    .inputs
+                    .into_iter()
+                    .map(|(name, input)| InputSpec {
+                        name,
+                        required: input.required,
+                        has_default: input.default.is_some(),
+                        deprecation: input.deprecated,
+                    })
+                    .collect();
+
+                CatalogueEntry {
+                    interface: ActionInterface::new(raw.uses.clone(), inputs, raw.outputs),
+                    uses: raw.uses,
+                    versions: raw.versions,
+                }
+            })
+            .collect();
+
+        Ok(Catalogue { entries })
+    }
+
+    // entries added later take precedence, so teams can correct the bundled
+    // catalogue or describe their own actions
+    pub fn extend(&mut self, other: Catalogue) {
+        self.entries.extend(other.entries);
+    }
+
+    // the release uses names, later entries taking precedence
+    pub fn lookup(&self, uses: &str) -> Option<&ActionInterface> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| is_release(uses, &e.uses, &e.versions))
+            .map(|e| &e.interface)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Catalogue, BUNDLED};
+
+    #[test]
+    fn bundled_catalogue_parses() {
+        if let Err(e) = Catalogue::parse(BUNDLED) {
+            panic!("bundled.toml: {e}");
+        }
+
+        let catalogue = Catalogue::bundled();
+        let checkout = catalogue.lookup("actions/checkout@v4.1.1").unwrap();
+        assert!(checkout.input("fetch-depth").map_or(false, |i| i.has_default));
+        assert!(checkout.outputs().any(|o| o == "commit"));
+
+        let cache = catalogue.lookup("actions/cache@v4").unwrap();
+        assert!(cache.input("key").map_or(false, |i| i.must_be_provided()));
+        assert!(cache.input("save-always").map_or(false, |i| i.deprecation.is_some()));
+    }
+
+    #[test]
+    fn lookup_by_major_version() {
+        let catalogue = Catalogue::bundled();
+        assert!(catalogue.lookup("Actions/Checkout@v4").is_some());
+        assert!(catalogue.lookup("actions/checkout@v40").is_none());
+        assert!(catalogue.lookup("actions/checkout@8ade135a41bc03ea155e62e844d188df1ea18608").is_none());
+        assert!(catalogue.lookup("actions/checkout").is_none());
+    }
+
+    #[test]
+    fn later_entries_take_precedence() {
+        let mut catalogue = Catalogue::bundled();
+        let ours = "[[action]]\nuses = \"actions/checkout\"\nversions = [\"v4\"]\n[action.inputs]\nours = {}\n";
+        catalogue.extend(Catalogue::parse(ours).unwrap());
+
+        let checkout = catalogue.lookup("actions/checkout@v4").unwrap();
+        assert!(checkout.input("ours").is_some());
+        assert!(checkout.input("fetch-depth").is_none());
+    }
+
+    #[test]
+    fn unknown_fields_are_refused() {
+        assert!(Catalogue::parse("[[action]]\nuses = \"a/b\"\nversions = []\nrequired = true\n").is_err());
+    }
+}
@@ -0,0 +1,89 @@
+use super::Action;
+
+// What a caller needs to know about an action to check how it is used. These
+// come from action.yml files in the project or from a catalogue of well known
+// actions, and outlive the documents they were read from.
+#[derive(Debug, Clone)]
+pub struct ActionInterface {
+    name: String,
+    inputs: Vec<InputSpec>,
+    outputs: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InputSpec {
+    pub name: String,
+    pub required: bool,
+    pub has_default: bool,
+    pub deprecation: Option<String>,
+}
+
+impl InputSpec {
+    // GitHub won't error on a missing required input with a default
+    pub fn must_be_provided(&self) -> bool {
+        self.required && !self.has_default
+    }
+}
+
+impl ActionInterface {
+    pub fn new(name: impl Into<String>, inputs: Vec<InputSpec>, outputs: Vec<String>) -> Self {
+        ActionInterface {
+            name: name.into(),
+            inputs,
+            outputs,
+        }
+    }
+
+    pub fn from_action(name: impl Into<String>, action: &Action) -> ActionInterface {
+        let inputs = action
+            .inputs
+            .iter()
+            .filter_map(|inputs| inputs.value())
+            .flat_map(|inputs| inputs.iter())
+            .filter_map(|(name, input)| {
+                let name = name.value()?;
+                let input = input.value();
+                Some(InputSpec {
+                    name: name.to_owned(),
+                    required: input
+                        .and_then(|i| i.required.as_ref())
+                        .and_then(|r| r.value())
+                        .copied()
+                        .unwrap_or(false),
+                    has_default: input.map_or(false, |i| i.default.is_some()),
+                    deprecation: input
+                        .and_then(|i| i.deprecation_message.as_ref())
+                        .and_then(|d| d.value())
+                        .cloned(),
+                })
+            })
+            .collect();
+
+        let outputs = action
+            .outputs
+            .iter()
+            .filter_map(|outputs| outputs.value())
+            .flat_map(|outputs| outputs.iter())
+            .filter_map(|(name, _)| name.value().cloned())
+            .collect();
+
+        ActionInterface::new(name, inputs, outputs)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn inputs(&self) -> std::slice::Iter<InputSpec> {
+        self.inputs.iter()
+    }
+
+    pub fn outputs(&self) -> std::slice::Iter<String> {
+        self.outputs.iter()
+    }
+
+    // input names are case insensitive
+    pub fn input(&self, name: &str) -> Option<&InputSpec> {
+        self.inputs.iter().find(|i| i.name.eq_ignore_ascii_case(name))
+    }
+}
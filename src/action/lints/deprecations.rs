@@ -0,0 +1,33 @@
+use crate::action::Action;
+use crate::document::{Annotatable, Annotation};
+use crate::lint::deprecations::deprecations;
+use crate::lint::{rules, LintRule};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::lints::deprecations::step_deprecations;
+
+// retired runtimes in runs.using, and the same checks as a workflow's steps
+// for a composite action's
+pub struct ActionDeprecations;
+
+impl LintRule<Action> for ActionDeprecations {
+    fn lint(&self, root: &PossumNode<Action>, annotations: &mut impl Annotatable) {
+        let using = root
+            .value()
+            .and_then(|a| a.runs.as_ref()?.value()?.using.as_ref());
+
+        if let Some(using) = using {
+            let runtime = using.value().and_then(|u| deprecations().runtime(&u.to_string()));
+            if let Some(runtime) = runtime {
+                annotations.annotate(Annotation::warn(
+                    &rules::DEPRECATED_ACTION_RUNTIME,
+                    using,
+                    &format!("{} has been retired by GitHub, use {}", runtime.using, runtime.replacement),
+                ));
+            }
+        }
+
+        for step in root.value().into_iter().flat_map(|a| a.steps()).filter_map(|s| s.value()) {
+            step_deprecations(step, annotations);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::action::interface::ActionInterface;
+use crate::document::{Annotatable, Annotation};
+use crate::lint::{rules, LintRule};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::job::Step;
+
+// checks a step's `with` against the inputs of the action it uses
+pub struct ActionInputs<'a>(pub &'a ActionInterface);
+
+impl<'a> LintRule<Step> for ActionInputs<'a> {
+    fn lint(&self, root: &PossumNode<Step>, annotations: &mut impl Annotatable) {
+        let step = match root.value() {
+            Some(step) => step,
+            None => return,
+        };
+
+        let action = self.0;
+        let provided: Vec<_> = step
+            .with
+            .iter()
+            .filter_map(|with| with.value())
+            .flat_map(|with| with.iter())
+            .filter_map(|(key, _)| key.value().map(|k| (k, key)))
+            .collect();
+
+        for (name, key) in provided.iter() {
+            match action.input(name) {
+                None => annotations.annotate(Annotation::error(
+                    &rules::UNKNOWN_ACTION_INPUT,
+                    *key,
+                    &format!("{} has no input named {}", action.name(), name),
+                )),
+                Some(input) => {
+                    if let Some(msg) = input.deprecation.as_ref() {
+                        annotations.annotate(Annotation::warn(
+                            &rules::DEPRECATED_ACTION_INPUT,
+                            *key,
+                            &format!("{} input {} is deprecated: {}", action.name(), name, msg),
+                        ))
+                    }
+                }
+            }
+        }
+
+        let at = match step.uses.as_ref() {
+            Some(uses) => uses.span(),
+            None => root.span(),
+        };
+
+        for input in action.inputs().filter(|i| i.must_be_provided()) {
+            if !provided
+                .iter()
+                .any(|(name, _)| input.name.eq_ignore_ascii_case(name))
+            {
+                annotations.annotate(Annotation::error(
+                    &rules::MISSING_ACTION_INPUT,
+                    &at,
+                    &format!("{} requires input {}", action.name(), input.name),
+                ))
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+mod deprecations;
+mod inputs;
+pub use inputs::ActionInputs;
+use super::{Action, Using};
+use crate::config::Options;
+use crate::document::Annotatable;
+use crate::lint::{LintRule, LintViolation};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::lints::MissingProperty;
+
+pub fn lint_action(action: &PossumNode<Action>, _options: &Options, annotations: &mut impl Annotatable) {
+    MissingActionProperties.lint(action, annotations);
+    deprecations::ActionDeprecations.lint(action, annotations);
+}
+
+pub struct MissingActionProperties;
+
+impl LintRule<Action> for MissingActionProperties {
+    fn lint(&self, root: &PossumNode<Action>, annotations: &mut impl Annotatable) {
+        let action = match root.value() {
+            Some(action) => action,
+            None => return,
+        };
+
+        for (prop, missing) in [
+            ("name", action.name.is_none()),
+            ("description", action.description.is_none()),
+            ("runs", action.runs.is_none()),
+        ] {
+            if missing {
+                annotations.annotate(MissingProperty(prop).at(root));
+            }
+        }
+
+        let Some(runs) = action.runs.as_ref() else {
+            return;
+        };
+
+        let Some(r) = runs.value() else {
+            return;
+        };
+
+        let using = match r.using.as_ref() {
+            None => {
+                annotations.annotate(MissingProperty("runs.using").at(runs));
+                return;
+            }
+            Some(using) => using,
+        };
+
+        let required = match using.value() {
+            Some(Using::Composite) => ("runs.steps", r.steps.is_none()),
+            Some(Using::Docker) => ("runs.image", r.image.is_none()),
+            Some(u) if u.is_node() => ("runs.main", r.main.is_none()),
+            _ => return,
+        };
+
+        if required.1 {
+            annotations.annotate(MissingProperty(required.0).at(runs));
+        }
+    }
+}
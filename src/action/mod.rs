@@ -0,0 +1,127 @@
+pub mod catalogue;
+pub mod interface;
+pub mod lints;
+pub mod parser;
+
+pub use self::parser::ActionParser;
+use crate::scavenge::ast::*;
+use crate::workflow::job::Step;
+use crate::workflow::on::WorkflowInputDefault;
+use std::default::Default;
+use std::path::Path;
+
+// action.yml and action.yaml are the only names GitHub looks for
+pub fn is_action_metadata(p: &Path) -> bool {
+    matches!(
+        p.file_name().and_then(|n| n.to_str()),
+        Some("action.yml") | Some("action.yaml")
+    )
+}
+
+// Whether `owner/repo[/path]@ref` is one of the versions of action, where a
+// ref of v4.1.0 is the v4 release. Commit shas don't tell us a version so
+// they never are.
+pub fn is_release(uses: &str, action: &str, versions: &[String]) -> bool {
+    let Some((name, reference)) = uses.split_once('@') else {
+        return false;
+    };
+
+    name.eq_ignore_ascii_case(action)
+        && versions.iter().any(|v| {
+            reference == v
+                || reference
+                    .strip_prefix(v.as_str())
+                    .map_or(false, |rest| rest.starts_with('.'))
+        })
+}
+
+possum_node_type! {
+    #[derive(Debug, Default)]
+    struct Action {
+        name: String,
+        author: String,
+        description: String,
+        inputs: PossumMap<String, ActionInput>,
+        outputs: PossumMap<String, ActionOutput>,
+        runs: Runs,
+        branding: Branding,
+    }
+}
+
+possum_node_type! {
+    #[derive(Debug, Default)]
+    struct ActionInput {
+        description: String,
+        required: bool,
+        default: WorkflowInputDefault,
+        deprecation_message: String,
+    }
+}
+
+possum_node_type! {
+    #[derive(Debug, Default)]
+    struct ActionOutput {
+        description: String,
+        value: String,
+    }
+}
+
+possum_node_type! {
+    #[derive(Debug, Default)]
+    struct Branding {
+        icon: String,
+        color: String,
+    }
+}
+
+// which of these are used depends on `using`
+//   composite: steps
+//   node: main, pre, post, pre_if, post_if
+//   docker: image, args, entrypoint, pre_entrypoint, post_entrypoint, env
+possum_node_type! {
+    #[derive(Debug, Default)]
+    struct Runs {
+        using: Using,
+        steps: PossumSeq<Step>,
+        main: String,
+        pre: String,
+        post: String,
+        pre_if: String,
+        post_if: String,
+        image: String,
+        args: PossumSeq<String>,
+        entrypoint: String,
+        pre_entrypoint: String,
+        post_entrypoint: String,
+        env: PossumMap<String, String>,
+    }
+}
+
+impl Action {
+    // composite actions are the only ones with steps
+    pub fn steps(&self) -> impl Iterator<Item = &PossumNode<Step>> {
+        self.runs
+            .iter()
+            .filter_map(|runs| runs.value())
+            .filter_map(|runs| runs.steps.as_ref())
+            .filter_map(|steps| steps.value())
+            .flat_map(|steps| steps.iter())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum Using {
+    Composite,
+    Node12,
+    Node16,
+    Node20,
+    Node24,
+    Docker,
+}
+
+impl Using {
+    pub fn is_node(&self) -> bool {
+        matches!(self, Using::Node12 | Using::Node16 | Using::Node20 | Using::Node24)
+    }
+}
@@ -0,0 +1,408 @@
+use yaml_peg::repr::Repr;
+use yaml_peg::Node as YamlNode;
+
+use super::{Action, ActionInput, ActionOutput, Branding, Runs, Using};
+use crate::document::{Annotations, AsDocumentPointer};
+use crate::scavenge::ast::PossumNodeKind;
+use crate::scavenge::parsers::{
+    BoolParser, Builder, FlatMappableParser, MapParser, ObjectParser, SeqParser, StringMapParser,
+    StringParser,
+};
+use crate::scavenge::{Parser, UnexpectedKey};
+use crate::scavenge::yaml::Source;
+use crate::workflow::parser::{InputDefaultParser, StepParser};
+
+pub struct ActionParser<'a> {
+    annotations: &'a mut Annotations,
+}
+
+impl<'a> ActionParser<'a> {
+    pub fn new(a: &'a mut Annotations) -> ActionParser<'a> {
+        ActionParser { annotations: a }
+    }
+}
+
+impl<'a, R> Parser<R, Action> for ActionParser<'a>
+where
+    R: Repr,
+{
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<Action> {
+        ObjectParser::new(ActionBuilder::default, self.annotations).parse_node(root, source)
+    }
+}
+
+#[derive(Default)]
+struct ActionBuilder {
+    action: Action,
+}
+
+impl Into<Action> for ActionBuilder {
+    fn into(self) -> Action {
+        self.action
+    }
+}
+
+impl Builder<Action> for ActionBuilder {
+    fn build<'a, P, R>(
+        &mut self,
+        key: &str,
+        value: &YamlNode<R>,
+        pointer: &P,
+        source: Source,
+        annotations: &'a mut Annotations,
+    ) where
+        P: AsDocumentPointer + 'a,
+        R: Repr,
+    {
+        match key {
+            "name" => {
+                self.action.name = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "author" => {
+                self.action.author = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "description" => {
+                self.action.description = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "inputs" => {
+                self.action.inputs = Some(
+                    MapParser::new(StringParser, InputParser(annotations))
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
+                );
+            }
+            "outputs" => {
+                self.action.outputs = Some(
+                    MapParser::new(StringParser, OutputParser(annotations))
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
+                );
+            }
+            "runs" => {
+                self.action.runs = Some(
+                    ObjectParser::new(RunsBuilder::default, annotations)
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
+                );
+            }
+            "branding" => {
+                self.action.branding = Some(
+                    ObjectParser::new(BrandingBuilder::default, annotations)
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
+                );
+            }
+            s => annotations.add(UnexpectedKey::from(s).at(pointer)),
+        }
+    }
+}
+
+struct InputParser<'a>(&'a mut Annotations);
+
+impl<'a, R> Parser<R, ActionInput> for InputParser<'a>
+where
+    R: Repr,
+{
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<ActionInput> {
+        ObjectParser::new(InputBuilder::default, self.0).parse_node(root, source)
+    }
+}
+
+#[derive(Default)]
+struct InputBuilder {
+    input: ActionInput,
+}
+
+impl Into<ActionInput> for InputBuilder {
+    fn into(self) -> ActionInput {
+        self.input
+    }
+}
+
+impl Builder<ActionInput> for InputBuilder {
+    fn build<'a, P, R>(
+        &mut self,
+        key: &str,
+        value: &YamlNode<R>,
+        pointer: &P,
+        source: Source,
+        annotations: &'a mut Annotations,
+    ) where
+        P: AsDocumentPointer + 'a,
+        R: Repr,
+    {
+        match key {
+            "description" => {
+                self.input.description = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "required" => {
+                self.input.required = Some(BoolParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "default" => {
+                self.input.default = Some(InputDefaultParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "deprecationMessage" => {
+                self.input.deprecation_message = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            s => annotations.add(UnexpectedKey::from(s).at(pointer)),
+        }
+    }
+}
+
+struct OutputParser<'a>(&'a mut Annotations);
+
+impl<'a, R> Parser<R, ActionOutput> for OutputParser<'a>
+where
+    R: Repr,
+{
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<ActionOutput> {
+        ObjectParser::new(OutputBuilder::default, self.0).parse_node(root, source)
+    }
+}
+
+#[derive(Default)]
+struct OutputBuilder {
+    output: ActionOutput,
+}
+
+impl Into<ActionOutput> for OutputBuilder {
+    fn into(self) -> ActionOutput {
+        self.output
+    }
+}
+
+impl Builder<ActionOutput> for OutputBuilder {
+    fn build<'a, P, R>(
+        &mut self,
+        key: &str,
+        value: &YamlNode<R>,
+        pointer: &P,
+        source: Source,
+        annotations: &'a mut Annotations,
+    ) where
+        P: AsDocumentPointer + 'a,
+        R: Repr,
+    {
+        match key {
+            "description" => {
+                self.output.description = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            // only composite actions map outputs to step outputs
+            "value" => {
+                self.output.value = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            s => annotations.add(UnexpectedKey::from(s).at(pointer)),
+        }
+    }
+}
+
+#[derive(Default)]
+struct BrandingBuilder {
+    branding: Branding,
+}
+
+impl Into<Branding> for BrandingBuilder {
+    fn into(self) -> Branding {
+        self.branding
+    }
+}
+
+impl Builder<Branding> for BrandingBuilder {
+    fn build<'a, P, R>(
+        &mut self,
+        key: &str,
+        value: &YamlNode<R>,
+        pointer: &P,
+        source: Source,
+        annotations: &'a mut Annotations,
+    ) where
+        P: AsDocumentPointer + 'a,
+        R: Repr,
+    {
+        match key {
+            "icon" => {
+                self.branding.icon = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "color" => {
+                self.branding.color = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            s => annotations.add(UnexpectedKey::from(s).at(pointer)),
+        }
+    }
+}
+
+struct UsingParser;
+
+impl<R> Parser<R, Using> for UsingParser
+where
+    R: Repr,
+{
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<Using> {
+        StringParser
+            .flatten(|s| match s.to_lowercase().parse::<Using>() {
+                Ok(using) => PossumNodeKind::Value(using),
+                Err(_) => PossumNodeKind::Invalid(format!(
+                    "expected composite, docker or a node runtime but found {s}"
+                )),
+            })
+            .parse_node(root, source)
+    }
+}
+
+#[derive(Default)]
+struct RunsBuilder {
+    runs: Runs,
+}
+
+impl Into<Runs> for RunsBuilder {
+    fn into(self) -> Runs {
+        self.runs
+    }
+}
+
+impl Builder<Runs> for RunsBuilder {
+    fn build<'a, P, R>(
+        &mut self,
+        key: &str,
+        value: &YamlNode<R>,
+        pointer: &P,
+        source: Source,
+        annotations: &'a mut Annotations,
+    ) where
+        P: AsDocumentPointer + 'a,
+        R: Repr,
+    {
+        match key {
+            "using" => {
+                self.runs.using = Some(UsingParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "steps" => {
+                self.runs.steps = Some(
+                    SeqParser::new(StepParser::new(annotations))
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
+                );
+            }
+            "main" => {
+                self.runs.main = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "pre" => {
+                self.runs.pre = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "post" => {
+                self.runs.post = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "pre-if" => {
+                self.runs.pre_if = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "post-if" => {
+                self.runs.post_if = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "image" => {
+                self.runs.image = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "args" => {
+                self.runs.args = Some(SeqParser::new(StringParser).parse_node(value, source).at(&source.span(value)));
+            }
+            "entrypoint" => {
+                self.runs.entrypoint = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "pre-entrypoint" => {
+                self.runs.pre_entrypoint = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "post-entrypoint" => {
+                self.runs.post_entrypoint = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "env" => {
+                self.runs.env = Some(StringMapParser::new().parse_node(value, source).at(&source.span(value)));
+            }
+            s => annotations.add(UnexpectedKey::from(s).at(pointer)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ActionParser;
+    use crate::action::{Action, Using};
+    use crate::document::Annotations;
+    use crate::scavenge::ast::PossumNodeKind;
+    use crate::scavenge::parser::test_document;
+    use crate::scavenge::Parser;
+
+    fn parse(raw: &str) -> (Action, Vec<String>) {
+        let (root, source) = test_document(raw);
+        let mut annotations = Annotations::new();
+        let action = match ActionParser::new(&mut annotations).parse_node(&root, source) {
+            PossumNodeKind::Value(action) => action,
+            _ => panic!("not parsed as an action"),
+        };
+        (action, annotations.entries().map(|a| a.message().to_owned()).collect())
+    }
+
+    fn using(raw: &str) -> Result<Using, String> {
+        let (action, _) = parse(&format!("runs:\n  using: {raw}\n"));
+        let runs = action.runs.unwrap();
+        match runs.value().unwrap().using.as_ref().unwrap().kind() {
+            PossumNodeKind::Value(u) => Ok(*u),
+            PossumNodeKind::Invalid(msg) => Err(msg.clone()),
+            _ => Err(String::new()),
+        }
+    }
+
+    #[test]
+    fn runtimes() {
+        assert_eq!(using("node24"), Ok(Using::Node24));
+        assert_eq!(using("node20"), Ok(Using::Node20));
+        assert_eq!(using("Node16"), Ok(Using::Node16));
+        assert_eq!(using("composite"), Ok(Using::Composite));
+        assert_eq!(using("docker"), Ok(Using::Docker));
+        assert_eq!(
+            using("node8"),
+            Err("expected composite, docker or a node runtime but found node8".to_owned())
+        );
+
+        assert!(Using::Node24.is_node());
+        assert!(!Using::Composite.is_node());
+    }
+
+    #[test]
+    fn composite_actions() {
+        let raw = "name: greet\ninputs:\n  who:\n    required: true\n    deprecationMessage: use name\noutputs:\n  greeting:\n    value: ${{ steps.hi.outputs.text }}\nruns:\n  using: composite\n  steps:\n    - id: hi\n      run: echo hi\n      shell: bash\n";
+        let (action, annotations) = parse(raw);
+        assert!(annotations.is_empty());
+        assert_eq!(action.name.as_ref().unwrap().value().map(String::as_str), Some("greet"));
+
+        let inputs = action.inputs.as_ref().unwrap();
+        let (name, who) = inputs.value().unwrap().iter().next().unwrap();
+        let who = who.value().unwrap();
+        assert_eq!(name.value().map(String::as_str), Some("who"));
+        assert_eq!(who.required.as_ref().and_then(|r| r.value()), Some(&true));
+        assert_eq!(who.deprecation_message.as_ref().and_then(|m| m.value()).map(String::as_str), Some("use name"));
+
+        let outputs = action.outputs.as_ref().unwrap();
+        let (_, greeting) = outputs.value().unwrap().iter().next().unwrap();
+        let value = greeting.value().unwrap().value.as_ref().and_then(|v| v.value());
+        assert_eq!(value.map(String::as_str), Some("${{ steps.hi.outputs.text }}"));
+
+        assert_eq!(action.steps().count(), 1);
+    }
+
+    #[test]
+    fn docker_actions() {
+        let raw = "runs:\n  using: docker\n  image: Dockerfile\n  args: [--verbose]\n  env:\n    DEBUG: 1\n";
+        let (action, annotations) = parse(raw);
+        assert!(annotations.is_empty());
+        let runs = action.runs.as_ref().unwrap().value().unwrap();
+        assert_eq!(runs.image.as_ref().and_then(|i| i.value()).map(String::as_str), Some("Dockerfile"));
+        assert_eq!(runs.args.as_ref().and_then(|a| a.value()).map(|a| a.iter().count()), Some(1));
+        assert!(runs.env.is_some());
+        assert_eq!(action.steps().count(), 0);
+    }
+
+    #[test]
+    fn unexpected_keys_are_reported() {
+        let (_, annotations) = parse("runs:\n  using: node24\n  mian: index.js\nbrand:\n  icon: x\n");
+        assert_eq!(annotations, ["Unexpected key: mian", "Unexpected key: brand"]);
+    }
+}
@@ -1,22 +1,160 @@
 pub mod render;
+use crate::expression::eval::{Context, ContextError, StatusAssumption};
+use crate::project::baseline::BASELINE_FILE;
 use crate::project::ProjectRoot;
-use clap::Parser;
+use crate::workflow::on::EventKind;
+use crate::workflow::simulate::{GitRef, SyntheticEvent};
+use clap::{Args, Parser, Subcommand};
 use std::error::Error;
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Arguments {
-    #[arg(
-        short, long,
-        default_value_t = ProjectRoot::WorkingDirectory, 
-        value_parser = parse_project_dir
+    #[command(subcommand)]
+    pub command: Command,
+}
 
-    )]
-    pub directory: ProjectRoot,
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Lint the workflows in a project
+    #[command(visible_alias = "lint")]
+    Hiss(HissArguments),
+    /// Dig through what the workflows in a project do
+    #[command(visible_alias = "search")]
+    Rummage(RummageArguments),
+    /// List every rule possum can report
+    Rules,
+}
+
+#[derive(Args, Debug)]
+pub struct HissArguments {
+    #[command(flatten)]
+    pub project: ProjectArguments,
     #[arg(short, long, default_value = "false")]
     pub one_line: bool,
+    /// Only report findings that aren't recorded in this baseline
+    #[arg(long, value_name = "FILE")]
+    pub baseline: Option<PathBuf>,
+    /// Record every current finding to a baseline and exit
+    #[arg(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        default_missing_value = BASELINE_FILE
+    )]
+    pub write_baseline: Option<PathBuf>,
+    /// Apply the fixes that come with findings to the files
+    #[arg(long, conflicts_with = "diff")]
+    pub fix: bool,
+    /// Show the fixes --fix would apply as a unified diff
+    #[arg(long)]
+    pub diff: bool,
+    /// Also apply fixes that might change what a workflow does
+    #[arg(long)]
+    pub unsafe_fixes: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct RummageArguments {
+    #[command(subcommand)]
+    pub command: RummageCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RummageCommand {
+    /// Show the GITHUB_TOKEN permissions every job ends up with
+    Permissions(ProjectArguments),
+    /// Show the concurrency group of every workflow and job
+    Concurrency(ProjectArguments),
+    /// Show which workflows and jobs an event would queue
+    Simulate(SimulateArguments),
+}
+
+#[derive(Args, Debug)]
+pub struct SimulateArguments {
+    /// Event to simulate, eg. push or pull_request
+    #[arg(short, long, value_parser = parse_event)]
+    pub event: EventKind,
+    /// Activity type of the event, eg. opened or labeled
+    #[arg(long = "type", value_name = "TYPE")]
+    pub activity: Option<String>,
+    /// Branch that was pushed to, or the base branch of a pull request
+    #[arg(long, conflicts_with = "tag")]
+    pub branch: Option<String>,
+    /// Tag that was pushed
+    #[arg(long)]
+    pub tag: Option<String>,
+    /// A changed file, relative to the repository root
+    #[arg(long = "path", value_name = "PATH")]
+    pub paths: Vec<String>,
+    /// JSON object of contexts to evaluate conditions with, eg. {"github": {"actor": "me"}}
+    #[arg(long, value_name = "FILE")]
+    pub context: Option<PathBuf>,
+    #[command(flatten)]
+    pub project: ProjectArguments,
+}
+
+impl SimulateArguments {
+    pub fn event(&self) -> SyntheticEvent {
+        let git_ref = match (&self.branch, &self.tag) {
+            (Some(b), _) => Some(GitRef::Branch(b.clone())),
+            (None, Some(t)) => Some(GitRef::Tag(t.clone())),
+            (None, None) => None,
+        };
+
+        SyntheticEvent {
+            kind: self.event,
+            activity: self.activity.clone(),
+            git_ref,
+            paths: self.paths.clone(),
+        }
+    }
+
+    // the given contexts, assuming everything earlier in the run succeeds
+    pub fn context(&self) -> Result<Context, ContextError> {
+        let context = match self.context.as_ref() {
+            Some(path) => Context::load(path)?,
+            None => Context::unknown(),
+        };
+        Ok(context.with_status(StatusAssumption::Succeeded))
+    }
+}
+
+// which documents to load, shared by every command that reads a project
+#[derive(Args, Debug)]
+pub struct ProjectArguments {
+    /// Documents to read instead of discovering them
+    pub files: Vec<PathBuf>,
+    /// Read the documents from stdin, one path per line
+    #[arg(long, default_value = "false")]
+    pub stdin: bool,
+    /// Only read documents matching this glob, relative to the project root
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+    /// Never read documents matching this glob, relative to the project root
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+    /// Project directory, defaults to the repository containing the working directory
+    #[arg(short, long, value_parser = parse_project_dir)]
+    pub directory: Option<ProjectRoot>,
+    /// Use this config instead of searching for .possum.toml
+    #[arg(short, long)]
+    pub config: Option<PathBuf>,
+}
+
+impl ProjectArguments {
+    pub fn root(&self) -> ProjectRoot {
+        self.directory
+            .clone()
+            .unwrap_or(ProjectRoot::WorkingDirectory)
+    }
 }
 
 fn parse_project_dir(s: &str) -> Result<ProjectRoot, Box<dyn Error + Send + Sync + 'static>> {
     Ok(ProjectRoot::Explicit(s.into()))
 }
+
+fn parse_event(s: &str) -> Result<EventKind, Box<dyn Error + Send + Sync + 'static>> {
+    EventKind::fromstr(s).map_err(|e| e.to_string().into())
+}
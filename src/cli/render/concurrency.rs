@@ -0,0 +1,57 @@
+use super::write_table;
+use crate::project::{Project, ProjectEntry};
+use crate::scavenge::ast::PossumNodeKind;
+use crate::workflow::concurrency::groups;
+use std::fmt::Display;
+
+// every concurrency group in the project, so that clashes are easy to spot
+pub struct ConcurrencyRender<'a>(pub &'a Project);
+
+impl<'a> Display for ConcurrencyRender<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut rows = vec![vec![
+            "workflow".to_owned(),
+            "job".to_owned(),
+            "group".to_owned(),
+            "cancel-in-progress".to_owned(),
+        ]];
+
+        for entry in self.0.entries() {
+            let (source, wf) = match entry {
+                ProjectEntry::Workflow {
+                    source, workflow, ..
+                } => match workflow.value() {
+                    Some(wf) => (source, wf),
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            for (job, concurrency) in groups(wf) {
+                let c = match concurrency.value() {
+                    Some(c) => c,
+                    None => continue,
+                };
+
+                let cancel = match c.cancels_in_progress().map(|c| c.kind()) {
+                    Some(PossumNodeKind::Value(b)) => b.to_string(),
+                    Some(PossumNodeKind::Expr(e)) => e.to_owned(),
+                    _ => "false".to_owned(),
+                };
+
+                rows.push(vec![
+                    source.display().to_string(),
+                    job.and_then(|j| j.value()).cloned().unwrap_or_else(|| "*".to_owned()),
+                    c.group().unwrap_or_default().to_owned(),
+                    cancel,
+                ]);
+            }
+        }
+
+        if rows.len() == 1 {
+            return writeln!(f, "no concurrency groups");
+        }
+
+        write_table(f, &rows)
+    }
+}
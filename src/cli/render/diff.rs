@@ -0,0 +1,201 @@
+use std::fmt::Display;
+use std::path::Path;
+
+// lines of unchanged context around each change
+const CONTEXT: usize = 3;
+
+// A unified diff of a file before and after fixing it
+pub struct DiffRender<'a>(pub &'a Path, pub &'a [u8], pub &'a [u8]);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Same,
+    Removed,
+    Added,
+}
+
+impl<'a> Display for DiffRender<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let before: Vec<&[u8]> = self.1.split_inclusive(|c| *c == b'\n').collect();
+        let after: Vec<&[u8]> = self.2.split_inclusive(|c| *c == b'\n').collect();
+        let ops = diff(&before, &after);
+
+        if ops.iter().all(|(op, _)| *op == Op::Same) {
+            return Ok(());
+        }
+
+        writeln!(f, "--- a/{}", self.0.display())?;
+        writeln!(f, "+++ b/{}", self.0.display())?;
+
+        // where each op is in the old and new files
+        let mut positions = Vec::with_capacity(ops.len() + 1);
+        let (mut old, mut new) = (0, 0);
+        for (op, _) in ops.iter() {
+            positions.push((old, new));
+            match op {
+                Op::Same => {
+                    old += 1;
+                    new += 1;
+                }
+                Op::Removed => old += 1,
+                Op::Added => new += 1,
+            }
+        }
+        positions.push((old, new));
+
+        for (start, end) in hunks(&ops) {
+            // the old file has every line but those added, the new every line but those removed
+            let without = |skip: Op| ops[start..end].iter().filter(|(op, _)| *op != skip).count();
+            let (old_count, new_count) = (without(Op::Added), without(Op::Removed));
+            let (old_start, new_start) = positions[start];
+            // an empty range is numbered by the line before it
+            let number = |at: usize, count: usize| if count == 0 { at } else { at + 1 };
+
+            writeln!(
+                f,
+                "@@ -{},{} +{},{} @@",
+                number(old_start, old_count),
+                old_count,
+                number(new_start, new_count),
+                new_count
+            )?;
+
+            for (op, line) in ops[start..end].iter() {
+                let sign = match op {
+                    Op::Same => ' ',
+                    Op::Removed => '-',
+                    Op::Added => '+',
+                };
+                let text = String::from_utf8_lossy(line);
+                writeln!(f, "{sign}{}", text.trim_end_matches('\n'))?;
+                if !line.ends_with(b"\n") {
+                    writeln!(f, "\\ No newline at end of file")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// The lines of both files, in order, as a longest common subsequence. Fixes
+// only touch a few lines so the common start and end are skipped first.
+fn diff<'l>(before: &[&'l [u8]], after: &[&'l [u8]]) -> Vec<(Op, &'l [u8])> {
+    let prefix = before.iter().zip(after.iter()).take_while(|(a, b)| a == b).count();
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old = &before[prefix..before.len() - suffix];
+    let new = &after[prefix..after.len() - suffix];
+
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops: Vec<(Op, &[u8])> = before[..prefix].iter().map(|l| (Op::Same, *l)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((Op::Same, old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push((Op::Removed, old[i]));
+            i += 1;
+        } else {
+            ops.push((Op::Added, new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(before[before.len() - suffix..].iter().map(|l| (Op::Same, *l)));
+
+    ops
+}
+
+// ranges of ops to print, each change with its context, merged where the
+// context of one change reaches the next
+fn hunks(ops: &[(Op, &[u8])]) -> Vec<(usize, usize)> {
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+
+    for (idx, _) in ops.iter().enumerate().filter(|(_, (op, _))| *op != Op::Same) {
+        let start = idx.saturating_sub(CONTEXT);
+        let end = (idx + 1 + CONTEXT).min(ops.len());
+
+        match hunks.last_mut() {
+            Some(last) if last.1 >= start => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    hunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiffRender;
+    use std::path::Path;
+
+    fn render(before: &str, after: &str) -> String {
+        DiffRender(Path::new("ci.yml"), before.as_bytes(), after.as_bytes()).to_string()
+    }
+
+    #[test]
+    fn unchanged_files_render_nothing() {
+        assert_eq!(render("a\nb\n", "a\nb\n"), "");
+    }
+
+    #[test]
+    fn replaced_line_with_context() {
+        let before = "1\n2\n3\n4\n5\n6\n7\n8\n";
+        let after = "1\n2\n3\n4\nfive\n6\n7\n8\n";
+        assert_eq!(
+            render(before, after),
+            "--- a/ci.yml\n+++ b/ci.yml\n@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n"
+        );
+    }
+
+    #[test]
+    fn insert_only_hunk_counts() {
+        assert_eq!(
+            render("a\nb\n", "a\nnew\nb\n"),
+            "--- a/ci.yml\n+++ b/ci.yml\n@@ -1,2 +1,3 @@\n a\n+new\n b\n"
+        );
+
+        // an insert into an empty file is numbered by the line before it
+        assert_eq!(render("", "a\n"), "--- a/ci.yml\n+++ b/ci.yml\n@@ -0,0 +1,1 @@\n+a\n");
+    }
+
+    #[test]
+    fn distant_changes_get_their_own_hunks() {
+        let line = |n: usize| match n {
+            2 => "two\n".to_owned(),
+            19 => "nineteen\n".to_owned(),
+            n => format!("{n}\n"),
+        };
+        let before: String = (1..=20).map(|n| format!("{n}\n")).collect();
+        let after: String = (1..=20).map(line).collect();
+        let rendered = render(&before, &after);
+        assert_eq!(rendered.matches("@@ -").count(), 2);
+        assert!(rendered.contains("@@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n"));
+        assert!(rendered.contains("@@ -16,5 +16,5 @@\n 16\n 17\n 18\n-19\n+nineteen\n 20\n"));
+    }
+
+    #[test]
+    fn missing_newline_at_end_of_file() {
+        assert_eq!(
+            render("a\nb", "a\nb\n"),
+            "--- a/ci.yml\n+++ b/ci.yml\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+b\n"
+        );
+    }
+}
@@ -1,6 +1,33 @@
+mod concurrency;
 mod debug;
+mod diff;
 mod oneline;
+mod permissions;
+mod rules;
+mod simulate;
+pub use self::concurrency::ConcurrencyRender;
 pub use self::debug::DebugRender;
+pub use self::diff::DiffRender;
 pub use self::oneline::OneLineRender;
+pub use self::permissions::PermissionsRender;
+pub use self::rules::RulesRender;
+pub use self::simulate::SimulateRender;
 
+// left aligned columns, the first row being the header
+pub(super) fn write_table(f: &mut std::fmt::Formatter<'_>, rows: &[Vec<String>]) -> std::fmt::Result {
+    let columns = rows.first().map_or(0, Vec::len);
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| rows.iter().map(|r| r[c].len()).max().unwrap_or(0))
+        .collect();
 
+    for row in rows {
+        let cells: Vec<_> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect();
+        writeln!(f, "  {}", cells.join("  ").trim_end())?;
+    }
+
+    Ok(())
+}
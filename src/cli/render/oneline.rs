@@ -8,23 +8,25 @@ pub struct OneLineRender(pub Project);
 impl Display for OneLineRender {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for entry in self.0.entries() {
-            match entry {
-                ProjectEntry::ParseFailure(path, err) => {
-                    writeln!(f, "{}:: {}", path.display(), OneLineParseFailure(err))?;
-                }
-                ProjectEntry::Workflow {
-                    source,
-                    document,
-                    annotations,
-                    ..
-                } => {
-                    for a in annotations.entries() {
+            if let ProjectEntry::ParseFailure(path, err) = entry {
+                writeln!(f, "{}:: {}", path.display(), OneLineParseFailure(err))?;
+            } else if let Some((source, document, annotations)) = entry.document() {
+                for a in annotations.entries() {
+                    writeln!(
+                        f,
+                        "{}:{}: {}",
+                        source.display(),
+                        OneLineDocumentPosition(document.pos(&a).unwrap()),
+                        &a
+                    )?;
+
+                    for r in a.related_spans() {
                         writeln!(
                             f,
-                            "{}:{}: {}",
+                            "{}:{}: note: {}",
                             source.display(),
-                            OneLineDocumentPosition(document.pos(&a).unwrap()),
-                            &a
+                            OneLineDocumentPosition(document.pos(r.span()).unwrap()),
+                            r.message()
                         )?;
                     }
                 }
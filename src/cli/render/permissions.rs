@@ -0,0 +1,123 @@
+use super::write_table;
+use crate::project::{Project, ProjectEntry};
+use crate::workflow::permissions::{EffectivePermissions, GrantedBy};
+use crate::workflow::Scope;
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+// One table per workflow, a row for each job and a column for every scope
+// that any of its jobs is granted
+pub struct PermissionsRender<'a>(pub &'a Project);
+
+impl<'a> Display for PermissionsRender<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entry in self.0.entries() {
+            let (source, workflow) = match entry {
+                ProjectEntry::Workflow {
+                    source, workflow, ..
+                } => match workflow.value() {
+                    Some(wf) => (source, wf),
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            let jobs: Vec<_> = workflow
+                .jobs()
+                .filter_map(|(name, job)| Some((name.value()?.as_str(), job.value()?)))
+                .map(|(name, job)| (name, EffectivePermissions::of(workflow, job)))
+                .collect();
+
+            let scopes: BTreeSet<Scope> = jobs
+                .iter()
+                .flat_map(|(_, p)| p.granted().map(|(s, _)| s))
+                .collect();
+
+            let mut rows = vec![std::iter::once("job".to_owned())
+                .chain(std::iter::once("from".to_owned()))
+                .chain(scopes.iter().map(Scope::to_string))
+                .collect::<Vec<_>>()];
+
+            for (name, permissions) in jobs.iter() {
+                let from = match permissions {
+                    EffectivePermissions::Default => "default".to_owned(),
+                    EffectivePermissions::Granted { from, .. } => from.to_string(),
+                };
+
+                let grants = scopes.iter().map(|s| match permissions.grant(*s) {
+                    Some(g) => g.to_string(),
+                    None => "?".to_owned(),
+                });
+
+                rows.push(
+                    [name.to_string(), from]
+                        .into_iter()
+                        .chain(grants)
+                        .collect(),
+                );
+            }
+
+            writeln!(f, "{}", source.display())?;
+            write_table(f, &rows)?;
+
+            if jobs
+                .iter()
+                .any(|(_, p)| *p == EffectivePermissions::Default)
+            {
+                writeln!(f, "  default: no permissions set, the repository's default token is used")?;
+            }
+
+            if jobs.iter().any(|(_, p)| {
+                matches!(p, EffectivePermissions::Granted { from: GrantedBy::Job, .. })
+            }) {
+                writeln!(f, "  job: job permissions replace the workflow's, they aren't merged")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PermissionsRender;
+    use crate::document::{Annotations, Document, DocumentPointer};
+    use crate::project::{Project, ProjectEntry, ProjectRoot};
+    use crate::scavenge::ast::PossumNodeKind;
+    use crate::workflow::parser::test_workflow;
+    use std::path::PathBuf;
+
+    fn render(raw: &str) -> String {
+        let mut project = Project::new(ProjectRoot::Explicit(PathBuf::from(".")));
+        project.push(ProjectEntry::Workflow {
+            source: PathBuf::from("ci.yml"),
+            document: Document::new(raw),
+            annotations: Annotations::new(),
+            workflow: PossumNodeKind::Value(test_workflow(raw)).at(&DocumentPointer(0)),
+        });
+        PermissionsRender(&project).to_string()
+    }
+
+    #[test]
+    fn a_row_per_job_and_a_column_per_granted_scope() {
+        let raw = "permissions:\n  contents: read\njobs:\n  build: {}\n  release:\n    permissions:\n      contents: write\n      packages: write\n";
+        let out = render(raw);
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines[0], "ci.yml");
+        assert!(lines[1].split_whitespace().eq(["job", "from", "contents", "packages"]), "{out}");
+        assert!(lines.iter().any(|l| l.split_whitespace().eq(["build", "workflow", "read", "none"])), "{out}");
+        assert!(lines.iter().any(|l| l.split_whitespace().eq(["release", "job", "write", "write"])), "{out}");
+        assert!(out.contains("job permissions replace the workflow's"));
+        assert!(!out.contains("default token"));
+    }
+
+    #[test]
+    fn jobs_on_the_default_token_are_explained() {
+        let out = render("jobs:\n  build: {}\n");
+        assert!(out.lines().any(|l| l.split_whitespace().eq(["build", "default"])), "{out}");
+        assert!(out.contains("the repository's default token is used"));
+        assert!(!out.contains("job permissions replace"));
+    }
+}
@@ -0,0 +1,15 @@
+use crate::lint::rules::Rule;
+use std::fmt::Display;
+
+pub struct RulesRender<'a>(pub &'a [&'static Rule]);
+
+impl<'a> Display for RulesRender<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for rule in self.0 {
+            writeln!(f, "{} ({}): {}", rule.id(), rule.category(), rule.title())?;
+            writeln!(f, "    {}", rule.help())?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use crate::expression::eval::Context;
+use crate::expression::value::Value;
+use crate::expression::Condition;
+use crate::project::{Project, ProjectEntry};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::job::Step;
+use crate::workflow::simulate::{SyntheticEvent, Verdict};
+use crate::workflow::Workflow;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+pub struct SimulateRender<'a>(pub &'a Project, pub &'a SyntheticEvent, pub &'a Context);
+
+// whether a job or step runs, with the reason when it isn't certain to
+enum Outcome {
+    Runs,
+    Skipped(String),
+    Unknown(String),
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Runs => write!(f, "runs"),
+            Outcome::Skipped(why) => write!(f, "skipped, {why}"),
+            Outcome::Unknown(why) => write!(f, "might run, {why}"),
+        }
+    }
+}
+
+fn outcome(cond: Option<&PossumNode<String>>, context: &Context) -> Outcome {
+    let raw = match cond.and_then(|c| c.value()) {
+        Some(raw) => raw,
+        None => return Outcome::Runs,
+    };
+
+    let result = Condition::parse(raw)
+        .map_err(|e| e.to_string())
+        .and_then(|c| Ok((c.runs(context).map_err(|e| e.to_string())?, c.evaluate(context))));
+
+    match result {
+        Err(e) => Outcome::Unknown(format!("{raw} is invalid: {e}")),
+        Ok((Some(true), _)) => Outcome::Runs,
+        Ok((Some(false), _)) => Outcome::Skipped(format!("{raw} is false")),
+        Ok((None, Ok(Value::Unknown { what, .. }))) => {
+            Outcome::Unknown(format!("{raw} depends on {what}"))
+        }
+        Ok((None, _)) => Outcome::Unknown(format!("{raw} depends on the run")),
+    }
+}
+
+// jobs whose needs are skipped are skipped too, unless they check the status
+fn job_outcomes(wf: &Workflow, context: &Context) -> HashMap<String, Outcome> {
+    let mut outcomes: HashMap<String, Outcome> = wf
+        .jobs()
+        .filter_map(|(name, job)| Some((name.value()?.clone(), outcome(job.value()?.cond.as_ref(), context))))
+        .collect();
+
+    loop {
+        let mut changed = false;
+        for (name, job) in wf.jobs() {
+            let (name, job) = match (name.value(), job.value()) {
+                (Some(name), Some(job)) => (name, job),
+                _ => continue,
+            };
+
+            let checks_status = job
+                .cond
+                .as_ref()
+                .and_then(|c| Condition::parse(c.value()?).ok())
+                .map_or(false, |c| c.checks_status());
+            if checks_status || matches!(outcomes.get(name), Some(Outcome::Skipped(_))) {
+                continue;
+            }
+
+            let needs = job.needs.iter().filter_map(|n| n.value()).flat_map(|n| n.iter());
+            if let Some(skipped) = needs
+                .filter_map(|n| n.value())
+                .find(|n| matches!(outcomes.get(*n), Some(Outcome::Skipped(_))))
+            {
+                let reason = Outcome::Skipped(format!("it needs {skipped}"));
+                outcomes.insert(name.clone(), reason);
+                changed = true;
+            }
+        }
+
+        if !changed {
+            return outcomes;
+        }
+    }
+}
+
+fn step_label(step: &Step, index: usize) -> String {
+    [&step.name, &step.id, &step.uses]
+        .into_iter()
+        .find_map(|s| s.as_ref()?.value().cloned())
+        .unwrap_or_else(|| format!("{}", index + 1))
+}
+
+impl<'a> Display for SimulateRender<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut runs = Vec::new();
+        let mut skipped = Vec::new();
+
+        for entry in self.0.entries() {
+            let (source, wf) = match entry {
+                ProjectEntry::Workflow {
+                    source, workflow, ..
+                } => match workflow.value() {
+                    Some(wf) => (source, wf),
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            let verdict = match wf.on.as_ref().and_then(|on| on.value()) {
+                Some(on) => self.1.evaluate(on),
+                None => Verdict::Skipped("has no triggers".to_owned()),
+            };
+
+            match verdict {
+                Verdict::Runs(reasons) => runs.push((source, wf, reasons)),
+                Verdict::Skipped(reason) => skipped.push((source, reason)),
+            }
+        }
+
+        writeln!(f, "queued:")?;
+        if runs.is_empty() {
+            writeln!(f, "  nothing")?;
+        }
+        for (source, wf, reasons) in runs {
+            writeln!(f, "  {}", source.display())?;
+            for r in reasons {
+                writeln!(f, "    {r}")?;
+            }
+
+            let context = self.1.context(self.2);
+            let mut outcomes = job_outcomes(wf, &context);
+            for (name, job) in wf.jobs() {
+                let (name, job) = match (name.value(), job.value()) {
+                    (Some(name), Some(job)) => (name, job),
+                    _ => continue,
+                };
+                let job_outcome = outcomes.remove(name).unwrap_or(Outcome::Runs);
+                writeln!(f, "    job {name} {job_outcome}")?;
+
+                if matches!(job_outcome, Outcome::Skipped(_)) {
+                    continue;
+                }
+                for (i, step) in job.steps().filter_map(|s| s.value()).enumerate() {
+                    let step_outcome = outcome(step.cond.as_ref(), &context);
+                    writeln!(f, "      step {} {step_outcome}", step_label(step, i))?;
+                }
+            }
+        }
+
+        writeln!(f, "not queued:")?;
+        if skipped.is_empty() {
+            writeln!(f, "  nothing")?;
+        }
+        for (source, reason) in skipped {
+            writeln!(f, "  {}: {reason}", source.display())?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,376 @@
+use crate::action::catalogue::Catalogue;
+use crate::document::{AnnotationLevel, Annotations};
+use crate::lint::{closest, rules};
+use glob::Pattern;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub const CONFIG_FILE: &str = ".possum.toml";
+
+// .possum.toml
+//
+//   # extra action catalogue files or directories, see action/catalogue
+//   catalogue = ["ci/possum-actions"]
+//
+//   [rules]
+//   unexpected-key = "warn"
+//   empty-jobs = "off"
+//   missing-timeout = "on"    # the level the rule reports at, for overrides
+//
+//   [options]
+//   trusted-action-owners = ["actions", "my-org"]
+//   runner-labels = ["my-self-hosted-runner"]
+//   max-timeout-minutes = 60
+//
+//   [discovery]
+//   include = [".github/workflows/**/*.yml"]
+//   exclude = [".github/workflows/generated/**"]
+//
+//   [[overrides]]
+//   paths = [".github/workflows/legacy-*.yml"]
+//   rules = { missing-property = "off" }
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    rules: HashMap<String, RuleSetting>,
+    #[serde(default)]
+    options: Options,
+    #[serde(default)]
+    overrides: Vec<Override>,
+    #[serde(default)]
+    discovery: DiscoveryConfig,
+    #[serde(default)]
+    catalogue: Vec<PathBuf>,
+    // overrides are matched relative to where the config was found
+    #[serde(skip)]
+    base: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Override {
+    paths: Vec<String>,
+    #[serde(default)]
+    rules: HashMap<String, RuleSetting>,
+    #[serde(default)]
+    options: Options,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct DiscoveryConfig {
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleSetting {
+    Off,
+    On,
+    Level(AnnotationLevel),
+}
+
+impl FromStr for RuleSetting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(RuleSetting::Off),
+            "on" => Ok(RuleSetting::On),
+            l => AnnotationLevel::from_str(l)
+                .map(RuleSetting::Level)
+                .map_err(|_| format!("expected off, on, info, warn, error or fatal but found {s}")),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RuleSetting {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        RuleSetting::from_str(&raw).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Options {
+    trusted_action_owners: Option<Vec<String>>,
+    runner_labels: Option<Vec<String>>,
+    max_timeout_minutes: Option<f64>,
+}
+
+impl Options {
+    // actions published by these owners are trusted to not be pinned
+    pub fn trusted_action_owners(&self) -> &[String] {
+        self.trusted_action_owners.as_deref().unwrap_or_default()
+    }
+
+    // self hosted runner labels in addition to GitHub's hosted runners
+    pub fn runner_labels(&self) -> &[String] {
+        self.runner_labels.as_deref().unwrap_or_default()
+    }
+
+    pub fn max_timeout_minutes(&self) -> Option<f64> {
+        self.max_timeout_minutes
+    }
+
+    fn merge(&self, over: &Options) -> Options {
+        Options {
+            trusted_action_owners: over
+                .trusted_action_owners
+                .clone()
+                .or_else(|| self.trusted_action_owners.clone()),
+            runner_labels: over
+                .runner_labels
+                .clone()
+                .or_else(|| self.runner_labels.clone()),
+            max_timeout_minutes: over.max_timeout_minutes.or(self.max_timeout_minutes),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ConfigError {
+    path: PathBuf,
+    reason: String,
+}
+
+impl ConfigError {
+    pub(crate) fn new(p: impl Into<PathBuf>, reason: impl ToString) -> ConfigError {
+        ConfigError {
+            path: p.into(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.reason)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    pub fn load(path: impl Into<PathBuf>) -> Result<Config, ConfigError> {
+        let path = path.into();
+        let raw = std::fs::read_to_string(&path).map_err(|e| ConfigError::new(&path, e))?;
+        let mut config = Config::parse(&raw).map_err(|e| ConfigError::new(&path, e))?;
+
+        config.base = path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        Ok(config)
+    }
+
+    fn parse(raw: &str) -> Result<Config, String> {
+        let config: Config = toml::from_str(raw).map_err(|e| e.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // looks for .possum.toml in dir and then each of its parents
+    pub fn discover(dir: impl AsRef<Path>) -> Result<Config, ConfigError> {
+        let dir = dir.as_ref();
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+
+        match dir
+            .ancestors()
+            .map(|d| d.join(CONFIG_FILE))
+            .find(|candidate| candidate.is_file())
+        {
+            Some(found) => Config::load(found),
+            None => Ok(Config::default()),
+        }
+    }
+
+    pub fn discovery(&self) -> &DiscoveryConfig {
+        &self.discovery
+    }
+
+    // the bundled catalogue followed by the configured ones
+    pub fn catalogue(&self) -> Result<Catalogue, ConfigError> {
+        let mut catalogue = Catalogue::bundled();
+        for path in self.catalogue.iter() {
+            catalogue.extend(Catalogue::load(self.base.join(path))?);
+        }
+        Ok(catalogue)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let rule_names = self
+            .rules
+            .keys()
+            .chain(self.overrides.iter().flat_map(|o| o.rules.keys()));
+
+        for name in rule_names {
+            if rules::find(name).is_none() {
+                let known = rules::RULES.iter().map(|r| r.name());
+                return Err(match closest(name.trim_start_matches("possum::"), known) {
+                    Some(close) => format!("unknown rule {name}, did you mean {close}?"),
+                    None => format!("unknown rule {name}"),
+                });
+            }
+        }
+
+        for o in self.overrides.iter() {
+            for p in o.paths.iter() {
+                Pattern::new(p).map_err(|e| format!("bad override path {p}: {e}"))?;
+            }
+        }
+
+        for p in self.discovery.include.iter().chain(self.discovery.exclude.iter()) {
+            Pattern::new(p).map_err(|e| format!("bad discovery pattern {p}: {e}"))?;
+        }
+
+        Ok(())
+    }
+
+    // resolves every override that applies to this document
+    pub fn settings_for(&self, path: impl AsRef<Path>) -> Settings {
+        let path = path.as_ref();
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let base = self.base.canonicalize().unwrap_or_else(|_| self.base.clone());
+        let relative = path.strip_prefix(&base).unwrap_or(&path);
+
+        let mut settings = Settings {
+            rules: HashMap::new(),
+            options: self.options.clone(),
+        };
+        settings.set(&self.rules);
+
+        for o in self.overrides.iter().filter(|o| {
+            o.paths
+                .iter()
+                .filter_map(|p| Pattern::new(p).ok())
+                .any(|p| p.matches_path(relative))
+        }) {
+            settings.set(&o.rules);
+            settings.options = settings.options.merge(&o.options);
+        }
+
+        settings
+    }
+}
+
+// the configuration that applies to a single document
+#[derive(Debug, Default)]
+pub struct Settings {
+    // by rule id, so that a rule named with and without possum:: is one rule
+    rules: HashMap<&'static str, RuleSetting>,
+    options: Options,
+}
+
+impl Settings {
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    // later settings of a rule replace earlier ones, names that aren't rules
+    // were refused when the config was loaded
+    fn set(&mut self, rules: &HashMap<String, RuleSetting>) {
+        for (name, setting) in rules.iter() {
+            if let Some(rule) = rules::find(name) {
+                self.rules.insert(rule.id(), *setting);
+            }
+        }
+    }
+
+    pub fn rule(&self, rule: &rules::Rule) -> RuleSetting {
+        self.rules.get(rule.id()).copied().unwrap_or(RuleSetting::On)
+    }
+
+    // drops disabled rules and changes the level of the rest
+    pub fn apply(&self, annotations: &mut Annotations) {
+        if self.rules.is_empty() {
+            return;
+        }
+
+        annotations.retain(|a| self.rule(a.rule()) != RuleSetting::Off);
+
+        for a in annotations.entries_mut() {
+            match self.rule(a.rule()) {
+                RuleSetting::Level(level) => a.set_level(level),
+                // the level the rule reported at, undoing an earlier level
+                RuleSetting::On | RuleSetting::Off => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, RuleSetting};
+    use crate::document::{Annotation, AnnotationLevel, Annotations, DocumentPointer};
+    use crate::lint::rules;
+
+    // the level of a missing timeout and an empty jobs finding in path, None
+    // for those that are turned off
+    fn levels(config: &Config, path: &str) -> Vec<Option<AnnotationLevel>> {
+        let mut annotations = Annotations::new();
+        for rule in [&rules::MISSING_TIMEOUT, &rules::EMPTY_JOBS] {
+            annotations.add(Annotation::warn(rule, &DocumentPointer(0), &"found"));
+        }
+        config.settings_for(path).apply(&mut annotations);
+
+        [&rules::MISSING_TIMEOUT, &rules::EMPTY_JOBS]
+            .iter()
+            .map(|r| annotations.entries().find(|a| a.rule().id() == r.id()).map(|a| *a.level()))
+            .collect()
+    }
+
+    #[test]
+    fn bad_configs_are_refused() {
+        let err = Config::parse("[rules]\nmissing-timeout = \"loud\"\n").unwrap_err();
+        assert!(err.contains("expected off, on, info, warn, error or fatal but found loud"), "{err}");
+
+        assert!(Config::parse("[options]\nmax-timeout = 5\n").is_err());
+        assert!(Config::parse("rules = 5\n").is_err());
+
+        let err = Config::parse("[[overrides]]\npaths = [\"[\"]\n").unwrap_err();
+        assert!(err.starts_with("bad override path ["), "{err}");
+    }
+
+    #[test]
+    fn unknown_rules_suggest_the_closest() {
+        let err = Config::parse("[rules]\nmissing-timout = \"off\"\n").unwrap_err();
+        assert_eq!(err, "unknown rule missing-timout, did you mean missing-timeout?");
+
+        let err = Config::parse("[[overrides]]\npaths = [\"*\"]\nrules = { \"possum::empty-job\" = \"off\" }\n").unwrap_err();
+        assert_eq!(err, "unknown rule possum::empty-job, did you mean empty-jobs?");
+
+        let err = Config::parse("[rules]\nsomething-else = \"off\"\n").unwrap_err();
+        assert_eq!(err, "unknown rule something-else");
+    }
+
+    #[test]
+    fn settings_turn_rules_off_and_change_their_level() {
+        let config = Config::default();
+        assert_eq!(levels(&config, "ci.yml"), [Some(AnnotationLevel::Warn), Some(AnnotationLevel::Warn)]);
+
+        let config = Config::parse("[rules]\nmissing-timeout = \"off\"\n\"possum::empty-jobs\" = \"error\"\n").unwrap();
+        assert_eq!(levels(&config, "ci.yml"), [None, Some(AnnotationLevel::Error)]);
+    }
+
+    #[test]
+    fn on_restores_the_rules_own_level() {
+        let raw = "[rules]\nmissing-timeout = \"off\"\nempty-jobs = \"error\"\n\n[[overrides]]\npaths = [\"legacy-*.yml\"]\nrules = { \"possum::missing-timeout\" = \"on\", empty-jobs = \"on\" }\n";
+        let config = Config::parse(raw).unwrap();
+        assert_eq!(levels(&config, "ci.yml"), [None, Some(AnnotationLevel::Error)]);
+        assert_eq!(levels(&config, "legacy-build.yml"), [Some(AnnotationLevel::Warn), Some(AnnotationLevel::Warn)]);
+
+        assert_eq!(config.settings_for("legacy-build.yml").rule(&rules::MISSING_TIMEOUT), RuleSetting::On);
+    }
+}
@@ -1,4 +1,6 @@
-use super::document::{AsDocumentPointer, DocumentPointer};
+use super::document::{AsDocumentPointer, DocumentPointer, DocumentSpan};
+use super::fix::{Applicability, Edit};
+use crate::lint::rules::Rule;
 use std::convert::AsRef;
 use std::fmt::Display;
 use strum::Display;
@@ -27,9 +29,30 @@ impl Annotations {
     pub fn entries(&self) -> std::slice::Iter<Annotation> {
         self.0.iter()
     }
+
+    pub fn entries_mut(&mut self) -> std::slice::IterMut<Annotation> {
+        self.0.iter_mut()
+    }
+
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&Annotation) -> bool,
+    {
+        self.0.retain(f)
+    }
+}
+
+impl Annotatable for Annotations {
+    fn annotate<A>(&mut self, annotation: A)
+    where
+        A: Into<Annotation>,
+    {
+        self.add(annotation)
+    }
 }
 
-#[derive(Debug, Display)]
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, strum::EnumString)]
+#[strum(ascii_case_insensitive)]
 pub enum AnnotationLevel {
     Info,
     Warn,
@@ -38,58 +61,173 @@ pub enum AnnotationLevel {
 }
 
 #[derive(Debug)]
-pub struct Annotation(AnnotationLevel, String, DocumentPointer);
+pub struct Annotation {
+    rule: &'static Rule,
+    level: AnnotationLevel,
+    msg: String,
+    span: DocumentSpan,
+    related: Vec<RelatedSpan>,
+    // the fix, applied all together or not at all
+    edits: Vec<Edit>,
+    applicability: Applicability,
+}
+
+// a secondary location that helps explain an annotation
+// e.g. where the job referenced by `needs` is actually defined
+#[derive(Debug)]
+pub struct RelatedSpan {
+    span: DocumentSpan,
+    msg: String,
+}
+
+impl RelatedSpan {
+    pub fn span(&self) -> DocumentSpan {
+        self.span
+    }
+
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+}
 
 impl Display for Annotation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}: {}", self.0, self.1)
+        write!(f, "{}: {} [{}]", self.level, self.msg, self.rule)
     }
 }
 
 impl AsRef<DocumentPointer> for Annotation {
     fn as_ref(&self) -> &DocumentPointer {
-        &self.2
+        self.span.as_ref()
+    }
+}
+
+impl AsDocumentPointer for Annotation {
+    fn as_document_pointer(&self) -> DocumentPointer {
+        self.span.start()
+    }
+
+    fn as_document_span(&self) -> DocumentSpan {
+        self.span
+    }
+}
+
+impl AsRef<DocumentSpan> for Annotation {
+    fn as_ref(&self) -> &DocumentSpan {
+        &self.span
     }
 }
 
 impl Annotation {
-    fn from_parts<P, I>(level: AnnotationLevel, msg: &I, pointer: &P) -> Annotation
+    fn from_parts<P, I>(
+        rule: &'static Rule,
+        level: AnnotationLevel,
+        msg: &I,
+        pointer: &P,
+    ) -> Annotation
+    where
+        P: AsDocumentPointer,
+        I: Display,
+    {
+        Annotation {
+            rule,
+            level,
+            msg: msg.to_string(),
+            span: pointer.as_document_span(),
+            related: Vec::new(),
+            edits: Vec::new(),
+            applicability: Applicability::Safe,
+        }
+    }
+
+    pub fn related<P, I>(mut self, pointer: &P, msg: &I) -> Annotation
     where
         P: AsDocumentPointer,
         I: Display,
     {
-        Annotation(level, msg.to_string(), pointer.as_document_pointer())
+        self.related.push(RelatedSpan {
+            span: pointer.as_document_span(),
+            msg: msg.to_string(),
+        });
+        self
+    }
+
+    pub fn edit(mut self, edit: Edit) -> Annotation {
+        self.edits.push(edit);
+        self
+    }
+
+    // the fix might change what the workflow does
+    pub fn unsafe_fix(mut self) -> Annotation {
+        self.applicability = Applicability::Unsafe;
+        self
+    }
+
+    pub fn edits(&self) -> &[Edit] {
+        &self.edits
+    }
+
+    // the edits that may be applied, none for an unsafe fix unless they're allowed
+    pub fn fix(&self, unsafe_fixes: bool) -> &[Edit] {
+        match self.applicability {
+            Applicability::Unsafe if !unsafe_fixes => &[],
+            _ => &self.edits,
+        }
+    }
+
+    pub fn rule(&self) -> &'static Rule {
+        self.rule
+    }
+
+    pub fn level(&self) -> &AnnotationLevel {
+        &self.level
+    }
+
+    pub fn set_level(&mut self, level: AnnotationLevel) {
+        self.level = level;
+    }
+
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+
+    pub fn span(&self) -> DocumentSpan {
+        self.span
+    }
+
+    pub fn related_spans(&self) -> std::slice::Iter<RelatedSpan> {
+        self.related.iter()
     }
 
-    pub fn info<P, I>(pointer: &P, msg: &I) -> Annotation
+    pub fn info<P, I>(rule: &'static Rule, pointer: &P, msg: &I) -> Annotation
     where
         P: AsDocumentPointer,
         I: Display,
     {
-        Annotation::from_parts(AnnotationLevel::Info, msg, pointer)
+        Annotation::from_parts(rule, AnnotationLevel::Info, msg, pointer)
     }
 
-    pub fn warn<P, I>(pointer: &P, msg: &I) -> Annotation
+    pub fn warn<P, I>(rule: &'static Rule, pointer: &P, msg: &I) -> Annotation
     where
         P: AsDocumentPointer,
         I: Display,
     {
-        Annotation::from_parts(AnnotationLevel::Warn, msg, pointer)
+        Annotation::from_parts(rule, AnnotationLevel::Warn, msg, pointer)
     }
 
-    pub fn error<P, I>(pointer: &P, msg: &I) -> Annotation
+    pub fn error<P, I>(rule: &'static Rule, pointer: &P, msg: &I) -> Annotation
     where
         P: AsDocumentPointer,
         I: Display,
     {
-        Annotation::from_parts(AnnotationLevel::Error, msg, pointer)
+        Annotation::from_parts(rule, AnnotationLevel::Error, msg, pointer)
     }
 
-    pub fn fatal<P, I>(pointer: &P, msg: &I) -> Annotation
+    pub fn fatal<P, I>(rule: &'static Rule, pointer: &P, msg: &I) -> Annotation
     where
         P: AsDocumentPointer,
         I: Display,
     {
-        Annotation::from_parts(AnnotationLevel::Fatal, msg, pointer)
+        Annotation::from_parts(rule, AnnotationLevel::Fatal, msg, pointer)
     }
 }
@@ -1,7 +1,8 @@
 use std::convert::AsRef;
 use std::convert::From;
+use super::paths::NodePaths;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct DocumentPointer(pub(crate) usize);
 
 impl AsDocumentPointer for DocumentPointer {
@@ -10,8 +11,20 @@ impl AsDocumentPointer for DocumentPointer {
     }
 }
 
+impl AsRef<DocumentPointer> for DocumentPointer {
+    fn as_ref(&self) -> &DocumentPointer {
+        self
+    }
+}
+
 pub trait AsDocumentPointer {
     fn as_document_pointer(&self) -> DocumentPointer;
+
+    // most things only know where they start, those that know where they end
+    // as well should override this
+    fn as_document_span(&self) -> DocumentSpan {
+        DocumentSpan::point(self.as_document_pointer())
+    }
 }
 
 impl From<u64> for DocumentPointer {
@@ -23,6 +36,74 @@ impl From<u64> for DocumentPointer {
     }
 }
 
+// start..end, end is exclusive
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DocumentSpan {
+    pub(crate) start: DocumentPointer,
+    pub(crate) end: DocumentPointer,
+}
+
+impl DocumentSpan {
+    pub fn new(start: DocumentPointer, end: DocumentPointer) -> DocumentSpan {
+        DocumentSpan {
+            start,
+            end: end.max(start),
+        }
+    }
+
+    pub fn point(at: DocumentPointer) -> DocumentSpan {
+        DocumentSpan::new(at, at)
+    }
+
+    pub fn start(&self) -> DocumentPointer {
+        self.start
+    }
+
+    pub fn end(&self) -> DocumentPointer {
+        self.end
+    }
+
+    pub fn len(&self) -> usize {
+        self.end.0 - self.start.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, p: &impl AsDocumentPointer) -> bool {
+        let p = p.as_document_pointer();
+        self.start <= p && (p < self.end || p == self.start)
+    }
+
+    // the smallest span that contains both self and other
+    pub fn cover(&self, other: &DocumentSpan) -> DocumentSpan {
+        DocumentSpan::new(self.start.min(other.start), self.end.max(other.end))
+    }
+}
+
+impl AsDocumentPointer for DocumentSpan {
+    fn as_document_pointer(&self) -> DocumentPointer {
+        self.start
+    }
+
+    fn as_document_span(&self) -> DocumentSpan {
+        self.clone()
+    }
+}
+
+impl AsRef<DocumentPointer> for DocumentSpan {
+    fn as_ref(&self) -> &DocumentPointer {
+        &self.start
+    }
+}
+
+impl AsRef<DocumentSpan> for DocumentSpan {
+    fn as_ref(&self) -> &DocumentSpan {
+        self
+    }
+}
+
 pub struct DocumentPosition(usize, usize);
 
 impl DocumentPosition {
@@ -43,9 +124,10 @@ pub enum DocumentError {
 }
 
 pub struct Document {
-    _raw: Vec<u8>,
+    raw: Vec<u8>,
     len: usize,
     lines: Vec<usize>,
+    paths: NodePaths,
 }
 
 impl std::fmt::Debug for Document {
@@ -59,10 +141,36 @@ impl Document {
     where
         D: Into<Vec<u8>>,
     {
-        let _raw = raw.into();
-        let len = _raw.len();
-        let lines = _raw.split(|c| *c == b'\n').map(|l| l.len() + 1).collect();
-        Document { _raw, len, lines }
+        let raw = raw.into();
+        let len = raw.len();
+        let lines = raw.split(|c| *c == b'\n').map(|l| l.len() + 1).collect();
+        Document {
+            raw,
+            len,
+            lines,
+            paths: NodePaths::new(),
+        }
+    }
+
+    pub fn with_paths(mut self, paths: NodePaths) -> Document {
+        self.paths = paths;
+        self
+    }
+
+    pub fn path_of(&self, p: &impl AsDocumentPointer) -> Option<&str> {
+        self.paths.path_of(p)
+    }
+
+    pub fn key_of(&self, path: &str) -> Option<DocumentSpan> {
+        self.paths.key_of(path)
+    }
+
+    pub fn node_at(&self, p: &impl AsDocumentPointer) -> Option<DocumentSpan> {
+        self.paths.node_at(p)
+    }
+
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
     }
 
     pub fn pos<P>(&self, p: P) -> Result<DocumentPosition, DocumentError>
@@ -81,4 +189,107 @@ impl Document {
 
         return Err(DocumentError::OutOfBounds);
     }
+
+    // (1 indexed line number, where the line starts, the line without its newline)
+    pub fn lines(&self) -> impl Iterator<Item = (usize, DocumentPointer, &[u8])> {
+        let mut start = 0;
+        self.raw
+            .split(|c| *c == b'\n')
+            .enumerate()
+            .map(move |(idx, line)| {
+                let at = DocumentPointer(start);
+                start += line.len() + 1;
+                (idx + 1, at, line)
+            })
+    }
+
+    // Where each line of a scalar's value starts in the document, searching
+    // forward from the scalar itself. Block scalars lose their indentation so
+    // each line is found by its content. None for lines that can't be found,
+    // like those of folded scalars.
+    pub fn locate_lines(&self, from: DocumentPointer, text: &str) -> Vec<Option<DocumentPointer>> {
+        let mut doc_lines = self.lines().skip_while(|(_, at, line)| at.0 + line.len() < from.0);
+        let mut found = Vec::new();
+
+        for wanted in text.lines() {
+            let wanted = wanted.trim_end();
+            let mut located = None;
+
+            for (_, at, line) in doc_lines.by_ref() {
+                let line = String::from_utf8_lossy(line);
+                // the first line can start partway through, after the key
+                let start = from.0.saturating_sub(at.0);
+                let rest = line.get(start..).unwrap_or_default();
+
+                if wanted.is_empty() && rest.trim().is_empty() {
+                    located = Some(DocumentPointer(at.0 + start));
+                    break;
+                }
+                if !wanted.is_empty() {
+                    if let Some(col) = rest.find(wanted) {
+                        located = Some(DocumentPointer(at.0 + start + col));
+                        break;
+                    }
+                }
+            }
+
+            if located.is_none() {
+                // nothing after this can be trusted either
+                found.resize(text.lines().count(), None);
+                return found;
+            }
+            found.push(located);
+        }
+
+        found
+    }
+
+    pub fn slice<S>(&self, s: S) -> Result<&[u8], DocumentError>
+    where
+        S: AsRef<DocumentSpan>,
+    {
+        let span = s.as_ref();
+        self.raw
+            .get(span.start.0..span.end.0)
+            .ok_or(DocumentError::OutOfBounds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Document, DocumentPointer};
+
+    fn located(raw: &str, from: &str, text: &str) -> Vec<Option<usize>> {
+        let doc = Document::new(raw);
+        let from = DocumentPointer(raw.find(from).unwrap());
+        doc.locate_lines(from, text)
+            .into_iter()
+            .map(|p| p.map(|p| p.0))
+            .collect()
+    }
+
+    #[test]
+    fn block_scalar_lines_are_found_under_their_key() {
+        let raw = "run: echo nope\nsteps:\n  - run: |\n      echo one\n\n        echo two\n    shell: bash\n";
+        assert_eq!(
+            located(raw, "|", "echo one\n\n  echo two\n"),
+            [
+                raw.find("echo one"),
+                Some(raw.find("\n\n").unwrap() + 1),
+                Some(raw.find("  echo two").unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn single_line_scalars_start_after_their_key() {
+        let raw = "- run: run echo\n";
+        assert_eq!(located(raw, "run echo", "run echo"), [Some(7)]);
+    }
+
+    #[test]
+    fn lines_after_one_that_cant_be_found_are_unknown() {
+        let raw = "run: >\n  folded\n  lines\n";
+        assert_eq!(located(raw, ">", "folded lines\nmore\n"), [None, None]);
+    }
 }
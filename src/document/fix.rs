@@ -0,0 +1,149 @@
+use super::document::{AsDocumentPointer, Document, DocumentSpan};
+
+// A machine applicable change to a document, the bytes in span are replaced
+// by the replacement. Inserts are edits of an empty span.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    span: DocumentSpan,
+    replacement: String,
+}
+
+// Whether a fix can be applied without anyone looking at it. A safe fix
+// leaves what the workflow does alone, an unsafe one might not and is only
+// applied when asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applicability {
+    Safe,
+    Unsafe,
+}
+
+impl Edit {
+    pub fn replace(at: &impl AsDocumentPointer, replacement: impl Into<String>) -> Edit {
+        Edit {
+            span: at.as_document_span(),
+            replacement: replacement.into(),
+        }
+    }
+
+    pub fn insert(at: &impl AsDocumentPointer, text: impl Into<String>) -> Edit {
+        Edit {
+            span: DocumentSpan::point(at.as_document_pointer()),
+            replacement: text.into(),
+        }
+    }
+
+    pub fn span(&self) -> DocumentSpan {
+        self.span
+    }
+
+    pub fn replacement(&self) -> &str {
+        &self.replacement
+    }
+
+    // two inserts at the same place would come out in whichever order they
+    // were applied, so they conflict too
+    fn conflicts(&self, other: &Edit) -> bool {
+        let (a, b) = (self.span, other.span);
+        (a.start().0 < b.end().0 && b.start().0 < a.end().0) || a.start().0 == b.start().0
+    }
+}
+
+impl Document {
+    // The document with the fixes applied, each fix being the edits of one
+    // annotation. Fixes that conflict with one already taken are left for
+    // the next run, as are any outside of the document. Everything that
+    // isn't edited, comments and formatting included, is kept byte for byte.
+    pub fn apply<'a>(&self, fixes: impl IntoIterator<Item = &'a [Edit]>) -> (Vec<u8>, usize) {
+        let mut taken: Vec<&Edit> = Vec::new();
+        let mut applied = 0;
+
+        for fix in fixes {
+            let fits = fix.iter().all(|e| e.span.end().0 <= self.raw().len())
+                && !fix
+                    .iter()
+                    .enumerate()
+                    .any(|(i, e)| fix[..i].iter().chain(taken.iter().copied()).any(|t| t.conflicts(e)));
+            if fits && !fix.is_empty() {
+                taken.extend(fix.iter());
+                applied += 1;
+            }
+        }
+
+        taken.sort_by_key(|e| e.span.start().0);
+
+        let raw = self.raw();
+        let mut out = Vec::with_capacity(raw.len());
+        let mut at = 0;
+        for edit in taken {
+            out.extend_from_slice(&raw[at..edit.span.start().0]);
+            out.extend_from_slice(edit.replacement.as_bytes());
+            at = edit.span.end().0;
+        }
+        out.extend_from_slice(&raw[at..]);
+
+        (out, applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Edit;
+    use crate::document::{Document, DocumentPointer, DocumentSpan};
+
+    fn span(raw: &str, text: &str) -> DocumentSpan {
+        let start = raw.find(text).unwrap();
+        DocumentSpan::new(DocumentPointer(start), DocumentPointer(start + text.len()))
+    }
+
+    fn apply(raw: &str, fixes: &[Vec<Edit>]) -> (String, usize) {
+        let (out, applied) = Document::new(raw).apply(fixes.iter().map(Vec::as_slice));
+        (String::from_utf8(out).unwrap(), applied)
+    }
+
+    #[test]
+    fn overlapping_fixes_are_left_for_the_next_run() {
+        let raw = "on: push\nname: ci\n";
+        let fixes = [
+            vec![Edit::replace(&span(raw, "push"), "pull_request")],
+            vec![Edit::replace(&span(raw, "sh\nna"), "x")],
+            vec![Edit::replace(&span(raw, "ci"), "build")],
+        ];
+        assert_eq!(apply(raw, &fixes), ("on: pull_request\nname: build\n".to_owned(), 2));
+    }
+
+    #[test]
+    fn a_fix_is_taken_whole_or_not_at_all() {
+        let raw = "a: 1\nb: 2\n";
+        let fixes = [
+            vec![Edit::replace(&span(raw, "1"), "one")],
+            vec![Edit::replace(&span(raw, "2"), "two"), Edit::replace(&span(raw, "1"), "uno")],
+        ];
+        assert_eq!(apply(raw, &fixes), ("a: one\nb: 2\n".to_owned(), 1));
+    }
+
+    #[test]
+    fn inserts_at_the_same_place_conflict() {
+        let raw = "a: 1\n";
+        let at = DocumentPointer(0);
+        let fixes = [vec![Edit::insert(&at, "# one\n")], vec![Edit::insert(&at, "# two\n")]];
+        assert_eq!(apply(raw, &fixes), ("# one\na: 1\n".to_owned(), 1));
+    }
+
+    #[test]
+    fn fixes_at_the_end_of_the_file() {
+        let raw = "a: 1";
+        let end = DocumentPointer(raw.len());
+        assert_eq!(apply(raw, &[vec![Edit::insert(&end, "\nb: 2\n")]]), ("a: 1\nb: 2\n".to_owned(), 1));
+
+        // anything past the end is skipped
+        let past = DocumentPointer(raw.len() + 1);
+        assert_eq!(apply(raw, &[vec![Edit::insert(&past, "x")]]), ("a: 1".to_owned(), 0));
+    }
+
+    #[test]
+    fn no_trailing_newline_is_kept() {
+        let raw = "a: 1\nb: 2";
+        let fixes = [vec![Edit::replace(&span(raw, "2"), "two")]];
+        assert_eq!(apply(raw, &fixes), ("a: 1\nb: two".to_owned(), 1));
+    }
+}
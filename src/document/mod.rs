@@ -1,5 +1,13 @@
 mod annotation;
 mod document;
+mod fix;
+mod paths;
+mod suppression;
 
 pub use annotation::{Annotatable, Annotation, AnnotationLevel, Annotations};
-pub use document::{AsDocumentPointer, Document, DocumentPointer, DocumentPosition};
+pub use document::{
+    AsDocumentPointer, Document, DocumentPointer, DocumentPosition, DocumentSpan,
+};
+pub use fix::Edit;
+pub use suppression::Suppressions;
+pub use paths::NodePaths;
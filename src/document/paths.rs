@@ -0,0 +1,64 @@
+use super::document::{AsDocumentPointer, DocumentSpan};
+
+// Where each node in a document lives, e.g. $.jobs.build.steps[2].uses
+// Unlike line numbers these survive unrelated edits to the document.
+#[derive(Debug, Default)]
+pub struct NodePaths(Vec<(DocumentSpan, String)>);
+
+impl NodePaths {
+    pub fn new() -> NodePaths {
+        NodePaths(Vec::new())
+    }
+
+    pub fn insert(&mut self, span: DocumentSpan, path: String) {
+        self.0.push((span, path))
+    }
+
+    // the path of the innermost node containing the pointer
+    pub fn path_of(&self, p: &impl AsDocumentPointer) -> Option<&str> {
+        let p = p.as_document_pointer();
+        self.0
+            .iter()
+            .filter(|(span, _)| span.contains(&p))
+            .min_by_key(|(span, _)| span.len())
+            .map(|(_, path)| path.as_str())
+    }
+
+    // The smallest node starting at the pointer, a map entry being its key
+    // and value together
+    pub fn node_at(&self, p: &impl AsDocumentPointer) -> Option<DocumentSpan> {
+        let p = p.as_document_pointer();
+        self.0
+            .iter()
+            .filter(|(span, _)| span.start() == p)
+            .filter_map(|(_, path)| {
+                self.0
+                    .iter()
+                    .filter(|(_, other)| other == path)
+                    .map(|(span, _)| *span)
+                    .reduce(|a, b| a.cover(&b))
+            })
+            .min_by_key(|span| span.len())
+    }
+
+    // where the key of a map entry is, keys are recorded before their values
+    pub fn key_of(&self, path: &str) -> Option<DocumentSpan> {
+        self.0.iter().find(|(_, p)| p == path).map(|(span, _)| *span)
+    }
+
+    pub fn key(parent: &str, key: &str) -> String {
+        if !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            format!("{parent}.{key}")
+        } else {
+            format!("{parent}[{key:?}]")
+        }
+    }
+
+    pub fn index(parent: &str, idx: usize) -> String {
+        format!("{parent}[{idx}]")
+    }
+}
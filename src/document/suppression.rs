@@ -0,0 +1,256 @@
+use super::annotation::{Annotation, Annotations};
+use super::document::{Document, DocumentPointer, DocumentSpan};
+use crate::lint::rules;
+
+// Suppressions are read from comments in the raw document as yaml_peg drops
+// comments entirely:
+//
+//   # possum: ignore[rule-a, rule-b]       suppresses the node on the next line
+//   uses: foo/bar@main # possum: ignore[rule]   suppresses the node on this line
+//   # possum: ignore-file[rule]            suppresses the entire document
+//
+// The node on a line is the one it starts with, a key with everything under
+// it or a sequence item, so a suppression above run: covers its whole script.
+#[derive(Debug, Default)]
+pub struct Suppressions(Vec<Suppression>);
+
+#[derive(Debug)]
+struct Suppression {
+    scope: SuppressionScope,
+    rule: String,
+    span: DocumentSpan,
+    used: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SuppressionScope {
+    File,
+    Node(DocumentSpan),
+}
+
+const DIRECTIVE: &str = "possum:";
+
+impl Suppressions {
+    pub fn scan(document: &Document) -> Suppressions {
+        let mut suppressions = Vec::new();
+        let mut block_scalar: Option<usize> = None;
+        let lines: Vec<_> = document.lines().collect();
+
+        for (idx, (_, start, raw)) in lines.iter().enumerate() {
+            let text = String::from_utf8_lossy(raw);
+            let indent = text.len() - text.trim_start().len();
+
+            // comments don't exist inside of block scalars, which is where
+            // run: scripts live and those have comments of their own
+            match block_scalar {
+                Some(parent) if text.trim().is_empty() || indent > parent => continue,
+                _ => block_scalar = None,
+            }
+
+            if opens_block_scalar(&text) {
+                block_scalar = Some(indent);
+            }
+
+            let Some(comment) = find_comment(&text) else {
+                continue;
+            };
+
+            let owns_line = text[..comment].trim().is_empty();
+            let body = text[comment + 1..].trim();
+            let Some(directive) = body.strip_prefix(DIRECTIVE) else {
+                continue;
+            };
+
+            let (scope, rest) = match directive.trim_start() {
+                d if d.starts_with("ignore-file[") => (SuppressionScope::File, &d[11..]),
+                d if d.starts_with("ignore[") => {
+                    // the next line with anything on it, other suppressions aside
+                    let target = if owns_line {
+                        lines[idx + 1..].iter().find(|(_, _, l)| {
+                            let l = String::from_utf8_lossy(l);
+                            !l.trim().is_empty() && !l.trim_start().starts_with('#')
+                        })
+                    } else {
+                        Some(&lines[idx])
+                    };
+                    let Some((_, at, line)) = target else {
+                        continue;
+                    };
+                    (SuppressionScope::Node(node_on(document, *at, line)), &d[6..])
+                }
+                _ => continue,
+            };
+
+            let Some(ids) = rest.strip_prefix('[').and_then(|r| r.split(']').next()) else {
+                continue;
+            };
+
+            let span = DocumentSpan::new(
+                DocumentPointer(start.0 + comment),
+                DocumentPointer(start.0 + raw.len()),
+            );
+
+            for rule in ids.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+                suppressions.push(Suppression {
+                    scope,
+                    rule: rule.to_owned(),
+                    span,
+                    used: false,
+                });
+            }
+        }
+
+        Suppressions(suppressions)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // removes every suppressed annotation and then reports the suppressions that
+    // didn't suppress anything
+    pub fn apply(mut self, annotations: &mut Annotations) {
+        if self.is_empty() {
+            return;
+        }
+
+        annotations.retain(|a| {
+            let mut suppressed = false;
+
+            for s in self.0.iter_mut() {
+                let in_scope = match s.scope {
+                    SuppressionScope::File => true,
+                    SuppressionScope::Node(node) => {
+                        node.contains(&a.span().start()) && a.span().end() <= node.end()
+                    }
+                };
+
+                if in_scope && a.rule().matches(&s.rule) {
+                    s.used = true;
+                    suppressed = true;
+                }
+            }
+
+            !suppressed
+        });
+
+        for s in self.0.iter().filter(|s| !s.used) {
+            let msg = match rules::find(&s.rule) {
+                Some(_) => format!("suppression of {} is unused", s.rule),
+                None => format!("suppression of unknown rule {}", s.rule),
+            };
+            annotations.add(Annotation::warn(&rules::UNUSED_SUPPRESSION, &s.span, &msg));
+        }
+    }
+}
+
+// The node the line starts with, after the - of any sequence it's in, or
+// the rest of the line when there's no node there
+fn node_on(document: &Document, at: DocumentPointer, line: &[u8]) -> DocumentSpan {
+    let mut col = line.iter().take_while(|c| **c == b' ').count();
+    while line[col..].starts_with(b"- ") {
+        col += 1 + line[col + 1..].iter().take_while(|c| **c == b' ').count();
+    }
+
+    let start = DocumentPointer(at.0 + col);
+    document
+        .node_at(&start)
+        .unwrap_or_else(|| DocumentSpan::new(start, DocumentPointer(at.0 + line.len())))
+}
+
+// a # only starts a comment at the start of a line or after whitespace and
+// never inside of a quoted scalar
+fn find_comment(line: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut prev = ' ';
+
+    for (idx, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') if prev.is_whitespace() || prev == ':' => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '#') if prev.is_whitespace() => return Some(idx),
+            _ => {}
+        }
+        prev = c;
+    }
+
+    None
+}
+
+fn opens_block_scalar(line: &str) -> bool {
+    let content = match find_comment(line) {
+        Some(idx) => &line[..idx],
+        None => line,
+    };
+
+    let content = content
+        .trim_end()
+        .trim_end_matches(|c: char| c.is_ascii_digit() || c == '-' || c == '+');
+
+    content.ends_with(": |")
+        || content.ends_with(": >")
+        || content.ends_with("- |")
+        || content.ends_with("- >")
+        || content == "|"
+        || content == ">"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Suppressions;
+    use crate::document::{Annotation, Annotations, Document, DocumentPointer};
+    use crate::lint::rules;
+    use crate::scavenge::parser::test_document;
+    use crate::scavenge::yaml::node_paths;
+
+    // a missing timeout at every marker, what's left after suppressing them
+    fn suppress(raw: &str, markers: &[&str]) -> Vec<String> {
+        let (root, source) = test_document(raw);
+        let document = Document::new(raw).with_paths(node_paths(source, &root));
+
+        let mut annotations = Annotations::new();
+        for marker in markers {
+            let at = DocumentPointer(raw.find(marker).unwrap());
+            annotations.add(Annotation::warn(&rules::MISSING_TIMEOUT, &at, marker));
+        }
+
+        Suppressions::scan(&document).apply(&mut annotations);
+        annotations
+            .entries()
+            .map(|a| format!("{}: {}", a.rule().name(), a.message()))
+            .collect()
+    }
+
+    #[test]
+    fn file_suppressions_cover_everything() {
+        let raw = "# possum: ignore-file[missing-timeout]\njobs:\n  a:\n    runs-on: x\n  b:\n    runs-on: y\n";
+        assert!(suppress(raw, &["a:", "b:"]).is_empty());
+    }
+
+    #[test]
+    fn node_suppressions_cover_the_node_and_nothing_else() {
+        let raw = "jobs:\n  # possum: ignore[missing-timeout]\n  a:\n    runs-on: x\n  b:\n    runs-on: y\n";
+        assert_eq!(suppress(raw, &["a:", "runs-on: x", "b:"]), ["missing-timeout: b:"]);
+
+        let raw = "jobs:\n  a:\n    runs-on: x # possum: ignore[missing-timeout]\n    steps: []\n";
+        assert_eq!(suppress(raw, &["x", "steps"]), ["missing-timeout: steps"]);
+    }
+
+    #[test]
+    fn a_suppression_above_a_block_scalar_covers_all_of_it() {
+        let raw = "steps:\n  # possum: ignore[missing-timeout]\n  - run: |\n      echo one\n      echo two\n  - run: echo three\n";
+        assert_eq!(suppress(raw, &["echo two", "echo three"]), ["missing-timeout: echo three"]);
+    }
+
+    #[test]
+    fn unknown_and_unused_suppressions_are_reported() {
+        let raw = "jobs:\n  # possum: ignore[missing-timeout, no-such-rule]\n  a:\n    runs-on: x\n";
+        assert_eq!(
+            suppress(raw, &[]),
+            [
+                "unused-suppression: suppression of missing-timeout is unused",
+                "unused-suppression: suppression of unknown rule no-such-rule",
+            ]
+        );
+    }
+}
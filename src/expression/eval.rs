@@ -0,0 +1,493 @@
+use super::parser::{BinaryOp, Expr, Literal};
+use super::value::Value;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+// what success() and friends return, since nothing has actually run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusAssumption {
+    // every earlier step and needed job succeeded
+    Succeeded,
+    // the status isn't known, so only always() is
+    Unknown,
+}
+
+// The contexts an expression is evaluated against. Contexts that weren't
+// given are unknown rather than empty.
+#[derive(Debug, Clone)]
+pub struct Context {
+    contexts: BTreeMap<String, Value>,
+    // objects made up by set_default, whose other properties aren't known
+    partial: Vec<Vec<String>>,
+    status: StatusAssumption,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalError(pub String);
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug)]
+pub struct ContextError {
+    path: PathBuf,
+    reason: String,
+}
+
+impl Display for ContextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.reason)
+    }
+}
+
+impl std::error::Error for ContextError {}
+
+impl Context {
+    // nothing about the run is known
+    pub fn unknown() -> Context {
+        Context {
+            contexts: BTreeMap::new(),
+            partial: Vec::new(),
+            status: StatusAssumption::Unknown,
+        }
+    }
+
+    // a JSON object of contexts, eg. {"github": {"ref": "refs/heads/main"}}
+    pub fn load(path: impl AsRef<Path>) -> Result<Context, ContextError> {
+        let path = path.as_ref();
+        let err = |reason: String| ContextError {
+            path: path.to_path_buf(),
+            reason,
+        };
+
+        let raw = std::fs::read_to_string(path).map_err(|e| err(e.to_string()))?;
+        let json: serde_json::Value = serde_json::from_str(&raw).map_err(|e| err(e.to_string()))?;
+
+        match Value::from(json) {
+            Value::Object(contexts) => Ok(Context {
+                contexts: contexts
+                    .into_iter()
+                    .map(|(k, v)| (k.to_lowercase(), v))
+                    .collect(),
+                partial: Vec::new(),
+                status: StatusAssumption::Unknown,
+            }),
+            _ => Err(err("expected an object of contexts".to_owned())),
+        }
+    }
+
+    pub fn with_status(mut self, status: StatusAssumption) -> Context {
+        self.status = status;
+        self
+    }
+
+    // sets a value unless one was already given, creating objects on the way
+    pub fn set_default(&mut self, path: &[&str], value: Value) {
+        let (name, rest) = match path.split_first() {
+            Some(split) => split,
+            None => return,
+        };
+
+        let mut walked = vec![name.to_lowercase()];
+        if !self.contexts.contains_key(&walked[0]) {
+            self.partial.push(walked.clone());
+        }
+        let mut current = self
+            .contexts
+            .entry(name.to_lowercase())
+            .or_insert_with(|| Value::Object(BTreeMap::new()));
+
+        for key in rest {
+            walked.push(key.to_lowercase());
+            current = match current {
+                Value::Object(m) => {
+                    if !m.contains_key(*key) {
+                        self.partial.push(walked.clone());
+                    }
+                    m.entry(key.to_string())
+                        .or_insert_with(|| Value::Object(BTreeMap::new()))
+                }
+                _ => return,
+            };
+        }
+
+        if matches!(current, Value::Object(m) if m.is_empty()) {
+            *current = value;
+        }
+    }
+
+    pub fn evaluate(&self, expr: &Expr) -> Result<Value, EvalError> {
+        match expr {
+            Expr::Literal(Literal::Null) => Ok(Value::Null),
+            Expr::Literal(Literal::Bool(b)) => Ok(Value::Bool(*b)),
+            Expr::Literal(Literal::Number(n)) => Ok(Value::Number(*n)),
+            Expr::Literal(Literal::Str(s)) => Ok(Value::String(s.clone())),
+            Expr::Context(name) => Ok(self
+                .contexts
+                .get(&name.to_lowercase())
+                .cloned()
+                .unwrap_or_else(|| Value::unknown(name.as_str()))),
+            Expr::Property(e, name) => {
+                let value = self.evaluate(e)?;
+                if value.property(name).is_none() && self.is_partial(e) {
+                    return Ok(Value::unknown(expr.to_string()));
+                }
+                Ok(property(&value, name, e.is_filtered()))
+            }
+            Expr::Index(e, i) => {
+                let value = self.evaluate(e)?;
+                match (self.evaluate(i)?, value) {
+                    (_, v @ Value::Unknown { .. }) => Ok(Value::unknown(format!("{v}[{i}]"))),
+                    (k @ Value::Unknown { .. }, _) => Ok(k.unknown_like()),
+                    (Value::Number(n), Value::Array(a)) if n >= 0.0 => {
+                        Ok(a.get(n as usize).cloned().unwrap_or(Value::Null))
+                    }
+                    (key, value) => Ok(property(&value, &key.to_text(), e.is_filtered())),
+                }
+            }
+            Expr::Filter(e) => match self.evaluate(e)? {
+                Value::Array(a) => Ok(Value::Array(a)),
+                Value::Object(m) => Ok(Value::Array(m.into_values().collect())),
+                v @ Value::Unknown { .. } => Ok(Value::Unknown {
+                    what: format!("{v}.*"),
+                    truthy: Some(true),
+                }),
+                _ => Ok(Value::Array(Vec::new())),
+            },
+            Expr::Not(e) => {
+                let value = self.evaluate(e)?;
+                match value.truthy() {
+                    Some(t) => Ok(Value::Bool(!t)),
+                    None => Ok(value.unknown_like()),
+                }
+            }
+            Expr::Binary(l, BinaryOp::And, r) => {
+                let left = self.evaluate(l)?;
+                match left.truthy() {
+                    Some(false) => Ok(left),
+                    Some(true) => self.evaluate(r),
+                    None => {
+                        let right = self.evaluate(r)?;
+                        Ok(Value::Unknown {
+                            what: left.to_string(),
+                            truthy: right.truthy().filter(|t| !t),
+                        })
+                    }
+                }
+            }
+            Expr::Binary(l, BinaryOp::Or, r) => {
+                let left = self.evaluate(l)?;
+                match left.truthy() {
+                    Some(true) => Ok(left),
+                    Some(false) => self.evaluate(r),
+                    None => {
+                        let right = self.evaluate(r)?;
+                        Ok(Value::Unknown {
+                            what: left.to_string(),
+                            truthy: right.truthy().filter(|t| *t),
+                        })
+                    }
+                }
+            }
+            Expr::Binary(l, op, r) => {
+                let left = self.evaluate(l)?;
+                let right = self.evaluate(r)?;
+                if let Some(unknown) = [&left, &right].into_iter().find(|v| v.is_unknown()) {
+                    return Ok(unknown.unknown_like());
+                }
+                Ok(Value::Bool(compare(&left, *op, &right)))
+            }
+            Expr::Call(name, args) => {
+                let values = args
+                    .iter()
+                    .map(|a| self.evaluate(a))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.call(name, &values, expr)
+            }
+        }
+    }
+
+    fn is_partial(&self, e: &Expr) -> bool {
+        match e.path() {
+            Some(path) => {
+                let path: Vec<String> = path.iter().map(|p| p.to_lowercase()).collect();
+                self.partial.contains(&path)
+            }
+            None => false,
+        }
+    }
+
+    fn call(&self, name: &str, args: &[Value], expr: &Expr) -> Result<Value, EvalError> {
+        let name = name.to_lowercase();
+        let (min, max) = match arity(&name) {
+            Some(arity) => arity,
+            None => return Err(EvalError(format!("unknown function {name}"))),
+        };
+        if args.len() < min || args.len() > max {
+            return Err(EvalError(if min == max {
+                format!("{name} takes {min} arguments but was given {}", args.len())
+            } else {
+                format!("{name} takes {min} to {max} arguments but was given {}", args.len())
+            }));
+        }
+
+        let status = |succeeded: bool| match self.status {
+            StatusAssumption::Succeeded => Value::Bool(succeeded),
+            StatusAssumption::Unknown => Value::unknown(format!("{name}()")),
+        };
+
+        match name.as_str() {
+            "always" => return Ok(Value::Bool(true)),
+            "success" => return Ok(status(true)),
+            "failure" | "cancelled" => return Ok(status(false)),
+            "hashfiles" => return Ok(Value::unknown(expr.to_string())),
+            _ => {}
+        }
+
+        if let Some(unknown) = args.iter().find(|v| v.is_unknown()) {
+            return Ok(unknown.unknown_like());
+        }
+
+        let text = |i: usize| args[i].to_text().to_lowercase();
+
+        Ok(match name.as_str() {
+            "contains" => match &args[0] {
+                Value::Array(items) => {
+                    Value::Bool(items.iter().any(|i| compare(i, BinaryOp::Eq, &args[1])))
+                }
+                _ => Value::Bool(text(0).contains(&text(1))),
+            },
+            "startswith" => Value::Bool(text(0).starts_with(&text(1))),
+            "endswith" => Value::Bool(text(0).ends_with(&text(1))),
+            "format" => Value::String(format(&args[0].to_text(), &args[1..])?),
+            "join" => {
+                let separator = args.get(1).map_or(",".to_owned(), Value::to_text);
+                match &args[0] {
+                    Value::Array(items) => Value::String(
+                        items
+                            .iter()
+                            .map(Value::to_text)
+                            .collect::<Vec<_>>()
+                            .join(&separator),
+                    ),
+                    v => Value::String(v.to_text()),
+                }
+            }
+            "tojson" => Value::String(
+                serde_json::to_string_pretty(&args[0].to_json()).unwrap_or_default(),
+            ),
+            "fromjson" => serde_json::from_str::<serde_json::Value>(&args[0].to_text())
+                .map(Value::from)
+                .map_err(|e| EvalError(format!("fromJSON: {e}")))?,
+            _ => unreachable!(),
+        })
+    }
+}
+
+// the least and most arguments each function takes
+pub fn arity(name: &str) -> Option<(usize, usize)> {
+    Some(match name.to_lowercase().as_str() {
+        "always" | "success" | "failure" | "cancelled" => (0, 0),
+        "contains" | "startswith" | "endswith" => (2, 2),
+        "format" => (1, usize::MAX),
+        "join" => (1, 2),
+        "tojson" | "fromjson" => (1, 1),
+        "hashfiles" => (1, usize::MAX),
+        _ => return None,
+    })
+}
+
+fn property(value: &Value, name: &str, filtered: bool) -> Value {
+    match value {
+        Value::Unknown { what, .. } => Value::unknown(format!("{what}.{name}")),
+        Value::Array(items) if filtered => Value::Array(
+            items
+                .iter()
+                .filter_map(|i| i.property(name))
+                .cloned()
+                .collect(),
+        ),
+        v => v.property(name).cloned().unwrap_or(Value::Null),
+    }
+}
+
+// loose comparison, converting to numbers when the types differ and ignoring
+// case between strings
+fn compare(left: &Value, op: BinaryOp, right: &Value) -> bool {
+    use std::cmp::Ordering;
+
+    let ordering = match (left, right) {
+        (Value::String(l), Value::String(r)) => Some(l.to_lowercase().cmp(&r.to_lowercase())),
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        (Value::Bool(l), Value::Bool(r)) => Some(l.cmp(r)),
+        (Value::Array(_) | Value::Object(_), _) | (_, Value::Array(_) | Value::Object(_)) => {
+            // only the same instance is equal, which a copy never is
+            None
+        }
+        (l, r) => l.to_number().partial_cmp(&r.to_number()),
+    };
+
+    match (op, ordering) {
+        (BinaryOp::Ne, o) => o != Some(Ordering::Equal),
+        (_, None) => false,
+        (BinaryOp::Eq, Some(o)) => o == Ordering::Equal,
+        (BinaryOp::Lt, Some(o)) => o == Ordering::Less,
+        (BinaryOp::Le, Some(o)) => o != Ordering::Greater,
+        (BinaryOp::Gt, Some(o)) => o == Ordering::Greater,
+        (BinaryOp::Ge, Some(o)) => o != Ordering::Less,
+        (BinaryOp::And | BinaryOp::Or, _) => unreachable!(),
+    }
+}
+
+// format('{0} and {{literal}}', x)
+fn format(template: &str, args: &[Value]) -> Result<String, EvalError> {
+    let mut out = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '{' => {
+                let mut index = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(d) if d.is_ascii_digit() => index.push(d),
+                        _ => return Err(EvalError(format!("format: invalid placeholder in {template}"))),
+                    }
+                }
+                let arg = index
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| args.get(i))
+                    .ok_or_else(|| EvalError(format!("format: no argument for {{{index}}}")))?;
+                out.push_str(&arg.to_text());
+            }
+            c => out.push(c),
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Context;
+    use crate::expression::parser::Expr;
+    use crate::expression::value::Value;
+
+    fn eval(raw: &str) -> Value {
+        Context::unknown().evaluate(&Expr::parse(raw).unwrap()).unwrap()
+    }
+
+    fn text(raw: &str) -> String {
+        match eval(raw) {
+            Value::String(s) => s,
+            v => panic!("{raw} is {v}"),
+        }
+    }
+
+    #[test]
+    fn different_types_are_compared_as_numbers() {
+        assert_eq!(eval("'true' == true"), Value::Bool(false));
+        assert_eq!(eval("null == 0"), Value::Bool(true));
+        assert_eq!(eval("null == false"), Value::Bool(true));
+        assert_eq!(eval("'' == 0"), Value::Bool(true));
+        assert_eq!(eval("' 1 ' == 1"), Value::Bool(true));
+        assert_eq!(eval("true == 1"), Value::Bool(true));
+        assert_eq!(eval("'abc' != 0"), Value::Bool(true));
+        assert_eq!(eval("NaN == NaN"), Value::Bool(false));
+    }
+
+    #[test]
+    fn strings_are_compared_ignoring_case() {
+        assert_eq!(eval("'Main' == 'mAIN'"), Value::Bool(true));
+        assert_eq!(eval("'a' < 'B'"), Value::Bool(true));
+    }
+
+    #[test]
+    fn arrays_and_objects_are_never_equal() {
+        assert_eq!(eval("fromJSON('[1]') == fromJSON('[1]')"), Value::Bool(false));
+        assert_eq!(eval("fromJSON('{}') != fromJSON('{}')"), Value::Bool(true));
+    }
+
+    #[test]
+    fn comparison_binds_tighter_than_equality() {
+        assert_eq!(eval("1 < 2 == true"), Value::Bool(true));
+        assert_eq!(eval("2 < 1 == false"), Value::Bool(true));
+    }
+
+    #[test]
+    fn and_and_or_return_an_operand() {
+        assert_eq!(eval("'' || 'default'"), Value::String("default".to_owned()));
+        assert_eq!(eval("'a' && 'b'"), Value::String("b".to_owned()));
+        assert_eq!(eval("0 && unknown.thing"), Value::Number(0.0));
+        assert_eq!(eval("!''"), Value::Bool(true));
+    }
+
+    #[test]
+    fn contains_is_loose_on_arrays_and_ignores_case_on_strings() {
+        assert_eq!(eval("contains(fromJSON('[\"a\", \"B\"]'), 'b')"), Value::Bool(true));
+        assert_eq!(eval("contains(fromJSON('[1, 2]'), '2')"), Value::Bool(true));
+        assert_eq!(eval("contains(fromJSON('[\"ab\"]'), 'a')"), Value::Bool(false));
+        assert_eq!(eval("contains('Hello', 'LL')"), Value::Bool(true));
+        assert_eq!(eval("startsWith('refs/heads/main', 'REFS/')"), Value::Bool(true));
+        assert_eq!(eval("endsWith('v1.0', '.1')"), Value::Bool(false));
+    }
+
+    #[test]
+    fn format_escapes_braces_by_doubling_them() {
+        assert_eq!(text("format('{{ {0} }}', 'x')"), "{ x }");
+        assert_eq!(text("format('{0}{1}{0}', 1, true)"), "1true1");
+        assert_eq!(text("format('}}{{')"), "}{");
+        let context = Context::unknown();
+        assert!(context.evaluate(&Expr::parse("format('{1}', 'a')").unwrap()).is_err());
+        assert!(context.evaluate(&Expr::parse("format('{x}', 'a')").unwrap()).is_err());
+    }
+
+    #[test]
+    fn functions() {
+        assert_eq!(text("join(fromJSON('[\"a\", 1]'))"), "a,1");
+        assert_eq!(text("join(fromJSON('[\"a\", \"b\"]'), '-')"), "a-b");
+        assert_eq!(text("toJSON(fromJSON('[1]'))"), "[\n  1\n]");
+        let context = Context::unknown();
+        assert!(context.evaluate(&Expr::parse("contains('a')").unwrap()).is_err());
+        assert!(context.evaluate(&Expr::parse("nope()").unwrap()).is_err());
+    }
+
+    #[test]
+    fn properties_ignore_case_and_filters_map_over_arrays() {
+        let mut context = Context::unknown();
+        context.set_default(&["github", "ref"], Value::String("refs/heads/main".to_owned()));
+        let eval = |raw: &str| context.evaluate(&Expr::parse(raw).unwrap()).unwrap();
+
+        assert_eq!(eval("github['REF']"), Value::String("refs/heads/main".to_owned()));
+        assert!(eval("github.sha").is_unknown());
+        assert!(eval("matrix.os").is_unknown());
+        assert_eq!(
+            eval("fromJSON('[{\"a\": 1}, {\"a\": 2}, {}]').*.a"),
+            Value::Array(vec![Value::Number(1.0), Value::Number(2.0)])
+        );
+        assert_eq!(eval("fromJSON('{\"a\": 1}').b"), Value::Null);
+    }
+
+    #[test]
+    fn unknowns_keep_what_is_known_about_them() {
+        assert_eq!(eval("github.actor || true").truthy(), Some(true));
+        assert_eq!(eval("github.actor && false").truthy(), Some(false));
+        assert_eq!(eval("github.actor == 'me'").truthy(), None);
+        assert_eq!(eval("success()").truthy(), None);
+        assert_eq!(eval("always()"), Value::Bool(true));
+    }
+}
@@ -0,0 +1,267 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Str(String),
+    Ident(String),
+    Dot,
+    Star,
+    Comma,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Not,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Or,
+}
+
+// where a token starts, in characters from the start of the expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lexeme {
+    pub token: Token,
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexError {
+    pub offset: usize,
+    pub reason: String,
+}
+
+impl Display for LexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at character {}", self.reason, self.offset + 1)
+    }
+}
+
+pub fn lex(raw: &str) -> Result<Vec<Lexeme>, LexError> {
+    let chars: Vec<char> = raw.chars().collect();
+    let mut lexemes = Vec::new();
+    let mut i = 0;
+
+    let err = |offset: usize, reason: &str| LexError {
+        offset,
+        reason: reason.to_owned(),
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        let token = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '.' if chars.get(i + 1).map_or(true, |n| !n.is_ascii_digit()) => {
+                i += 1;
+                Token::Dot
+            }
+            '*' => {
+                i += 1;
+                Token::Star
+            }
+            ',' => {
+                i += 1;
+                Token::Comma
+            }
+            '(' => {
+                i += 1;
+                Token::LParen
+            }
+            ')' => {
+                i += 1;
+                Token::RParen
+            }
+            '[' => {
+                i += 1;
+                Token::LBracket
+            }
+            ']' => {
+                i += 1;
+                Token::RBracket
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                i += 2;
+                Token::Ne
+            }
+            '!' => {
+                i += 1;
+                Token::Not
+            }
+            '<' if chars.get(i + 1) == Some(&'=') => {
+                i += 2;
+                Token::Le
+            }
+            '<' => {
+                i += 1;
+                Token::Lt
+            }
+            '>' if chars.get(i + 1) == Some(&'=') => {
+                i += 2;
+                Token::Ge
+            }
+            '>' => {
+                i += 1;
+                Token::Gt
+            }
+            '=' if chars.get(i + 1) == Some(&'=') => {
+                i += 2;
+                Token::Eq
+            }
+            '&' if chars.get(i + 1) == Some(&'&') => {
+                i += 2;
+                Token::And
+            }
+            '|' if chars.get(i + 1) == Some(&'|') => {
+                i += 2;
+                Token::Or
+            }
+            // strings are single quoted, with '' for a literal quote
+            '\'' => {
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(err(start, "unterminated string")),
+                        Some('\'') if chars.get(i + 1) == Some(&'\'') => {
+                            s.push('\'');
+                            i += 2;
+                        }
+                        Some('\'') => {
+                            i += 1;
+                            break;
+                        }
+                        Some(c) => {
+                            s.push(*c);
+                            i += 1;
+                        }
+                    }
+                }
+                Token::Str(s)
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' => {
+                while i < chars.len()
+                    && (chars[i].is_ascii_alphanumeric() || matches!(chars[i], '.' | '-' | '+'))
+                {
+                    // a sign only belongs to the number at its start or in an exponent
+                    if matches!(chars[i], '-' | '+')
+                        && i != start
+                        && !matches!(chars[i - 1], 'e' | 'E')
+                    {
+                        break;
+                    }
+                    i += 1;
+                }
+                let raw: String = chars[start..i].iter().collect();
+                Token::Number(parse_number(&raw).ok_or_else(|| err(start, "invalid number"))?)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '-')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                match word.as_str() {
+                    "null" => Token::Null,
+                    "true" => Token::Bool(true),
+                    "false" => Token::Bool(false),
+                    "NaN" => Token::Number(f64::NAN),
+                    "Infinity" => Token::Number(f64::INFINITY),
+                    _ => Token::Ident(word),
+                }
+            }
+            _ => return Err(err(start, &format!("unexpected {c}"))),
+        };
+
+        lexemes.push(Lexeme {
+            token,
+            offset: start,
+        });
+    }
+
+    Ok(lexemes)
+}
+
+// decimal, 0x hexadecimal or exponent notation
+pub fn parse_number(raw: &str) -> Option<f64> {
+    let (negative, digits) = match raw.strip_prefix('-') {
+        Some(d) => (true, d),
+        None => (false, raw.strip_prefix('+').unwrap_or(raw)),
+    };
+
+    let n = if let Some(hex) = digits.strip_prefix("0x") {
+        i64::from_str_radix(hex, 16).ok()? as f64
+    } else if let Some(oct) = digits.strip_prefix("0o") {
+        i64::from_str_radix(oct, 8).ok()? as f64
+    } else {
+        digits.parse::<f64>().ok()?
+    };
+
+    Some(if negative { -n } else { n })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lex, parse_number, Token};
+
+    fn tokens(raw: &str) -> Vec<Token> {
+        lex(raw).unwrap().into_iter().map(|l| l.token).collect()
+    }
+
+    #[test]
+    fn operators_and_names() {
+        assert_eq!(
+            tokens("!github.event_name!=x-y[0]<=1&&a||b"),
+            [
+                Token::Not,
+                Token::Ident("github".to_owned()),
+                Token::Dot,
+                Token::Ident("event_name".to_owned()),
+                Token::Ne,
+                Token::Ident("x-y".to_owned()),
+                Token::LBracket,
+                Token::Number(0.0),
+                Token::RBracket,
+                Token::Le,
+                Token::Number(1.0),
+                Token::And,
+                Token::Ident("a".to_owned()),
+                Token::Or,
+                Token::Ident("b".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn strings_escape_quotes_by_doubling_them() {
+        assert_eq!(tokens("'it''s'"), [Token::Str("it's".to_owned())]);
+        assert_eq!(lex("'open").unwrap_err().offset, 0);
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(tokens("-1.5e2"), [Token::Number(-150.0)]);
+        assert_eq!(tokens("0xff"), [Token::Number(255.0)]);
+        assert_eq!(tokens(".5"), [Token::Number(0.5)]);
+        assert_eq!(tokens("1-2"), [Token::Number(1.0), Token::Number(-2.0)]);
+        assert_eq!(parse_number("1e"), None);
+    }
+
+    #[test]
+    fn keywords_are_case_sensitive() {
+        assert_eq!(tokens("true null"), [Token::Bool(true), Token::Null]);
+        assert_eq!(tokens("True"), [Token::Ident("True".to_owned())]);
+    }
+}
@@ -0,0 +1,187 @@
+pub mod eval;
+pub mod lexer;
+pub mod parser;
+pub mod schema;
+pub mod value;
+
+use eval::{Context, EvalError};
+use parser::{Expr, ParseError};
+use value::Value;
+
+pub const STATUS_FUNCTIONS: &[&str] = &["success", "failure", "cancelled", "always"];
+
+// A ${{ }} inside a string, with the byte range it covers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Embedded<'a> {
+    pub start: usize,
+    pub end: usize,
+    pub source: &'a str,
+}
+
+impl<'a> Embedded<'a> {
+    pub fn parse(&self) -> Result<Expr, ParseError> {
+        Expr::parse(self.source)
+    }
+}
+
+// every ${{ }} in raw, skipping over }} inside string literals
+pub fn embedded(raw: &str) -> Result<Vec<Embedded>, ParseError> {
+    let mut found = Vec::new();
+    let mut rest = 0;
+
+    while let Some(at) = raw[rest..].find("${{") {
+        let start = rest + at;
+        let inner = start + 3;
+        let mut quoted = false;
+        let mut end = None;
+
+        for (i, c) in raw[inner..].char_indices() {
+            match c {
+                '\'' => quoted = !quoted,
+                '}' if !quoted && raw[inner + i..].starts_with("}}") => {
+                    end = Some(inner + i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let end = end.ok_or_else(|| ParseError {
+            offset: raw[..start].chars().count(),
+            reason: "${{ is never closed".to_owned(),
+        })?;
+
+        found.push(Embedded {
+            start,
+            end: end + 2,
+            source: &raw[inner..end],
+        });
+        rest = end + 2;
+    }
+
+    Ok(found)
+}
+
+// The `if` of a job or step. The ${{ }} is optional, but text around it
+// turns the whole condition into a string.
+#[derive(Debug, Clone)]
+pub enum Condition {
+    Expression(Expr),
+    Template(String, Vec<Expr>),
+}
+
+impl Condition {
+    pub fn parse(raw: &str) -> Result<Condition, ParseError> {
+        let trimmed = raw.trim();
+        let parts = embedded(trimmed)?;
+
+        match parts.as_slice() {
+            [] => Expr::parse(trimmed).map(Condition::Expression),
+            [only] if only.start == 0 && only.end == trimmed.len() => {
+                only.parse().map(Condition::Expression)
+            }
+            parts => Ok(Condition::Template(
+                trimmed.to_owned(),
+                parts.iter().map(Embedded::parse).collect::<Result<_, _>>()?,
+            )),
+        }
+    }
+
+    // whether it uses success(), failure(), cancelled() or always() itself
+    pub fn checks_status(&self) -> bool {
+        match self {
+            Condition::Expression(e) => e.calls(STATUS_FUNCTIONS),
+            Condition::Template(_, parts) => parts.iter().any(|e| e.calls(STATUS_FUNCTIONS)),
+        }
+    }
+
+    // the condition as written, without the implicit success()
+    pub fn evaluate(&self, context: &Context) -> Result<Value, EvalError> {
+        match self {
+            Condition::Expression(e) => context.evaluate(e),
+            Condition::Template(raw, parts) => {
+                for p in parts {
+                    context.evaluate(p)?;
+                }
+                Ok(Value::String(raw.clone()))
+            }
+        }
+    }
+
+    // Some when the condition is the same whatever the run. always() on its
+    // own is the usual way to run regardless of status, so it doesn't count.
+    pub fn constant(&self) -> Result<Option<bool>, EvalError> {
+        Ok(match self.evaluate(&Context::unknown())?.truthy() {
+            Some(true) if self.checks_status() => None,
+            truthy => truthy,
+        })
+    }
+
+    // whether the job or step runs, None if that depends on the run
+    pub fn runs(&self, context: &Context) -> Result<Option<bool>, EvalError> {
+        let value = self.evaluate(context)?;
+        if self.checks_status() {
+            return Ok(value.truthy());
+        }
+
+        // no status function means success() && condition
+        let implicit = context.evaluate(&Expr::Call("success".to_owned(), Vec::new()))?;
+
+        Ok(match (implicit.truthy(), value.truthy()) {
+            (Some(false), _) | (_, Some(false)) => Some(false),
+            (Some(true), v) => v,
+            (None, _) => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::eval::{Context, StatusAssumption};
+    use super::Condition;
+
+    fn constant(raw: &str) -> Option<bool> {
+        Condition::parse(raw).unwrap().constant().unwrap()
+    }
+
+    fn runs(raw: &str, status: StatusAssumption) -> Option<bool> {
+        let context = Context::unknown().with_status(status);
+        Condition::parse(raw).unwrap().runs(&context).unwrap()
+    }
+
+    #[test]
+    fn the_braces_are_optional_but_text_around_them_makes_a_string() {
+        assert!(matches!(Condition::parse(" ${{ a == b }} "), Ok(Condition::Expression(_))));
+        assert!(matches!(Condition::parse("a == b"), Ok(Condition::Expression(_))));
+        assert!(matches!(Condition::parse("${{ a }} == b"), Ok(Condition::Template(..))));
+        assert_eq!(constant("${{ github.ref }} == 'refs/heads/main'"), Some(true));
+    }
+
+    #[test]
+    fn constant_conditions() {
+        assert_eq!(constant("true"), Some(true));
+        assert_eq!(constant("${{ false }}"), Some(false));
+        assert_eq!(constant("'true' == true"), Some(false));
+        assert_eq!(constant("github.ref == 'refs/heads/main' || 1"), Some(true));
+        assert_eq!(constant("failure() && false"), Some(false));
+
+        assert_eq!(constant("always()"), None);
+        assert_eq!(constant("success() || true"), None);
+        assert_eq!(constant("github.ref == 'refs/heads/main'"), None);
+        assert_eq!(constant("contains(github.event.labels.*.name, 'x')"), None);
+    }
+
+    #[test]
+    fn conditions_without_a_status_function_need_success() {
+        use StatusAssumption::*;
+
+        assert_eq!(runs("true", Succeeded), Some(true));
+        assert_eq!(runs("true", Unknown), None);
+        assert_eq!(runs("false", Unknown), Some(false));
+        assert_eq!(runs("github.ref == 'x'", Succeeded), None);
+
+        assert_eq!(runs("always()", Unknown), Some(true));
+        assert_eq!(runs("failure()", Succeeded), Some(false));
+        assert_eq!(runs("success() || failure()", Succeeded), Some(true));
+    }
+}
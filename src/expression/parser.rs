@@ -0,0 +1,457 @@
+use super::lexer::{lex, LexError, Lexeme, Token};
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Str(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    And,
+    Or,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Literal),
+    // a top level name like github or matrix
+    Context(String),
+    Property(Box<Expr>, String),
+    Index(Box<Expr>, Box<Expr>),
+    // .* or [*]
+    Filter(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub offset: usize,
+    pub reason: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at character {}", self.reason, self.offset + 1)
+    }
+}
+
+impl From<LexError> for ParseError {
+    fn from(e: LexError) -> Self {
+        ParseError {
+            offset: e.offset,
+            reason: e.reason,
+        }
+    }
+}
+
+impl Display for BinaryOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            BinaryOp::And => "&&",
+            BinaryOp::Or => "||",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+        };
+        write!(f, "{op}")
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Literal(Literal::Null) => write!(f, "null"),
+            Expr::Literal(Literal::Bool(b)) => write!(f, "{b}"),
+            Expr::Literal(Literal::Number(n)) => write!(f, "{n}"),
+            Expr::Literal(Literal::Str(s)) => write!(f, "'{}'", s.replace('\'', "''")),
+            Expr::Context(name) => write!(f, "{name}"),
+            Expr::Property(e, name) => write!(f, "{e}.{name}"),
+            Expr::Index(e, i) => write!(f, "{e}[{i}]"),
+            Expr::Filter(e) => write!(f, "{e}.*"),
+            Expr::Not(e) if matches!(e.as_ref(), Expr::Binary(..)) => write!(f, "!({e})"),
+            Expr::Not(e) => write!(f, "!{e}"),
+            Expr::Binary(l, op, r) => {
+                write_operand(f, l, *op)?;
+                write!(f, " {op} ")?;
+                write_operand(f, r, *op)
+            }
+            Expr::Call(name, args) => {
+                let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+                write!(f, "{name}({})", args.join(", "))
+            }
+        }
+    }
+}
+
+impl BinaryOp {
+    fn precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Or => 0,
+            BinaryOp::And => 1,
+            BinaryOp::Eq | BinaryOp::Ne => 2,
+            BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => 3,
+        }
+    }
+}
+
+// parenthesises operands that bind looser than the operator they're under
+fn write_operand(f: &mut std::fmt::Formatter<'_>, e: &Expr, under: BinaryOp) -> std::fmt::Result {
+    match e {
+        Expr::Binary(_, op, _) if op.precedence() < under.precedence() => write!(f, "({e})"),
+        _ => write!(f, "{e}"),
+    }
+}
+
+impl Expr {
+    pub fn parse(raw: &str) -> Result<Expr, ParseError> {
+        let lexemes = lex(raw)?;
+        let mut parser = ExprParser {
+            lexemes: &lexemes,
+            at: 0,
+            end: raw.chars().count(),
+        };
+
+        let expr = parser.or()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(l) => Err(parser.unexpected(l)),
+        }
+    }
+
+    // calls visit on this expression and everything inside it
+    pub fn walk<'a>(&'a self, visit: &mut impl FnMut(&'a Expr)) {
+        visit(self);
+        match self {
+            Expr::Literal(_) | Expr::Context(_) => {}
+            Expr::Property(e, _) | Expr::Filter(e) | Expr::Not(e) => e.walk(visit),
+            Expr::Index(e, i) => {
+                e.walk(visit);
+                i.walk(visit);
+            }
+            Expr::Binary(l, _, r) => {
+                l.walk(visit);
+                r.walk(visit);
+            }
+            Expr::Call(_, args) => args.iter().for_each(|a| a.walk(visit)),
+        }
+    }
+
+    // the names along a reference like github.event['pull_request'].title,
+    // with * for filters
+    pub fn path(&self) -> Option<Vec<&str>> {
+        match self {
+            Expr::Context(name) => Some(vec![name.as_str()]),
+            Expr::Property(e, name) => e.path().map(|mut p| {
+                p.push(name.as_str());
+                p
+            }),
+            Expr::Index(e, i) => match i.as_ref() {
+                Expr::Literal(Literal::Str(name)) => e.path().map(|mut p| {
+                    p.push(name.as_str());
+                    p
+                }),
+                _ => None,
+            },
+            Expr::Filter(e) => e.path().map(|mut p| {
+                p.push("*");
+                p
+            }),
+            _ => None,
+        }
+    }
+
+    // whether it's under a .* and so produces an array to map over
+    pub fn is_filtered(&self) -> bool {
+        match self {
+            Expr::Filter(_) => true,
+            Expr::Property(e, _) | Expr::Index(e, _) => e.is_filtered(),
+            _ => false,
+        }
+    }
+
+    pub fn calls(&self, names: &[&str]) -> bool {
+        let mut found = false;
+        self.walk(&mut |e| {
+            if let Expr::Call(name, _) = e {
+                found |= names.iter().any(|n| n.eq_ignore_ascii_case(name));
+            }
+        });
+        found
+    }
+}
+
+struct ExprParser<'a> {
+    lexemes: &'a [Lexeme],
+    at: usize,
+    end: usize,
+}
+
+impl<'a> ExprParser<'a> {
+    fn peek(&self) -> Option<&'a Lexeme> {
+        self.lexemes.get(self.at)
+    }
+
+    fn next(&mut self) -> Option<&'a Lexeme> {
+        let l = self.lexemes.get(self.at);
+        self.at += 1;
+        l
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek().map(|l| &l.token) == Some(token) {
+            self.at += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), ParseError> {
+        if self.eat(&token) {
+            return Ok(());
+        }
+        Err(match self.peek() {
+            Some(l) => ParseError {
+                offset: l.offset,
+                reason: format!("expected {what}"),
+            },
+            None => self.eof(&format!("expected {what}")),
+        })
+    }
+
+    fn unexpected(&self, l: &Lexeme) -> ParseError {
+        ParseError {
+            offset: l.offset,
+            reason: format!("unexpected {}", describe(&l.token)),
+        }
+    }
+
+    fn eof(&self, reason: &str) -> ParseError {
+        ParseError {
+            offset: self.end,
+            reason: reason.to_owned(),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.and()?;
+        while self.eat(&Token::Or) {
+            left = Expr::Binary(Box::new(left), BinaryOp::Or, Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.equality()?;
+        while self.eat(&Token::And) {
+            left = Expr::Binary(Box::new(left), BinaryOp::And, Box::new(self.equality()?));
+        }
+        Ok(left)
+    }
+
+    fn equality(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.comparison()?;
+        loop {
+            let op = match self.peek().map(|l| &l.token) {
+                Some(Token::Eq) => BinaryOp::Eq,
+                Some(Token::Ne) => BinaryOp::Ne,
+                _ => return Ok(left),
+            };
+            self.at += 1;
+            left = Expr::Binary(Box::new(left), op, Box::new(self.comparison()?));
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek().map(|l| &l.token) {
+                Some(Token::Lt) => BinaryOp::Lt,
+                Some(Token::Le) => BinaryOp::Le,
+                Some(Token::Gt) => BinaryOp::Gt,
+                Some(Token::Ge) => BinaryOp::Ge,
+                _ => return Ok(left),
+            };
+            self.at += 1;
+            left = Expr::Binary(Box::new(left), op, Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.primary()?;
+        loop {
+            if self.eat(&Token::Dot) {
+                match self.next() {
+                    Some(Lexeme {
+                        token: Token::Ident(name),
+                        ..
+                    }) => expr = Expr::Property(Box::new(expr), name.clone()),
+                    // keywords are fine as property names
+                    Some(Lexeme {
+                        token: Token::Null,
+                        ..
+                    }) => expr = Expr::Property(Box::new(expr), "null".to_owned()),
+                    Some(Lexeme {
+                        token: Token::Bool(b),
+                        ..
+                    }) => expr = Expr::Property(Box::new(expr), b.to_string()),
+                    Some(Lexeme {
+                        token: Token::Star, ..
+                    }) => expr = Expr::Filter(Box::new(expr)),
+                    Some(l) => return Err(self.unexpected(l)),
+                    None => return Err(self.eof("expected a property name")),
+                }
+            } else if self.eat(&Token::LBracket) {
+                if self.eat(&Token::Star) {
+                    expr = Expr::Filter(Box::new(expr));
+                } else {
+                    let index = self.or()?;
+                    expr = Expr::Index(Box::new(expr), Box::new(index));
+                }
+                self.expect(Token::RBracket, "]")?;
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let lexeme = match self.next() {
+            Some(l) => l,
+            None => return Err(self.eof("expected an expression")),
+        };
+
+        match &lexeme.token {
+            Token::Null => Ok(Expr::Literal(Literal::Null)),
+            Token::Bool(b) => Ok(Expr::Literal(Literal::Bool(*b))),
+            Token::Number(n) => Ok(Expr::Literal(Literal::Number(*n))),
+            Token::Str(s) => Ok(Expr::Literal(Literal::Str(s.clone()))),
+            Token::LParen => {
+                let inner = self.or()?;
+                self.expect(Token::RParen, ")")?;
+                Ok(inner)
+            }
+            Token::Ident(name) if self.eat(&Token::LParen) => {
+                let mut args = Vec::new();
+                if !self.eat(&Token::RParen) {
+                    loop {
+                        args.push(self.or()?);
+                        if self.eat(&Token::RParen) {
+                            break;
+                        }
+                        self.expect(Token::Comma, ", or )")?;
+                    }
+                }
+                Ok(Expr::Call(name.clone(), args))
+            }
+            Token::Ident(name) => Ok(Expr::Context(name.clone())),
+            _ => Err(self.unexpected(lexeme)),
+        }
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Null => "null".to_owned(),
+        Token::Bool(b) => b.to_string(),
+        Token::Number(n) => n.to_string(),
+        Token::Str(s) => format!("'{s}'"),
+        Token::Ident(i) => i.clone(),
+        Token::Dot => ".".to_owned(),
+        Token::Star => "*".to_owned(),
+        Token::Comma => ",".to_owned(),
+        Token::LParen => "(".to_owned(),
+        Token::RParen => ")".to_owned(),
+        Token::LBracket => "[".to_owned(),
+        Token::RBracket => "]".to_owned(),
+        Token::Not => "!".to_owned(),
+        Token::Lt => "<".to_owned(),
+        Token::Le => "<=".to_owned(),
+        Token::Gt => ">".to_owned(),
+        Token::Ge => ">=".to_owned(),
+        Token::Eq => "==".to_owned(),
+        Token::Ne => "!=".to_owned(),
+        Token::And => "&&".to_owned(),
+        Token::Or => "||".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BinaryOp, Expr, Literal};
+
+    fn parsed(raw: &str) -> String {
+        Expr::parse(raw).unwrap().to_string()
+    }
+
+    #[test]
+    fn comparison_binds_tighter_than_equality() {
+        match Expr::parse("1 < 2 == true").unwrap() {
+            Expr::Binary(l, BinaryOp::Eq, r) => {
+                assert!(matches!(*l, Expr::Binary(_, BinaryOp::Lt, _)));
+                assert_eq!(*r, Expr::Literal(Literal::Bool(true)));
+            }
+            e => panic!("parsed as {e}"),
+        }
+    }
+
+    #[test]
+    fn precedence_is_kept_when_printed() {
+        assert_eq!(parsed("a || b && c == d"), "a || b && c == d");
+        assert_eq!(parsed("(a || b) && c"), "(a || b) && c");
+        assert_eq!(parsed("!(a == b)"), "!(a == b)");
+        assert_eq!(parsed("!a == b"), "!a == b");
+    }
+
+    #[test]
+    fn references() {
+        let expr = Expr::parse("github.event['pull_request'].labels.*.name").unwrap();
+        assert_eq!(
+            expr.path(),
+            Some(vec!["github", "event", "pull_request", "labels", "*", "name"])
+        );
+        assert!(expr.is_filtered());
+        assert_eq!(Expr::parse("matrix[env.key]").unwrap().path(), None);
+    }
+
+    #[test]
+    fn calls() {
+        let expr = Expr::parse("contains(github.ref, 'x') && Always()").unwrap();
+        assert!(expr.calls(&["always"]));
+        assert!(!expr.calls(&["success"]));
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        let err = Expr::parse("github.ref ==").unwrap_err();
+        assert_eq!((err.offset, err.reason.as_str()), (13, "expected an expression"));
+
+        let err = Expr::parse("a b").unwrap_err();
+        assert_eq!((err.offset, err.reason.as_str()), (2, "unexpected b"));
+
+        assert!(Expr::parse("f(a,").is_err());
+        assert!(Expr::parse("a[0").is_err());
+    }
+}
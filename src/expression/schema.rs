@@ -0,0 +1,289 @@
+use super::parser::{BinaryOp, Expr, Literal};
+use crate::lint::closest;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+// What a context or property is known to hold
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Any,
+    String,
+    Number,
+    Bool,
+    // only these properties exist
+    Object(BTreeMap<String, Shape>),
+    // any property name, each holding the same shape
+    Map(Box<Shape>),
+    Array(Box<Shape>),
+}
+
+impl Shape {
+    pub fn object<'a>(fields: impl IntoIterator<Item = (&'a str, Shape)>) -> Shape {
+        Shape::Object(fields.into_iter().map(|(k, v)| (k.to_owned(), v)).collect())
+    }
+
+    pub fn map(of: Shape) -> Shape {
+        Shape::Map(Box::new(of))
+    }
+
+    pub fn array(of: Shape) -> Shape {
+        Shape::Array(Box::new(of))
+    }
+
+    // anything either of them could hold
+    pub fn union(self, other: Shape) -> Shape {
+        match (self, other) {
+            (Shape::Object(mut a), Shape::Object(b)) => {
+                for (k, v) in b {
+                    let merged = match a.remove(&k) {
+                        Some(existing) => existing.union(v),
+                        None => v,
+                    };
+                    a.insert(k, merged);
+                }
+                Shape::Object(a)
+            }
+            (Shape::Map(a), Shape::Map(b)) => Shape::map(a.union(*b)),
+            (Shape::Array(a), Shape::Array(b)) => Shape::array(a.union(*b)),
+            (a, b) if a == b => a,
+            _ => Shape::Any,
+        }
+    }
+
+    fn field(&self, name: &str) -> Option<&Shape> {
+        match self {
+            Shape::Object(fields) => fields
+                .get(name)
+                .or_else(|| fields.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Shape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Shape::Any => write!(f, "anything"),
+            Shape::String => write!(f, "a string"),
+            Shape::Number => write!(f, "a number"),
+            Shape::Bool => write!(f, "a boolean"),
+            Shape::Object(_) | Shape::Map(_) => write!(f, "an object"),
+            Shape::Array(_) => write!(f, "an array"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    UnknownContext {
+        name: String,
+        suggestion: Option<String>,
+    },
+    // the context exists, just not where it's used
+    Unavailable {
+        name: String,
+    },
+    UnknownProperty {
+        path: String,
+        property: String,
+        suggestion: Option<String>,
+    },
+    NotAnObject {
+        path: String,
+        property: String,
+        shape: Shape,
+    },
+}
+
+impl Problem {
+    // what was referred to, eg. github.event.pull_request.titel
+    pub fn reference(&self) -> String {
+        match self {
+            Problem::UnknownContext { name, .. } | Problem::Unavailable { name } => name.clone(),
+            Problem::UnknownProperty { path, property, .. } | Problem::NotAnObject { path, property, .. } => {
+                format!("{path}.{property}")
+            }
+        }
+    }
+
+    // the context the problem is in
+    pub fn context(&self) -> String {
+        let reference = self.reference();
+        reference
+            .split(|c| c == '.' || c == '[')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::UnknownContext { name, suggestion } => {
+                write!(f, "there is no {name} context")?;
+                if let Some(s) = suggestion {
+                    write!(f, ", did you mean {s}?")?;
+                }
+                Ok(())
+            }
+            Problem::Unavailable { name } => write!(f, "the {name} context isn't available here"),
+            Problem::UnknownProperty {
+                path,
+                property,
+                suggestion,
+            } => {
+                write!(f, "{path} has no property {property}")?;
+                if let Some(s) = suggestion {
+                    write!(f, ", did you mean {path}.{s}?")?;
+                }
+                Ok(())
+            }
+            Problem::NotAnObject {
+                path,
+                property,
+                shape,
+            } => write!(f, "{path} is {shape} so it has no property {property}"),
+        }
+    }
+}
+
+// The shape of every context, and which of them can be used where the
+// expression is
+#[derive(Debug, Clone)]
+pub struct Schema {
+    contexts: BTreeMap<String, Shape>,
+    available: Vec<String>,
+}
+
+impl Schema {
+    pub fn new(contexts: BTreeMap<String, Shape>) -> Schema {
+        let available = contexts.keys().cloned().collect();
+        Schema {
+            contexts,
+            available,
+        }
+    }
+
+    pub fn available(mut self, names: &[&str]) -> Schema {
+        self.available = names.iter().map(|n| n.to_string()).collect();
+        self
+    }
+
+    pub fn check(&self, expr: &Expr) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        self.shape(expr, &mut problems);
+        problems
+    }
+
+    // what an expression evaluates to, as far as the schema knows
+    pub fn shape_of(&self, expr: &Expr) -> Shape {
+        self.shape(expr, &mut Vec::new())
+    }
+
+    fn shape(&self, expr: &Expr, problems: &mut Vec<Problem>) -> Shape {
+        match expr {
+            Expr::Literal(Literal::Null) => Shape::Any,
+            Expr::Literal(Literal::Bool(_)) => Shape::Bool,
+            Expr::Literal(Literal::Number(_)) => Shape::Number,
+            Expr::Literal(Literal::Str(_)) => Shape::String,
+            Expr::Context(name) => {
+                let lower = name.to_lowercase();
+                match self.contexts.get(&lower) {
+                    None => {
+                        problems.push(Problem::UnknownContext {
+                            name: name.clone(),
+                            suggestion: closest(name, self.contexts.keys().map(String::as_str))
+                                .map(ToOwned::to_owned),
+                        });
+                        Shape::Any
+                    }
+                    Some(_) if !self.available.contains(&lower) => {
+                        problems.push(Problem::Unavailable { name: name.clone() });
+                        Shape::Any
+                    }
+                    Some(shape) => shape.clone(),
+                }
+            }
+            Expr::Property(e, name) => {
+                let shape = self.shape(e, problems);
+                self.property(e, shape, name, problems)
+            }
+            Expr::Index(e, i) => {
+                let shape = self.shape(e, problems);
+                match i.as_ref() {
+                    Expr::Literal(Literal::Str(name)) => self.property(e, shape, name, problems),
+                    i => {
+                        self.shape(i, problems);
+                        match shape {
+                            Shape::Map(of) | Shape::Array(of) => *of,
+                            _ => Shape::Any,
+                        }
+                    }
+                }
+            }
+            Expr::Filter(e) => match self.shape(e, problems) {
+                Shape::Map(of) | Shape::Array(of) => Shape::Array(of),
+                Shape::Object(fields) => Shape::array(
+                    fields.into_values().reduce(Shape::union).unwrap_or(Shape::Any),
+                ),
+                _ => Shape::array(Shape::Any),
+            },
+            Expr::Not(e) => {
+                self.shape(e, problems);
+                Shape::Bool
+            }
+            Expr::Binary(l, op, r) => {
+                let left = self.shape(l, problems);
+                let right = self.shape(r, problems);
+                match op {
+                    BinaryOp::And | BinaryOp::Or => left.union(right),
+                    _ => Shape::Bool,
+                }
+            }
+            Expr::Call(name, args) => {
+                for a in args {
+                    self.shape(a, problems);
+                }
+                match name.to_lowercase().as_str() {
+                    "contains" | "startswith" | "endswith" | "success" | "failure" | "cancelled"
+                    | "always" => Shape::Bool,
+                    "format" | "join" | "tojson" | "hashfiles" => Shape::String,
+                    _ => Shape::Any,
+                }
+            }
+        }
+    }
+
+    fn property(&self, of: &Expr, shape: Shape, name: &str, problems: &mut Vec<Problem>) -> Shape {
+        let path = || of.path().map_or_else(|| of.to_string(), |p| p.join("."));
+
+        match shape {
+            Shape::Any => Shape::Any,
+            Shape::Map(of) => *of,
+            Shape::Object(ref fields) => match shape.field(name) {
+                Some(s) => s.clone(),
+                None => {
+                    problems.push(Problem::UnknownProperty {
+                        path: path(),
+                        property: name.to_owned(),
+                        suggestion: closest(name, fields.keys().map(String::as_str)).map(ToOwned::to_owned),
+                    });
+                    Shape::Any
+                }
+            },
+            // a filtered array of objects gives an array of their properties
+            Shape::Array(inner) if of.is_filtered() => {
+                Shape::array(self.property(of, *inner, name, problems))
+            }
+            shape => {
+                problems.push(Problem::NotAnObject {
+                    path: path(),
+                    property: name.to_owned(),
+                    shape,
+                });
+                Shape::Any
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+// An expression result. Unknown stands in for anything that depends on the
+// run, eg. a context that wasn't given, along with whatever is still known
+// about its truthiness.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(BTreeMap<String, Value>),
+    Unknown { what: String, truthy: Option<bool> },
+}
+
+impl Value {
+    pub fn unknown(what: impl Into<String>) -> Value {
+        Value::Unknown {
+            what: what.into(),
+            truthy: None,
+        }
+    }
+
+    // an unknown result that depends on the same thing as this one
+    pub fn unknown_like(&self) -> Value {
+        match self {
+            Value::Unknown { what, .. } => Value::unknown(what.as_str()),
+            v => Value::unknown(v.to_string()),
+        }
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Value::Unknown { .. })
+    }
+
+    // None when it depends on the run
+    pub fn truthy(&self) -> Option<bool> {
+        match self {
+            Value::Null => Some(false),
+            Value::Bool(b) => Some(*b),
+            Value::Number(n) => Some(*n != 0.0 && !n.is_nan()),
+            Value::String(s) => Some(!s.is_empty()),
+            Value::Array(_) | Value::Object(_) => Some(true),
+            Value::Unknown { truthy, .. } => *truthy,
+        }
+    }
+
+    // how comparisons see the value when the types differ
+    pub fn to_number(&self) -> f64 {
+        match self {
+            Value::Null => 0.0,
+            Value::Bool(b) => *b as u8 as f64,
+            Value::Number(n) => *n,
+            Value::String(s) if s.trim().is_empty() => 0.0,
+            Value::String(s) => super::lexer::parse_number(s.trim()).unwrap_or(f64::NAN),
+            _ => f64::NAN,
+        }
+    }
+
+    // how functions like contains and format see the value
+    pub fn to_text(&self) -> String {
+        match self {
+            Value::Null => String::new(),
+            Value::Bool(b) => b.to_string(),
+            Value::Number(n) => format_number(*n),
+            Value::String(s) => s.clone(),
+            Value::Array(_) => "Array".to_owned(),
+            Value::Object(_) => "Object".to_owned(),
+            Value::Unknown { what, .. } => format!("<{what}>"),
+        }
+    }
+
+    // case insensitive, like the contexts themselves
+    pub fn property(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Object(m) => m
+                .get(name)
+                .or_else(|| m.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v)),
+            _ => None,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value as Json;
+        match self {
+            Value::Null | Value::Unknown { .. } => Json::Null,
+            Value::Bool(b) => Json::Bool(*b),
+            // whole numbers are written without a .0, like format does
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => Json::from(*n as i64),
+            Value::Number(n) => serde_json::Number::from_f64(*n).map_or(Json::Null, Json::Number),
+            Value::String(s) => Json::String(s.clone()),
+            Value::Array(a) => Json::Array(a.iter().map(Value::to_json).collect()),
+            Value::Object(m) => Json::Object(m.iter().map(|(k, v)| (k.clone(), v.to_json())).collect()),
+        }
+    }
+}
+
+impl From<serde_json::Value> for Value {
+    fn from(json: serde_json::Value) -> Self {
+        use serde_json::Value as Json;
+        match json {
+            Json::Null => Value::Null,
+            Json::Bool(b) => Value::Bool(b),
+            Json::Number(n) => Value::Number(n.as_f64().unwrap_or(f64::NAN)),
+            Json::String(s) => Value::String(s),
+            Json::Array(a) => Value::Array(a.into_iter().map(Value::from).collect()),
+            Json::Object(m) => Value::Object(m.into_iter().map(|(k, v)| (k, Value::from(v))).collect()),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::String(s) => write!(f, "'{s}'"),
+            Value::Array(_) | Value::Object(_) => write!(f, "{}", self.to_json()),
+            Value::Unknown { what, .. } => write!(f, "{what}"),
+            v => write!(f, "{}", v.to_text()),
+        }
+    }
+}
+
+// whole numbers don't get a trailing .0
+fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        n.to_string()
+    }
+}
@@ -0,0 +1,160 @@
+use crate::action::is_release;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+const BUNDLED: &str = include_str!("deprecations.toml");
+
+// Everything GitHub has deprecated that possum knows about, kept as data in
+// deprecations.toml so that new deprecations don't need new code.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Deprecations {
+    #[serde(default)]
+    command: Vec<Command>,
+    #[serde(default)]
+    runtime: Vec<Runtime>,
+    #[serde(default)]
+    action: Vec<ActionRelease>,
+    #[serde(default)]
+    env: Vec<EnvVar>,
+    #[serde(default)]
+    runner: Vec<Runner>,
+}
+
+// a `::name` workflow command
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Command {
+    pub name: String,
+    pub replacement: String,
+    // the environment file that takes its place, for fixes
+    pub file: Option<String>,
+}
+
+// a value of runs.using
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Runtime {
+    pub using: String,
+    pub replacement: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ActionRelease {
+    pub uses: String,
+    pub versions: Vec<String>,
+    pub runtime: String,
+    pub replacement: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnvVar {
+    pub name: String,
+    pub replacement: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Runner {
+    pub label: String,
+    pub replacement: String,
+}
+
+pub fn deprecations() -> &'static Deprecations {
+    static BUNDLED_DEPRECATIONS: OnceLock<Deprecations> = OnceLock::new();
+    BUNDLED_DEPRECATIONS.get_or_init(|| toml::from_str(BUNDLED).expect("bundled deprecations are valid"))
+}
+
+impl Deprecations {
+    // the first disabled command echoed in text, and where it starts
+    pub fn command(&self, text: &str) -> Option<(usize, &Command)> {
+        self.command
+            .iter()
+            .filter_map(|c| {
+                let at = text.find(&format!("::{}", c.name))?;
+                // ::set-env is also the start of ::set-envoy, the name ends at a space or ::
+                let rest = &text[at + 2 + c.name.len()..];
+                (rest.is_empty() || rest.starts_with([' ', ':'])).then_some((at, c))
+            })
+            .min_by_key(|(at, _)| *at)
+    }
+
+    pub fn runtime(&self, using: &str) -> Option<&Runtime> {
+        self.runtime.iter().find(|r| r.using.eq_ignore_ascii_case(using))
+    }
+
+    pub fn action(&self, uses: &str) -> Option<&ActionRelease> {
+        self.action.iter().find(|a| is_release(uses, &a.uses, &a.versions))
+    }
+
+    pub fn env(&self, name: &str) -> Option<&EnvVar> {
+        self.env.iter().find(|e| e.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn runner(&self, label: &str) -> Option<&Runner> {
+        self.runner.iter().find(|r| r.label.eq_ignore_ascii_case(label))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{deprecations, Deprecations, BUNDLED};
+
+    #[test]
+    fn bundled_deprecations_parse() {
+        if let Err(e) = toml::from_str::<Deprecations>(BUNDLED) {
+            panic!("deprecations.toml: {e}");
+        }
+
+        let d = deprecations();
+        assert!(!d.command.is_empty());
+        assert!(!d.runtime.is_empty());
+        assert!(!d.action.is_empty());
+        assert!(!d.env.is_empty());
+        assert!(!d.runner.is_empty());
+
+        // every command a fix rewrites names a file to write to
+        for c in d.command.iter() {
+            assert!(c.file.as_deref().map_or(false, |f| f.starts_with("GITHUB_")), "{}", c.name);
+        }
+    }
+
+    #[test]
+    fn commands_end_at_a_space_or_colons() {
+        let d = deprecations();
+        let (at, c) = d.command("echo \"::set-output name=x::1\"").unwrap();
+        assert_eq!((at, c.name.as_str()), (6, "set-output"));
+        assert_eq!(d.command("echo ::save-state::x ::set-env name=y::z").unwrap().1.name, "save-state");
+        assert!(d.command("echo ::set-envoy").is_none());
+        assert!(d.command("echo set-output").is_none());
+    }
+
+    #[test]
+    fn lookups() {
+        let d = deprecations();
+        assert!(d.runtime("Node12").is_some());
+        assert!(d.runtime("node20").is_some());
+        assert!(d.runtime("node24").is_none());
+        assert_eq!(d.action("actions/checkout@v4.2.2").map(|a| a.runtime.as_str()), Some("node20"));
+        assert!(d.action("actions/cache@v3.3.1").is_some());
+        assert!(d.action("actions/cache@v30").is_none());
+        assert!(d.action("actions/cache").is_none());
+        assert!(d.env("actions_allow_unsecure_commands").is_some());
+        assert!(d.runner("ubuntu-18.04").is_some());
+        assert!(d.runner("ubuntu-latest").is_none());
+    }
+
+    #[test]
+    fn replacements_are_not_deprecated_themselves() {
+        let d = deprecations();
+        for r in d.runtime.iter() {
+            assert!(d.runtime(&r.replacement).is_none(), "{} -> {}", r.using, r.replacement);
+        }
+        for a in d.action.iter() {
+            assert!(d.runtime(&a.runtime).is_some(), "{} runs on {}", a.uses, a.runtime);
+            assert!(d.action(&a.replacement).is_none(), "{} -> {}", a.uses, a.replacement);
+        }
+    }
+}
@@ -0,0 +1,40 @@
+pub mod deprecations;
+pub mod rules;
+
+use crate::scavenge::ast::PossumNode;
+use crate::document::{Annotatable, AsDocumentPointer, Annotation};
+
+pub trait LintRule<T> {
+    fn lint(&self, root: &PossumNode<T>, annotations: &mut impl Annotatable);
+}
+
+pub trait LintViolation {
+    fn at(&self, loc: &impl AsDocumentPointer) -> Annotation;
+}
+
+// the candidate closest to word, if it's close enough to be a typo of it
+pub fn closest<'c>(word: &str, candidates: impl IntoIterator<Item = &'c str>) -> Option<&'c str> {
+    let word = word.to_lowercase();
+    candidates
+        .into_iter()
+        .map(|c| (distance(&word, &c.to_lowercase()), c))
+        .filter(|(d, _)| *d <= 2)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            cur[j + 1] = substitution.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+
+    prev[b.len()]
+}
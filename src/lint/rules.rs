@@ -0,0 +1,389 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum RuleCategory {
+    Syntax,
+    Correctness,
+    Security,
+    Style,
+}
+
+#[derive(Debug)]
+pub struct Rule {
+    id: &'static str,
+    category: RuleCategory,
+    title: &'static str,
+    help: &'static str,
+}
+
+const PREFIX: &str = "possum::";
+
+impl Rule {
+    // possum::some-rule
+    pub fn id(&self) -> &'static str {
+        self.id
+    }
+
+    // some-rule
+    pub fn name(&self) -> &'static str {
+        self.id.trim_start_matches(PREFIX)
+    }
+
+    pub fn category(&self) -> RuleCategory {
+        self.category
+    }
+
+    pub fn title(&self) -> &'static str {
+        self.title
+    }
+
+    pub fn help(&self) -> &'static str {
+        self.help
+    }
+
+    // rules may be referred to with or without the possum:: prefix
+    pub fn matches(&self, id: &str) -> bool {
+        let id = id.trim();
+        id == self.id || id == self.name()
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+impl PartialEq for Rule {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Rule {}
+
+macro_rules! rules {
+    {
+        $(
+            $name:ident {
+                id: $id:literal,
+                category: $category:ident,
+                title: $title:literal,
+                help: $help:literal $(,)?
+            }
+        )*
+    } => {
+        $(
+            pub static $name: Rule = Rule {
+                id: concat!("possum::", $id),
+                category: RuleCategory::$category,
+                title: $title,
+                help: $help,
+            };
+        )*
+
+        pub static RULES: &[&Rule] = &[$(&$name),*];
+    };
+}
+
+rules! {
+    UNEXPECTED_YAML {
+        id: "unexpected-yaml",
+        category: Syntax,
+        title: "value has the wrong yaml type",
+        help: "GitHub expects a different kind of value here, e.g. a map where a string was provided",
+    }
+    UNEXPECTED_KEY {
+        id: "unexpected-key",
+        category: Syntax,
+        title: "key is not recognized",
+        help: "GitHub ignores or rejects keys it does not know about, check for typos",
+    }
+    MISSING_PROPERTY {
+        id: "missing-property",
+        category: Correctness,
+        title: "required property is missing",
+        help: "workflows need both `on` and `jobs` and actions need `name`, `description` and `runs`",
+    }
+    EMPTY_JOBS {
+        id: "empty-jobs",
+        category: Correctness,
+        title: "workflow has no jobs",
+        help: "a workflow without jobs is rejected by GitHub, add a job or remove the workflow",
+    }
+    MISSING_LOCAL_ACTION {
+        id: "missing-local-action",
+        category: Correctness,
+        title: "local action does not exist",
+        help: "`uses: ./path` must point at a directory in the repository containing action.yml or action.yaml",
+    }
+    UNKNOWN_ACTION_INPUT {
+        id: "unknown-action-input",
+        category: Correctness,
+        title: "action does not declare this input",
+        help: "check `with:` against the inputs the action declares, the input may have been renamed or removed",
+    }
+    MISSING_ACTION_INPUT {
+        id: "missing-action-input",
+        category: Correctness,
+        title: "required action input is not provided",
+        help: "provide the input in `with:`, it is required and has no default",
+    }
+    DEPRECATED_ACTION_INPUT {
+        id: "deprecated-action-input",
+        category: Style,
+        title: "action input is deprecated",
+        help: "the action marks this input with a deprecationMessage, follow its advice",
+    }
+    MISSING_PERMISSIONS {
+        id: "missing-permissions",
+        category: Security,
+        title: "workflow does not set permissions",
+        help: "add a top level `permissions:` block granting only what the jobs need, `permissions: {}` grants nothing",
+    }
+    WRITE_ALL_PERMISSIONS {
+        id: "write-all-permissions",
+        category: Security,
+        title: "permissions grant write-all",
+        help: "list the scopes that need write access instead of granting it to all of them",
+    }
+    UNUSED_WRITE_PERMISSION {
+        id: "unused-write-permission",
+        category: Security,
+        title: "write permission appears to be unused",
+        help: "nothing in the job looks like it needs write access to this scope, grant read or remove it",
+    }
+    UNKNOWN_PERMISSION_SCOPE {
+        id: "unknown-permission-scope",
+        category: Correctness,
+        title: "unknown permission scope",
+        help: "use one of the scopes GITHUB_TOKEN can be granted, the finding lists them",
+    }
+    UNUSED_ID_TOKEN {
+        id: "unused-id-token-permission",
+        category: Security,
+        title: "id-token: write is granted but never used",
+        help: "only grant id-token: write to jobs that log in with OIDC, such as through cloud credential actions",
+    }
+    EXPOSED_ID_TOKEN {
+        id: "exposed-id-token",
+        category: Security,
+        title: "OIDC tokens can be minted by untrusted code",
+        help: "avoid id-token: write on pull_request_target and workflow_run, and pin every action in the job to a commit sha",
+    }
+    SECRET_IN_RUN {
+        id: "secret-in-run",
+        category: Security,
+        title: "secret interpolated into a script",
+        help: "set the secret in the step's `env:` and read the environment variable in the script, interpolated values end up in the script on disk and in process listings",
+    }
+    SECRET_TO_UNPINNED_ACTION {
+        id: "secret-to-unpinned-action",
+        category: Security,
+        title: "secret passed to an unpinned third party action",
+        help: "pin third party actions that receive secrets to a full commit sha, or add their owner to trusted-action-owners",
+    }
+    INHERITED_SECRETS {
+        id: "inherited-secrets",
+        category: Security,
+        title: "every secret is passed to a workflow outside the repository",
+        help: "pass only the secrets the called workflow needs with `secrets:` instead of `secrets: inherit`",
+    }
+    SECRETS_ON_FORK_TRIGGER {
+        id: "secrets-on-fork-trigger",
+        category: Security,
+        title: "secrets are used by a job forks can trigger",
+        help: "pull_request_target and workflow_run run with secrets for changes from forks, keep secrets out of jobs that handle fork contents",
+    }
+    UNDECLARED_SECRET {
+        id: "undeclared-secret",
+        category: Correctness,
+        title: "secret is not declared by workflow_call",
+        help: "declare the secret under `on.workflow_call.secrets` so that callers can pass it",
+    }
+    UNKNOWN_RUNNER_LABEL {
+        id: "unknown-runner-label",
+        category: Correctness,
+        title: "no runner has this label",
+        help: "use a GitHub hosted runner label or add self hosted labels to runner-labels in .possum.toml, jobs that match no runner are queued until they time out",
+    }
+    DEPRECATED_RUNNER {
+        id: "deprecated-runner",
+        category: Correctness,
+        title: "runner image is deprecated",
+        help: "GitHub retires old runner images, jobs using them fail once the image is removed",
+    }
+    SELF_HOSTED_PUBLIC_TRIGGER {
+        id: "self-hosted-public-trigger",
+        category: Security,
+        title: "self hosted runner used by a publicly triggered workflow",
+        help: "anyone who can open a pull request or issue can run code on self hosted runners used by these workflows, use GitHub hosted runners instead",
+    }
+    MISSING_TIMEOUT {
+        id: "missing-timeout",
+        category: Correctness,
+        title: "job has no timeout",
+        help: "set `timeout-minutes` on the job, otherwise a stuck job runs for 6 hours",
+    }
+    EXCESSIVE_TIMEOUT {
+        id: "excessive-timeout",
+        category: Correctness,
+        title: "timeout is above the configured maximum",
+        help: "lower `timeout-minutes` or raise max-timeout-minutes in .possum.toml",
+    }
+    INVALID_TIMEOUT {
+        id: "invalid-timeout",
+        category: Correctness,
+        title: "timeout is not a positive whole number",
+        help: "`timeout-minutes` must be a whole number of minutes greater than zero",
+    }
+    STEP_TIMEOUT_EXCEEDS_JOB {
+        id: "step-timeout-exceeds-job",
+        category: Correctness,
+        title: "step timeout is longer than its job's",
+        help: "the job is cancelled when its own timeout runs out, so the step's timeout never applies",
+    }
+    SHARED_CONCURRENCY_GROUP {
+        id: "shared-concurrency-group",
+        category: Correctness,
+        title: "workflows share a concurrency group",
+        help: "concurrency groups are shared by the whole repository, include `${{ github.workflow }}` in the group so workflows don't wait on or cancel each other",
+    }
+    SERIALIZED_CONCURRENCY {
+        id: "serialized-concurrency",
+        category: Correctness,
+        title: "concurrency group is shared by every branch",
+        help: "include `${{ github.ref }}` or similar in the group so that each branch or pull request gets a group of its own",
+    }
+    CANCELLED_RELEASE {
+        id: "cancelled-release",
+        category: Correctness,
+        title: "release or deployment can be cancelled part way through",
+        help: "drop `cancel-in-progress: true` from release and deployment workflows so newer runs queue instead of interrupting",
+    }
+    CONFLICTING_FILTERS {
+        id: "conflicting-filters",
+        category: Correctness,
+        title: "filter and its -ignore counterpart used together",
+        help: "GitHub rejects events with both branches and branches-ignore, tags and tags-ignore, or paths and paths-ignore, use ! patterns instead",
+    }
+    IGNORED_FILTER {
+        id: "ignored-filter",
+        category: Correctness,
+        title: "event ignores this filter",
+        help: "paths only apply to push and pull_request events, tags only to push and branches to push, pull_request and workflow_run",
+    }
+    INVALID_FILTER {
+        id: "invalid-filter",
+        category: Syntax,
+        title: "filter pattern is not valid",
+        help: "filters support *, **, ?, +, [] and a leading !, escape special characters with \\",
+    }
+    NEGATION_FIRST {
+        id: "negation-first",
+        category: Correctness,
+        title: "negated pattern has nothing to exclude",
+        help: "patterns apply in order, so a ! pattern only excludes what earlier patterns included",
+    }
+    UNMATCHABLE_FILTER {
+        id: "unmatchable-filter",
+        category: Correctness,
+        title: "filter pattern can never match",
+        help: "branch and tag filters match names without refs/heads/ or refs/tags/, path filters are relative to the repository root",
+    }
+    INVALID_EXPRESSION {
+        id: "invalid-expression",
+        category: Syntax,
+        title: "expression can't be parsed or evaluated",
+        help: "see https://docs.github.com/en/actions/learn-github-actions/expressions for the expression syntax and functions",
+    }
+    CONSTANT_CONDITION {
+        id: "constant-condition",
+        category: Correctness,
+        title: "condition is always true or always false",
+        help: "remove the `if` or the job or step, text around `${{ }}` in an `if` makes it a string that is always true",
+    }
+    UNKNOWN_CONTEXT {
+        id: "unknown-context",
+        category: Correctness,
+        title: "expression refers to a context or property that doesn't exist",
+        help: "unknown properties evaluate to an empty string, check the spelling against https://docs.github.com/en/actions/learn-github-actions/contexts",
+    }
+    UNAVAILABLE_CONTEXT {
+        id: "unavailable-context",
+        category: Correctness,
+        title: "context isn't available where it is used",
+        help: "see https://docs.github.com/en/actions/learn-github-actions/contexts#context-availability for which contexts each key can use",
+    }
+    UNDEFINED_MATRIX_KEY {
+        id: "undefined-matrix-key",
+        category: Correctness,
+        title: "matrix key isn't defined by the job's matrix",
+        help: "a key that isn't in `strategy.matrix` or added by its `include` is an empty string",
+    }
+    UNDECLARED_INPUT {
+        id: "undeclared-input",
+        category: Correctness,
+        title: "input isn't declared by the workflow",
+        help: "declare the input under `on.workflow_dispatch.inputs` or `on.workflow_call.inputs`, undeclared inputs are empty",
+    }
+    STRING_INPUT_AS_BOOLEAN {
+        id: "string-input-as-boolean",
+        category: Correctness,
+        title: "input is compared as the wrong type",
+        help: "github.event.inputs are always strings while inputs keep their declared type, prefer inputs.NAME for boolean inputs",
+    }
+    UNQUOTED_EXPRESSION {
+        id: "unquoted-expression",
+        category: Security,
+        title: "expression is substituted into a script without quotes",
+        help: "the value is pasted into the script before the shell runs it, pass it through `env:` and use \"$NAME\" instead",
+    }
+    MISSING_SET_E {
+        id: "missing-set-e",
+        category: Correctness,
+        title: "script keeps going after a command fails",
+        help: "a custom `shell:` doesn't stop on errors, add `set -e` or use `shell: bash`",
+    }
+    UNSANITIZED_ENV_WRITE {
+        id: "unsanitized-env-write",
+        category: Security,
+        title: "untrusted input is written to $GITHUB_ENV, $GITHUB_OUTPUT or $GITHUB_PATH",
+        help: "a value containing a newline can set any variable for later steps, write it with a random heredoc delimiter or strip newlines first",
+    }
+    DEPRECATED_WORKFLOW_COMMAND {
+        id: "deprecated-workflow-command",
+        category: Correctness,
+        title: "script uses a deprecated workflow command",
+        help: "GitHub has turned off the commands that stdout could be used to inject, use the environment files each message points to",
+    }
+    DEPRECATED_ACTION_RUNTIME {
+        id: "deprecated-action-runtime",
+        category: Correctness,
+        title: "action runs on a Node version GitHub has retired",
+        help: "runners force old actions onto a newer Node, which they may not work on, upgrade to a release built for a supported Node",
+    }
+    DEPRECATED_ENV_VAR {
+        id: "deprecated-env-var",
+        category: Security,
+        title: "environment variable turns deprecated behaviour back on",
+        help: "ACTIONS_ALLOW_UNSECURE_COMMANDS lets anything printed by a step set environment variables and PATH",
+    }
+    UNQUOTED_ON_KEY {
+        id: "unquoted-on-key",
+        category: Style,
+        title: "`on` key isn't quoted",
+        help: "YAML 1.1 tools like yamllint and PyYAML read a bare on as true, write \"on\": so every tool agrees",
+    }
+    UNUSED_SUPPRESSION {
+        id: "unused-suppression",
+        category: Style,
+        title: "suppression comment does not suppress anything",
+        help: "remove the `# possum: ignore[...]` comment or correct the rule id it names",
+    }
+}
+
+pub fn find(id: &str) -> Option<&'static Rule> {
+    RULES.iter().find(|r| r.matches(id)).map(|r| *r)
+}
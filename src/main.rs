@@ -2,32 +2,272 @@
  * CLI Commands:
  *
  * possum lint/hiss <directory | .>
- * possum search/rummage <directory | .>
+ * possum search/rummage permissions|concurrency|simulate <directory | .>
+ * possum rules
+ *
+ * hiss exits 1 when errors are left, 2 when possum itself fails
  */
+mod action;
 mod cli;
+mod config;
 mod document;
+mod expression;
 mod lint;
 mod project;
 mod scavenge;
+mod shell;
 mod workflow;
 
 use clap::Parser;
-use cli::render::{DebugRender, OneLineRender};
-use cli::Arguments;
+use cli::render::{
+    ConcurrencyRender, DebugRender, DiffRender, OneLineRender, PermissionsRender, RulesRender,
+    SimulateRender,
+};
+use cli::{Arguments, Command, HissArguments, ProjectArguments, RummageArguments, RummageCommand};
+use config::Config;
+use document::{Annotation, AnnotationLevel};
+use project::baseline::Baseline;
 use project::builder::build;
+use project::discovery::Discovery;
+use project::{InitFailure, Project, ProjectEntry};
+use std::process::ExitCode;
+
+// findings were left to fix
+const FINDINGS: u8 = 1;
+// possum couldn't do what it was asked, clap uses the same code for bad arguments
+const FAILED: u8 = 2;
+
+fn main() -> ExitCode {
+    let args = Arguments::parse();
+
+    match args.command {
+        Command::Hiss(hiss) => run_hiss(hiss),
+        Command::Rummage(rummage) => run_rummage(rummage),
+        Command::Rules => {
+            print!("{}", RulesRender(lint::rules::RULES));
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+fn run_hiss(args: HissArguments) -> ExitCode {
+    let config = match load_config(&args.project) {
+        Some(config) => config,
+        None => return ExitCode::from(FAILED),
+    };
+
+    let mut proj = match load_project(&args.project, &config) {
+        Some(proj) => proj,
+        None => return ExitCode::from(FAILED),
+    };
+
+    if let Some(path) = args.write_baseline {
+        let baseline = Baseline::from_project(&proj);
+        return match baseline.write(&path) {
+            Ok(()) => {
+                println!("recorded {} findings in {}", baseline.len(), path.display());
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                ExitCode::from(FAILED)
+            }
+        };
+    }
+
+    let fixed = match args.baseline.map(Baseline::load) {
+        None => Vec::new(),
+        Some(Ok(baseline)) => baseline.ratchet(&mut proj),
+        Some(Err(err)) => {
+            eprintln!("{}", err);
+            return ExitCode::from(FAILED);
+        }
+    };
+
+    // counted before the renderers take the project, --fix leaves only what it can't fix
+    let left = if args.fix {
+        failing(&proj, |a| a.fix(args.unsafe_fixes).is_empty())
+    } else {
+        failing(&proj, |_| true)
+    };
+
+    let mut code = if left {
+        ExitCode::from(FINDINGS)
+    } else {
+        ExitCode::SUCCESS
+    };
+
+    if args.fix || args.diff {
+        if !fix(&proj, args.fix, args.unsafe_fixes) {
+            code = ExitCode::from(FAILED);
+        }
+    } else if args.one_line {
+        print!("{}", OneLineRender(proj));
+    } else {
+        print!("{}", DebugRender(proj));
+    }
+
+    for f in fixed {
+        println!("fixed since baseline: {}", f);
+    }
+
+    code
+}
+
+// Whether CI should fail: a document that couldn't be parsed, or an error
+// that survived suppressions, the configuration and the baseline. Warnings
+// and info are only reported. A fix that conflicts with another is counted
+// as fixed and is caught by the next run instead.
+fn failing(proj: &Project, left: impl Fn(&Annotation) -> bool) -> bool {
+    proj.entries().any(|e| match e {
+        ProjectEntry::ParseFailure(..) => true,
+        _ => e.document().map_or(false, |(_, _, annotations)| {
+            annotations.entries().any(|a| {
+                matches!(a.level(), AnnotationLevel::Error | AnnotationLevel::Fatal) && left(a)
+            })
+        }),
+    })
+}
+
+// findings that are suppressed, disabled or in the baseline aren't fixed,
+// false if any of the fixed files couldn't be written
+fn fix(proj: &Project, write: bool, unsafe_fixes: bool) -> bool {
+    let mut written = true;
+    let mut held_back = 0;
+    for (source, document, annotations) in proj.entries().filter_map(|e| e.document()) {
+        let fixes: Vec<_> = annotations
+            .entries()
+            .map(|a| a.fix(unsafe_fixes))
+            .filter(|edits| !edits.is_empty())
+            .collect();
+        held_back += annotations
+            .entries()
+            .filter(|a| !a.edits().is_empty() && a.fix(unsafe_fixes).is_empty())
+            .count();
+        if fixes.is_empty() {
+            continue;
+        }
+
+        let (fixed, applied) = document.apply(fixes.iter().copied());
+        if !write {
+            // diff paths are relative so the output can be given to git apply
+            let cwd = std::env::current_dir().unwrap_or_default();
+            let path = source.strip_prefix(&cwd).unwrap_or(source);
+            print!("{}", DiffRender(path, document.raw(), &fixed));
+            continue;
+        }
 
-fn main() {
-    let args = Arguments::try_parse().unwrap();
-    let project = build(args.directory);
+        match std::fs::write(source, fixed) {
+            Ok(()) => println!("fixed {} of {} findings in {}", applied, fixes.len(), source.display()),
+            Err(err) => {
+                eprintln!("couldn't write {}: {}", source.display(), err);
+                written = false;
+            }
+        }
+    }
+
+    if held_back > 0 {
+        eprintln!("{held_back} more fixes might change what a workflow does, --unsafe-fixes applies them");
+    }
+
+    written
+}
+
+fn run_rummage(args: RummageArguments) -> ExitCode {
+    let project = |args: &ProjectArguments| {
+        load_config(args).and_then(|config| load_project(args, &config))
+    };
+
+    let shown = match args.command {
+        RummageCommand::Permissions(args) => {
+            project(&args).map(|proj| print!("{}", PermissionsRender(&proj)))
+        }
+        RummageCommand::Concurrency(args) => {
+            project(&args).map(|proj| print!("{}", ConcurrencyRender(&proj)))
+        }
+        RummageCommand::Simulate(args) => match args.context() {
+            Ok(context) => project(&args.project)
+                .map(|proj| print!("{}", SimulateRender(&proj, &args.event(), &context))),
+            Err(err) => {
+                eprintln!("{}", err);
+                None
+            }
+        },
+    };
+
+    match shown {
+        Some(()) => ExitCode::SUCCESS,
+        None => ExitCode::from(FAILED),
+    }
+}
 
-    match project {
-        Err(err) => println!("{:#?}", err),
+fn load_config(args: &ProjectArguments) -> Option<Config> {
+    let config = match &args.config {
+        Some(path) => Config::load(path),
+        None => Config::discover(args.root().dir()),
+    };
+
+    match config {
+        Ok(config) => Some(config),
+        Err(err) => {
+            eprintln!("{}", err);
+            None
+        }
+    }
+}
+
+fn load_project(args: &ProjectArguments, config: &Config) -> Option<Project> {
+    let catalogue = match config.catalogue() {
+        Ok(catalogue) => catalogue,
+        Err(err) => {
+            eprintln!("{}", err);
+            return None;
+        }
+    };
+
+    let discovery = match discover(args, config) {
+        Ok(discovery) => discovery,
+        Err(err) => {
+            eprintln!("{}", err);
+            return None;
+        }
+    };
+
+    match build(args.root(), &discovery, config, &catalogue) {
+        Err(err) => {
+            eprintln!("{}", err);
+            None
+        }
         Ok(proj) => {
-            if args.one_line {
-                print!("{}", OneLineRender(proj));
-            } else {
-                print!("{}", DebugRender(proj));
+            for (p, why) in proj.skipped() {
+                eprintln!("skipped {}: {}", p.display(), why);
             }
+            Some(proj)
         }
     }
 }
+
+// command line patterns replace the configured includes and add to its excludes
+fn discover(args: &ProjectArguments, config: &Config) -> Result<Discovery, InitFailure> {
+    let configured = config.discovery();
+    let include = if args.include.is_empty() {
+        &configured.include
+    } else {
+        &args.include
+    };
+    let exclude = configured.exclude.iter().chain(args.exclude.iter());
+
+    let root = args.root();
+    let discovery =
+        Discovery::new(include, exclude).map_err(|e| InitFailure::bad_pattern(&root, e))?;
+
+    if args.stdin {
+        discovery
+            .read_explicit(std::io::stdin().lock())
+            .map_err(|e| InitFailure::unreadable_stdin(&root, e))
+    } else if !args.files.is_empty() {
+        Ok(discovery.explicit(args.files.clone()))
+    } else {
+        Ok(discovery)
+    }
+}
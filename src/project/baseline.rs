@@ -0,0 +1,208 @@
+use super::{Project, ProjectRoot};
+use crate::document::{Annotation, Document};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+pub const BASELINE_FILE: &str = ".possum-baseline.toml";
+
+// Findings are fingerprinted by where they are in the document's structure
+// rather than by line, so unrelated edits don't invalidate the baseline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    rule: String,
+    file: String,
+    path: String,
+}
+
+impl Fingerprint {
+    fn new(file: &str, document: &Document, a: &Annotation) -> Fingerprint {
+        Fingerprint {
+            rule: a.rule().id().to_owned(),
+            file: file.to_owned(),
+            path: document.path_of(a).unwrap_or("$").to_owned(),
+        }
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} at {}", self.file, self.rule, self.path)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    #[serde(default, rename = "finding")]
+    findings: Vec<Fingerprint>,
+}
+
+#[derive(Debug)]
+pub struct BaselineError {
+    path: PathBuf,
+    reason: String,
+}
+
+impl BaselineError {
+    fn new(p: impl Into<PathBuf>, reason: impl ToString) -> BaselineError {
+        BaselineError {
+            path: p.into(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl Display for BaselineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.reason)
+    }
+}
+
+impl std::error::Error for BaselineError {}
+
+// documents are recorded relative to the project so the baseline can be checked in
+fn relative_source(root: &ProjectRoot, source: &Path) -> String {
+    let root = root.dir();
+    source
+        .strip_prefix(&root)
+        .unwrap_or(source)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+impl Baseline {
+    pub fn from_project(project: &Project) -> Baseline {
+        let mut findings = Vec::new();
+
+        for entry in project.entries() {
+            if let Some((source, document, annotations)) = entry.document() {
+                let file = relative_source(project.root(), source);
+                findings.extend(
+                    annotations
+                        .entries()
+                        .map(|a| Fingerprint::new(&file, document, a)),
+                );
+            }
+        }
+
+        Baseline { findings }
+    }
+
+    pub fn len(&self) -> usize {
+        self.findings.len()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Baseline, BaselineError> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path).map_err(|e| BaselineError::new(path, e))?;
+        toml::from_str(&raw).map_err(|e| BaselineError::new(path, e))
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), BaselineError> {
+        let path = path.as_ref();
+        let raw = toml::to_string(self).map_err(|e| BaselineError::new(path, e))?;
+        std::fs::write(path, raw).map_err(|e| BaselineError::new(path, e))
+    }
+
+    // drops every finding already in the baseline from the project and returns
+    // the baseline entries that no longer occur
+    pub fn ratchet(mut self, project: &mut Project) -> Vec<Fingerprint> {
+        let root = project.root().clone();
+
+        for entry in project.entries_mut() {
+            if let Some((source, document, annotations)) = entry.document_mut() {
+                let file = relative_source(&root, source);
+                annotations.retain(|a| {
+                    let fingerprint = Fingerprint::new(&file, document, a);
+                    match self.findings.iter().position(|f| *f == fingerprint) {
+                        Some(idx) => {
+                            self.findings.swap_remove(idx);
+                            false
+                        }
+                        None => true,
+                    }
+                });
+            }
+        }
+
+        self.findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Baseline;
+    use crate::config::Options;
+    use crate::document::{Document, DocumentPointer};
+    use crate::lint::LintRule;
+    use crate::project::{Project, ProjectEntry, ProjectRoot};
+    use crate::scavenge::ast::PossumNodeKind;
+    use crate::scavenge::yaml::node_paths;
+    use crate::workflow::lints::test_lint;
+    use crate::workflow::lints::timeouts::Timeouts;
+    use crate::workflow::parser::test_workflow;
+    use std::path::PathBuf;
+
+    // a project of one workflow, with a missing timeout for each of its jobs
+    fn project(raw: &str) -> Project {
+        let (root, source) = crate::scavenge::parser::test_document(raw);
+        let options = Options::default();
+        let mut project = Project::new(ProjectRoot::Explicit(PathBuf::from("/repo")));
+        project.push(ProjectEntry::Workflow {
+            source: PathBuf::from("/repo/.github/workflows/ci.yml"),
+            document: Document::new(raw).with_paths(node_paths(source, &root)),
+            annotations: test_lint(raw, |wf, _, a| Timeouts(&options).lint(wf, a)),
+            workflow: PossumNodeKind::Value(test_workflow(raw)).at(&DocumentPointer(0)),
+        });
+        project
+    }
+
+    // the jobs left with a finding
+    fn left(project: &Project) -> Vec<String> {
+        let (_, document, annotations) = project.entries().next().unwrap().document().unwrap();
+        annotations
+            .entries()
+            .map(|a| document.path_of(a).unwrap_or("$").to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn fingerprints_ignore_lines_and_unrelated_keys() {
+        let before = "on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n  test:\n    runs-on: ubuntu-latest\n";
+        let baseline = Baseline::from_project(&project(before));
+        assert_eq!(baseline.len(), 2);
+
+        let after = "# ci\nname: CI\non: [push, pull_request]\nenv:\n  CI: true\njobs:\n\n  test:\n    runs-on: macos-latest\n    steps:\n      - run: make test\n  build:\n    runs-on: ubuntu-latest\n  lint:\n    runs-on: ubuntu-latest\n";
+        let mut after = project(after);
+        let fixed = baseline.ratchet(&mut after);
+        assert!(fixed.is_empty(), "{fixed:?}");
+        assert_eq!(left(&after), ["$.jobs.lint"]);
+    }
+
+    #[test]
+    fn findings_that_are_gone_are_returned() {
+        let before = "on: push\njobs:\n  build: {}\n  test: {}\n";
+        let baseline = Baseline::from_project(&project(before));
+
+        let mut after = project("on: push\njobs:\n  build: {}\n  test:\n    timeout-minutes: 10\n");
+        let fixed: Vec<_> = baseline.ratchet(&mut after).iter().map(ToString::to_string).collect();
+        assert_eq!(fixed, [".github/workflows/ci.yml: possum::missing-timeout at $.jobs.test"]);
+        assert!(left(&after).is_empty());
+    }
+
+    #[test]
+    fn baselines_round_trip_through_their_file() {
+        let baseline = Baseline::from_project(&project("on: push\njobs:\n  build: {}\n  test: {}\n"));
+        let path = std::env::temp_dir().join(format!("possum-baseline-{}.toml", std::process::id()));
+        baseline.write(&path).unwrap();
+        let raw = std::fs::read_to_string(&path).unwrap();
+        let loaded = Baseline::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(raw.contains("[[finding]]"), "{raw}");
+        assert!(raw.contains("file = \".github/workflows/ci.yml\""), "{raw}");
+        assert_eq!(loaded.unwrap().findings, baseline.findings);
+    }
+}
@@ -1,14 +1,25 @@
-use super::{Project, ProjectEntry, InitFailure};
-use crate::document::{Annotations, Document};
-use crate::scavenge::{parse_single_document as possum_parse, ParseFailure};
+use super::{calls, concurrency, Project, ProjectEntry, InitFailure};
+use super::discovery::{Discovered, Discovery};
+use crate::config::{Config, Settings};
+use crate::document::{Annotations, Document, Suppressions};
+use crate::scavenge::yaml::{node_paths, Source};
+use crate::scavenge::{load_single_document, ParseFailure, Parser};
+use crate::action::catalogue::Catalogue;
+use crate::action::lints::lint_action;
+use crate::action::{is_action_metadata, ActionParser};
+use crate::workflow::lints::lint_workflow;
 use crate::workflow::WorkflowParser;
-use std::ffi::OsStr;
 use std::path::PathBuf;
 use yaml_peg::parser::Loader;
 use yaml_peg::repr::RcRepr;
 use super::ProjectRoot;
 
-pub fn build(root: ProjectRoot) -> Result<Project, InitFailure> {
+pub fn build(
+    root: ProjectRoot,
+    discovery: &Discovery,
+    config: &Config,
+    catalogue: &Catalogue,
+) -> Result<Project, InitFailure> {
     if !root.exists() {
         Err(InitFailure::dir_not_found(&root))?;
     }
@@ -17,52 +28,128 @@ pub fn build(root: ProjectRoot) -> Result<Project, InitFailure> {
         Err(InitFailure::not_dir(&root))?;
     }
 
-    let workflows = get_all_workflows(&root);
+    let documents = discovery.discover(&root.dir());
+    let selected = documents.iter().filter(|d| !matches!(d, Discovered::Skipped(..))).count();
 
-    if workflows.is_empty() {
+    // a pre-commit hook that only passes unrelated files has nothing to check,
+    // which isn't the same as a project without any workflows
+    if selected == 0 && !discovery.is_explicit() {
         Err(InitFailure::no_workflows(&root))?;
     }
 
     let mut project = Project::new(root);
 
-    for p in workflows {
-        match std::fs::read(&p) {
-            Ok(raw) => {
-                let mut annotations = Annotations::new();
-                let loader: Loader<'_, RcRepr> = yaml_peg::parser::Loader::new(&raw);
-                let mut parser = WorkflowParser::new(&mut annotations);
-                match possum_parse(loader, &mut parser) {
-                    Ok(workflow) => {
-                        project.push(ProjectEntry::Workflow {
-                            source: p,
-                            annotations,
-                            workflow,
-                            document: Document::new(raw),
-                        });
-                    }
-                    Err(pf) => {
-                        project.push(ProjectEntry::ParseFailure(p, pf));
-                    }
-                }
+    for d in documents {
+        match d {
+            Discovered::Found(p) => {
+                let settings = config.settings_for(&p);
+                project.push(load_document(p, &settings));
             }
-            Err(e) => {
+            Discovered::Unreadable(p, e) => {
                 project.push(ProjectEntry::ParseFailure(p, ParseFailure::CouldntOpen(e)));
             }
+            Discovered::Skipped(p, why) => project.skip(p, why),
+        }
+    }
+
+    // some lints need to see more than one document at a time
+    calls::lint_action_calls(&mut project, catalogue);
+    concurrency::lint_shared_groups(&mut project);
+
+    for entry in project.entries_mut() {
+        if let Some((source, document, annotations)) = entry.document_mut() {
+            finish(document, &config.settings_for(source), annotations);
         }
     }
 
     Ok(project)
 }
 
-fn get_all_workflows(root: impl Into<PathBuf>) -> Vec<PathBuf> {
-    std::fs::read_dir(root.into())
-        .unwrap()
-        .map(|d| d.unwrap().path())
-        .filter(|p| {
-            p.extension()
-                .and_then(OsStr::to_str)
-                .and_then(|ext| Some(ext.ends_with("yaml") || ext.ends_with("yml")))
-                .unwrap()
-        })
-        .collect()
+pub(crate) fn load_document(p: PathBuf, settings: &Settings) -> ProjectEntry {
+    let raw = match std::fs::read(&p) {
+        Ok(raw) => raw,
+        Err(e) => return ProjectEntry::ParseFailure(p, ParseFailure::CouldntOpen(e)),
+    };
+
+    parse_document(p, &raw, settings)
+}
+
+fn parse_document(p: PathBuf, raw: &[u8], settings: &Settings) -> ProjectEntry {
+    let mut annotations = Annotations::new();
+    let loader: Loader<'_, RcRepr> = yaml_peg::parser::Loader::new(raw);
+    let root = match load_single_document(loader) {
+        Ok(root) => root,
+        Err(pf) => return ProjectEntry::ParseFailure(p, pf),
+    };
+
+    // nodes only know where they start, their spans are read from the document
+    let source = Source::new(raw);
+    let document = Document::new(raw).with_paths(node_paths(source, &root));
+
+    if is_action_metadata(&p) {
+        let action = ActionParser::new(&mut annotations)
+            .parse_node(&root, source)
+            .at(&source.span(&root));
+        lint_action(&action, settings.options(), &mut annotations);
+        ProjectEntry::Action {
+            source: p,
+            annotations,
+            action,
+            document,
+        }
+    } else {
+        let workflow = WorkflowParser::new(&mut annotations)
+            .parse_node(&root, source)
+            .at(&source.span(&root));
+        lint_workflow(&workflow, &document, settings.options(), &mut annotations);
+        ProjectEntry::Workflow {
+            source: p,
+            annotations,
+            workflow,
+            document,
+        }
+    }
+}
+
+// suppressions are applied before the configuration so that disabling a rule
+// doesn't make every suppression of it look unused
+fn finish(document: &Document, settings: &Settings, annotations: &mut Annotations) {
+    Suppressions::scan(document).apply(annotations);
+    settings.apply(annotations);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build;
+    use crate::action::catalogue::Catalogue;
+    use crate::config::Config;
+    use crate::project::discovery::{Discovery, Skip};
+    use crate::project::ProjectRoot;
+    use std::path::PathBuf;
+
+    fn empty_project(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("possum-builder-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("docker-compose.yml"), "services: {}\n").unwrap();
+        root
+    }
+
+    #[test]
+    fn explicit_files_that_are_all_skipped_are_an_empty_project() {
+        let root = empty_project("explicit");
+        let discovery = Discovery::default().explicit(vec![PathBuf::from("docker-compose.yml")]);
+        let project = build(ProjectRoot::Explicit(root), &discovery, &Config::default(), &Catalogue::default()).unwrap();
+
+        assert_eq!(project.entries().count(), 0);
+        let skipped: Vec<_> = project.skipped().collect();
+        assert_eq!(skipped, [&(PathBuf::from("docker-compose.yml"), Skip::NotIncluded)]);
+    }
+
+    #[test]
+    fn a_project_without_workflows_fails() {
+        let root = empty_project("searched");
+        let built = build(ProjectRoot::Explicit(root), &Discovery::default(), &Config::default(), &Catalogue::default());
+        assert!(built.is_err());
+    }
 }
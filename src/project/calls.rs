@@ -0,0 +1,219 @@
+use super::builder::load_document;
+use super::{Project, ProjectEntry};
+use crate::action::catalogue::Catalogue;
+use crate::action::interface::ActionInterface;
+use crate::action::lints::ActionInputs;
+use crate::config::Settings;
+use crate::document::{Annotatable, Annotation, Annotations};
+use crate::lint::{rules, LintRule};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::job::Step;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const METADATA_FILES: [&str; 2] = ["action.yml", "action.yaml"];
+
+// Local actions are referenced as `uses: ./path/to/dir`, relative to the root
+// of the repository rather than to the file doing the referencing. Anything
+// else comes from the catalogue, if it knows about it.
+struct Actions<'c> {
+    root: PathBuf,
+    known: HashMap<PathBuf, Option<ActionInterface>>,
+    catalogue: &'c Catalogue,
+}
+
+impl<'c> Actions<'c> {
+    fn new(project: &Project, catalogue: &'c Catalogue) -> Actions<'c> {
+        let root = project.root().dir();
+        let mut known = HashMap::new();
+
+        for entry in project.entries() {
+            if let ProjectEntry::Action { source, action, .. } = entry {
+                if let (Some(dir), Some(action)) = (source.parent(), action.value()) {
+                    let name = display_name(&root, dir);
+                    known.insert(canonical(dir), Some(ActionInterface::from_action(name, action)));
+                }
+            }
+        }
+
+        Actions {
+            root,
+            known,
+            catalogue,
+        }
+    }
+
+    // actions that weren't discovered (eg. when only linting a single
+    // workflow) are read from disk on demand
+    fn resolve_local(&mut self, uses: &str) -> Option<&ActionInterface> {
+        let dir = self.root.join(uses);
+        let root = &self.root;
+
+        self.known
+            .entry(canonical(&dir))
+            .or_insert_with(|| {
+                let path = METADATA_FILES
+                    .iter()
+                    .map(|f| dir.join(f))
+                    .find(|p| p.is_file())?;
+                match load_document(path, &Settings::default()) {
+                    ProjectEntry::Action { action, .. } => action
+                        .value()
+                        .map(|a| ActionInterface::from_action(display_name(root, &dir), a)),
+                    _ => None,
+                }
+            })
+            .as_ref()
+    }
+
+    fn lint(&mut self, step: &PossumNode<Step>, annotations: &mut Annotations) {
+        let (node, uses) = match step
+            .value()
+            .and_then(|s| s.uses.as_ref())
+            .and_then(|u| u.value().map(|v| (u, v)))
+        {
+            Some(uses) => uses,
+            None => return,
+        };
+
+        if !uses.starts_with("./") {
+            if let Some(interface) = self.catalogue.lookup(uses) {
+                ActionInputs(interface).lint(step, annotations);
+            }
+            return;
+        }
+
+        match self.resolve_local(uses) {
+            Some(interface) => ActionInputs(interface).lint(step, annotations),
+            None => annotations.annotate(Annotation::error(
+                &rules::MISSING_LOCAL_ACTION,
+                node,
+                &format!("no action.yml found in {}", uses),
+            )),
+        }
+    }
+}
+
+pub fn lint_action_calls(project: &mut Project, catalogue: &Catalogue) {
+    let mut actions = Actions::new(project, catalogue);
+
+    for entry in project.entries_mut() {
+        match entry {
+            ProjectEntry::Workflow {
+                workflow,
+                annotations,
+                ..
+            } => {
+                let steps = workflow
+                    .value()
+                    .into_iter()
+                    .flat_map(|w| w.jobs())
+                    .filter_map(|(_, job)| job.value())
+                    .flat_map(|job| job.steps());
+                for step in steps {
+                    actions.lint(step, annotations);
+                }
+            }
+            ProjectEntry::Action {
+                action,
+                annotations,
+                ..
+            } => {
+                for step in action.value().into_iter().flat_map(|a| a.steps()) {
+                    actions.lint(step, annotations);
+                }
+            }
+            ProjectEntry::ParseFailure(..) => {}
+        }
+    }
+}
+
+fn canonical(dir: &Path) -> PathBuf {
+    dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf())
+}
+
+fn display_name(root: &Path, dir: &Path) -> String {
+    let root = canonical(root);
+    let dir = canonical(dir);
+    match dir.strip_prefix(&root) {
+        Ok(rel) => format!("./{}", rel.display()),
+        Err(_) => dir.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::action::catalogue::Catalogue;
+    use crate::config::Config;
+    use crate::lint::rules;
+    use crate::project::builder::build;
+    use crate::project::discovery::Discovery;
+    use crate::project::ProjectRoot;
+    use std::path::PathBuf;
+
+    const ACTION: &str = "name: greet\ndescription: says hello\ninputs:\n  who:\n    description: who to greet\n    required: true\nruns:\n  using: node24\n  main: index.js\n";
+
+    // a repository with a ./greet action and a workflow of the given steps
+    fn repository(name: &str, steps: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("possum-calls-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join(".github/workflows")).unwrap();
+        std::fs::create_dir_all(root.join("greet")).unwrap();
+        std::fs::write(root.join("greet/action.yml"), ACTION).unwrap();
+        let workflow = format!("on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    timeout-minutes: 5\n    steps:\n{steps}");
+        std::fs::write(root.join(".github/workflows/ci.yml"), workflow).unwrap();
+        root
+    }
+
+    // the messages of the findings about calls in the workflow, with the
+    // workflow the only file looked at unless all of them are
+    fn calls(root: PathBuf, everything: bool) -> Vec<String> {
+        let discovery = if everything {
+            Discovery::default()
+        } else {
+            Discovery::default().explicit(vec![root.join(".github/workflows/ci.yml")])
+        };
+        let project = build(ProjectRoot::Explicit(root.clone()), &discovery, &Config::default(), &Catalogue::bundled()).unwrap();
+        let _ = std::fs::remove_dir_all(&root);
+
+        let (_, _, annotations) = project
+            .entries()
+            .filter_map(|e| e.document())
+            .find(|(source, _, _)| source.ends_with("ci.yml"))
+            .unwrap();
+        annotations
+            .entries()
+            .filter(|a| {
+                [&rules::MISSING_LOCAL_ACTION, &rules::UNKNOWN_ACTION_INPUT, &rules::MISSING_ACTION_INPUT]
+                    .iter()
+                    .any(|r| r.id() == a.rule().id())
+            })
+            .map(|a| a.message().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn missing_local_actions_are_reported() {
+        let root = repository("missing", "      - uses: ./missing\n      - uses: ./greet\n        with:\n          who: world\n");
+        assert_eq!(calls(root, false), ["no action.yml found in ./missing"]);
+    }
+
+    #[test]
+    fn local_actions_are_read_whether_they_were_discovered_or_not() {
+        for everything in [false, true] {
+            let steps = "      - uses: ./greet\n        with:\n          whom: world\n";
+            let root = repository(&format!("local-{everything}"), steps);
+            assert_eq!(
+                calls(root, everything),
+                ["./greet has no input named whom", "./greet requires input who"]
+            );
+        }
+    }
+
+    #[test]
+    fn other_actions_come_from_the_catalogue() {
+        let steps = "      - uses: actions/checkout@v4\n        with:\n          fetch-depth: 0\n          depth: 0\n      - uses: someone/unknown@v1\n        with:\n          anything: 1\n";
+        let root = repository("catalogue", steps);
+        assert_eq!(calls(root, false), ["actions/checkout has no input named depth"]);
+    }
+}
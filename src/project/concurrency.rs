@@ -0,0 +1,129 @@
+use super::{Project, ProjectEntry};
+use crate::document::{Annotatable, Annotation, DocumentSpan};
+use crate::lint::rules;
+use crate::workflow::concurrency::{groups, GroupTemplate};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+// Concurrency groups are global to the repository, so two workflows that use
+// the same group, without github.workflow to tell them apart, queue behind or
+// cancel each other.
+pub fn lint_shared_groups(project: &mut Project) {
+    let mut shared: BTreeMap<String, Vec<(usize, PathBuf, DocumentSpan)>> = BTreeMap::new();
+
+    for (i, entry) in project.entries().enumerate() {
+        let (source, wf) = match entry {
+            ProjectEntry::Workflow {
+                source, workflow, ..
+            } => match workflow.value() {
+                Some(wf) => (source, wf),
+                None => continue,
+            },
+            _ => continue,
+        };
+
+        for (_, concurrency) in groups(wf) {
+            let group = match concurrency.value().and_then(|c| c.group()) {
+                Some(group) => group,
+                None => continue,
+            };
+
+            if !GroupTemplate(group).is_per_workflow() {
+                shared
+                    .entry(group.to_owned())
+                    .or_default()
+                    .push((i, source.clone(), concurrency.span()));
+            }
+        }
+    }
+
+    let mut found: Vec<(usize, Annotation)> = Vec::new();
+
+    for (group, uses) in shared.iter() {
+        for (i, source, span) in uses.iter() {
+            let mut others: Vec<_> = uses
+                .iter()
+                .filter(|(_, other, _)| other != source)
+                .map(|(_, other, _)| other.display().to_string())
+                .collect();
+            others.dedup();
+
+            if others.is_empty() {
+                continue;
+            }
+
+            found.push((
+                *i,
+                Annotation::warn(
+                    &rules::SHARED_CONCURRENCY_GROUP,
+                    span,
+                    &format!(
+                        "concurrency group {group} is also used by {}",
+                        others.join(", ")
+                    ),
+                ),
+            ));
+        }
+    }
+
+    let mut entries: Vec<_> = project.entries_mut().collect();
+    for (i, annotation) in found {
+        if let Some((_, _, annotations)) = entries[i].document_mut() {
+            annotations.annotate(annotation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lint_shared_groups;
+    use crate::document::{Annotations, Document, DocumentPointer};
+    use crate::project::{Project, ProjectEntry, ProjectRoot};
+    use crate::scavenge::ast::PossumNodeKind;
+    use crate::workflow::parser::test_workflow;
+    use std::path::PathBuf;
+
+    // the shared group findings of each workflow, named by its group
+    fn shared(groups: &[(&str, &str)]) -> Vec<(String, Vec<String>)> {
+        let mut project = Project::new(ProjectRoot::Explicit(PathBuf::from(".")));
+        for (file, group) in groups {
+            let raw = format!("on: push\njobs:\n  build:\n    concurrency: \"{group}\"\n");
+            project.push(ProjectEntry::Workflow {
+                source: PathBuf::from(file),
+                document: Document::new(raw.as_str()),
+                annotations: Annotations::new(),
+                workflow: PossumNodeKind::Value(test_workflow(&raw)).at(&DocumentPointer(0)),
+            });
+        }
+
+        lint_shared_groups(&mut project);
+        project
+            .entries()
+            .filter_map(|e| e.document())
+            .map(|(source, _, annotations)| {
+                let messages = annotations.entries().map(|a| a.message().to_owned()).collect();
+                (source.display().to_string(), messages)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn groups_shared_between_workflows_are_reported() {
+        let found = shared(&[("ci.yml", "deploy"), ("release.yml", "deploy"), ("docs.yml", "docs")]);
+        assert_eq!(
+            found,
+            [
+                ("ci.yml".to_owned(), vec!["concurrency group deploy is also used by release.yml".to_owned()]),
+                ("release.yml".to_owned(), vec!["concurrency group deploy is also used by ci.yml".to_owned()]),
+                ("docs.yml".to_owned(), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_kept_apart_by_the_workflow_are_not() {
+        let group = "${{ github.workflow }}-${{ github.ref }}";
+        let found = shared(&[("ci.yml", group), ("release.yml", group)]);
+        assert!(found.iter().all(|(_, messages)| messages.is_empty()), "{found:?}");
+    }
+}
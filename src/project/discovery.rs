@@ -0,0 +1,388 @@
+use glob::{MatchOptions, Pattern, PatternError};
+use std::ffi::OsStr;
+use std::fmt::Display;
+use std::io::BufRead;
+use std::path::{Component, Path, PathBuf};
+
+pub const DEFAULT_INCLUDE: &[&str] = &[
+    ".github/workflows/**/*.yml",
+    ".github/workflows/**/*.yaml",
+    ".github/actions/**/action.yml",
+    ".github/actions/**/action.yaml",
+    "action.yml",
+    "action.yaml",
+];
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+#[derive(Debug)]
+pub enum Discovered {
+    Found(PathBuf),
+    Unreadable(PathBuf, std::io::Error),
+    Skipped(PathBuf, Skip),
+}
+
+// why an explicitly named file isn't read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Skip {
+    NotYaml,
+    Missing,
+    OutsideRoot,
+    NotIncluded,
+    Excluded,
+}
+
+impl Display for Skip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            Skip::NotYaml => "not a yaml file",
+            Skip::Missing => "no such file",
+            Skip::OutsideRoot => "outside of the project root",
+            Skip::NotIncluded => "doesn't match any include pattern",
+            Skip::Excluded => "matches an exclude pattern",
+        };
+
+        write!(f, "{}", msg)
+    }
+}
+
+// Which documents make up a project. Patterns are relative to the project root.
+// Explicitly named files are relative to the project root too, like the paths
+// git prints, and are only read when they are in the project and match the
+// patterns, so that every file a pre-commit hook passes can be given to possum.
+#[derive(Debug)]
+pub struct Discovery {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    explicit: Option<Vec<PathBuf>>,
+}
+
+impl Default for Discovery {
+    fn default() -> Self {
+        Discovery {
+            include: DEFAULT_INCLUDE
+                .iter()
+                .map(|p| Pattern::new(p).unwrap())
+                .collect(),
+            exclude: Vec::new(),
+            explicit: None,
+        }
+    }
+}
+
+impl Discovery {
+    pub fn new<I, E>(include: I, exclude: E) -> Result<Discovery, PatternError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+        E: IntoIterator,
+        E::Item: AsRef<str>,
+    {
+        let mut discovery = Discovery::default();
+        let include = compile(include)?;
+
+        if !include.is_empty() {
+            discovery.include = include;
+        }
+
+        discovery.exclude = compile(exclude)?;
+        Ok(discovery)
+    }
+
+    pub fn explicit(mut self, files: Vec<PathBuf>) -> Discovery {
+        self.explicit = Some(files);
+        self
+    }
+
+    // newline separated paths, e.g. from `git diff --name-only`
+    pub fn read_explicit(self, r: impl BufRead) -> std::io::Result<Discovery> {
+        let mut files = Vec::new();
+
+        for line in r.lines() {
+            let line = line?;
+            let line = line.trim();
+            if !line.is_empty() {
+                files.push(PathBuf::from(line));
+            }
+        }
+
+        Ok(self.explicit(files))
+    }
+
+    // whether the documents were named rather than searched for
+    pub fn is_explicit(&self) -> bool {
+        self.explicit.is_some()
+    }
+
+    pub fn discover(&self, root: &Path) -> Vec<Discovered> {
+        match &self.explicit {
+            Some(files) => files.iter().map(|p| self.select(root, p)).collect(),
+            None => {
+                let mut found = self.walk(root);
+                found.retain(|d| match d {
+                    Discovered::Found(p) => !self.is_excluded(p.strip_prefix(root).unwrap_or(p)),
+                    _ => true,
+                });
+                found
+            }
+        }
+    }
+
+    fn select(&self, root: &Path, p: &Path) -> Discovered {
+        let skip = |why| Discovered::Skipped(p.to_path_buf(), why);
+
+        if !is_yaml(p) {
+            return skip(Skip::NotYaml);
+        }
+
+        let relative = match relative_to(root, p) {
+            Some(relative) => relative,
+            None => return skip(Skip::OutsideRoot),
+        };
+
+        if !self.is_included(&relative) {
+            skip(Skip::NotIncluded)
+        } else if self.is_excluded(&relative) {
+            skip(Skip::Excluded)
+        } else if !root.join(&relative).is_file() {
+            // eg. deleted in the commit a pre-commit hook is checking
+            skip(Skip::Missing)
+        } else {
+            Discovered::Found(root.join(relative))
+        }
+    }
+
+    fn walk(&self, root: &Path) -> Vec<Discovered> {
+        let mut found: Vec<Discovered> = Vec::new();
+        let escaped_root = Pattern::escape(&root.to_string_lossy());
+
+        for include in self.include.iter() {
+            let pattern = format!("{}/{}", escaped_root, include.as_str());
+            // the patterns were already validated when discovery was created
+            let paths = match glob::glob_with(&pattern, MATCH_OPTIONS) {
+                Ok(paths) => paths,
+                Err(_) => continue,
+            };
+
+            for p in paths {
+                match p {
+                    Ok(p) if p.is_file() => {
+                        let seen = found.iter().any(|d| match d {
+                            Discovered::Found(f) => *f == p,
+                            _ => false,
+                        });
+
+                        if !seen {
+                            found.push(Discovered::Found(p));
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        let path = e.path().to_path_buf();
+                        found.push(Discovered::Unreadable(path, e.into_error()));
+                    }
+                }
+            }
+        }
+
+        found
+    }
+
+    fn is_included(&self, relative: &Path) -> bool {
+        self.include
+            .iter()
+            .any(|i| i.matches_path_with(relative, MATCH_OPTIONS))
+    }
+
+    fn is_excluded(&self, relative: &Path) -> bool {
+        self.exclude
+            .iter()
+            .any(|e| e.matches_path_with(relative, MATCH_OPTIONS))
+    }
+}
+
+fn compile<I>(patterns: I) -> Result<Vec<Pattern>, PatternError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    patterns
+        .into_iter()
+        .map(|p| Pattern::new(p.as_ref()))
+        .collect()
+}
+
+// where a path given on the command line is in the project, None if it isn't
+fn relative_to(root: &Path, p: &Path) -> Option<PathBuf> {
+    let root = absolute(root);
+    absolute(&root.join(p))
+        .strip_prefix(&root)
+        .ok()
+        .map(Path::to_path_buf)
+}
+
+// files that don't exist can't be canonicalized, their .. are removed by hand
+fn absolute(p: &Path) -> PathBuf {
+    if let Ok(p) = p.canonicalize() {
+        return p;
+    }
+
+    let p = std::env::current_dir().map_or_else(|_| p.to_path_buf(), |cwd| cwd.join(p));
+    let mut normal = PathBuf::new();
+    for c in p.components() {
+        match c {
+            Component::ParentDir => {
+                normal.pop();
+            }
+            Component::CurDir => {}
+            c => normal.push(c),
+        }
+    }
+    normal
+}
+
+fn is_yaml(p: &Path) -> bool {
+    matches!(
+        p.extension().and_then(OsStr::to_str),
+        Some("yml") | Some("yaml")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Discovered, Discovery, Skip};
+    use std::path::{Path, PathBuf};
+
+    // a project on disk holding empty files at each of the paths
+    fn project(name: &str, files: &[&str]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("possum-discovery-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for f in files {
+            let p = root.join(f);
+            std::fs::create_dir_all(p.parent().unwrap()).unwrap();
+            std::fs::write(p, "").unwrap();
+        }
+        root.canonicalize().unwrap()
+    }
+
+    fn found(root: &Path, discovered: Vec<Discovered>) -> Vec<String> {
+        let mut found: Vec<_> = discovered
+            .into_iter()
+            .filter_map(|d| match d {
+                Discovered::Found(p) => Some(p.strip_prefix(root).unwrap().display().to_string()),
+                _ => None,
+            })
+            .collect();
+        found.sort();
+        found
+    }
+
+    fn skipped(discovered: Vec<Discovered>) -> Vec<(String, Skip)> {
+        discovered
+            .into_iter()
+            .filter_map(|d| match d {
+                Discovered::Skipped(p, why) => Some((p.display().to_string(), why)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn default_includes() {
+        let root = project(
+            "defaults",
+            &[
+                ".github/workflows/ci.yml",
+                ".github/workflows/nested/release.yaml",
+                ".github/workflows/notes.md",
+                ".github/actions/setup/action.yml",
+                ".github/actions/setup/other.yml",
+                "action.yaml",
+                "docs/example.yml",
+            ],
+        );
+
+        assert_eq!(
+            found(&root, Discovery::default().discover(&root)),
+            [
+                ".github/actions/setup/action.yml",
+                ".github/workflows/ci.yml",
+                ".github/workflows/nested/release.yaml",
+                "action.yaml",
+            ]
+        );
+    }
+
+    #[test]
+    fn configured_includes_and_excludes() {
+        let root = project("configured", &["ci/build.yml", "ci/deploy.yml", "ci/deep/test.yml"]);
+        let discovery = Discovery::new(["ci/*.yml"], ["ci/deploy.yml"]).unwrap();
+
+        assert_eq!(found(&root, discovery.discover(&root)), ["ci/build.yml"]);
+    }
+
+    #[test]
+    fn explicit_files_are_relative_to_the_root() {
+        let root = project(
+            "explicit",
+            &[".github/workflows/ci.yml", ".github/workflows/old.yml", "docs/example.yml"],
+        );
+        let discovery = Discovery::new(Vec::<&str>::new(), [".github/workflows/old.yml"])
+            .unwrap()
+            .explicit(
+                [
+                    ".github/workflows/ci.yml",
+                    ".github/workflows/../workflows/ci.yml",
+                    ".github/workflows/old.yml",
+                    ".github/workflows/deleted.yml",
+                    "docs/example.yml",
+                    "README.md",
+                    "../elsewhere/ci.yml",
+                    "/somewhere/else/.github/workflows/ci.yml",
+                ]
+                .iter()
+                .map(PathBuf::from)
+                .collect(),
+            );
+
+        let discovered = discovery.discover(&root);
+        assert_eq!(found(&root, discovery.discover(&root)), [".github/workflows/ci.yml", ".github/workflows/ci.yml"]);
+        assert_eq!(
+            skipped(discovered),
+            [
+                (".github/workflows/old.yml".to_owned(), Skip::Excluded),
+                (".github/workflows/deleted.yml".to_owned(), Skip::Missing),
+                ("docs/example.yml".to_owned(), Skip::NotIncluded),
+                ("README.md".to_owned(), Skip::NotYaml),
+                ("../elsewhere/ci.yml".to_owned(), Skip::OutsideRoot),
+                ("/somewhere/else/.github/workflows/ci.yml".to_owned(), Skip::OutsideRoot),
+            ]
+        );
+    }
+
+    #[test]
+    fn absolute_explicit_files_inside_the_root() {
+        let root = project("absolute", &[".github/workflows/ci.yml"]);
+        let discovery = Discovery::default().explicit(vec![root.join(".github/workflows/ci.yml")]);
+
+        assert_eq!(found(&root, discovery.discover(&root)), [".github/workflows/ci.yml"]);
+    }
+
+    #[test]
+    fn explicit_files_can_all_be_skipped() {
+        let root = project("empty", &["docker-compose.yml"]);
+        let discovery = Discovery::default()
+            .read_explicit("docker-compose.yml\n\n".as_bytes())
+            .unwrap();
+
+        assert!(discovery.is_explicit());
+        assert_eq!(
+            skipped(discovery.discover(&root)),
+            [("docker-compose.yml".to_owned(), Skip::NotIncluded)]
+        );
+    }
+}
@@ -1,6 +1,12 @@
+pub mod baseline;
 pub mod builder;
+mod calls;
+mod concurrency;
+pub mod discovery;
+use crate::document::{Annotations, Document};
+use discovery::Skip;
 use crate::scavenge::ParseFailure;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fmt::Display;
 
 #[derive(Debug)]
@@ -25,6 +31,14 @@ impl InitFailure {
     pub fn not_dir(p: impl Into<PathBuf>) -> InitFailure {
         Self::new(p, InitFailureReason::NotADirectory)
     }
+
+    pub fn bad_pattern(p: impl Into<PathBuf>, err: glob::PatternError) -> InitFailure {
+        Self::new(p, InitFailureReason::BadPattern(err))
+    }
+
+    pub fn unreadable_stdin(p: impl Into<PathBuf>, err: std::io::Error) -> InitFailure {
+        Self::new(p, InitFailureReason::UnreadableStdin(err))
+    }
 }
 
 impl Display for InitFailure {
@@ -40,6 +54,8 @@ impl std::error::Error for InitFailure{}
     NoWorkflows,
     DirectoryNotFound,
     NotADirectory,
+    BadPattern(glob::PatternError),
+    UnreadableStdin(std::io::Error),
 }
 
 
@@ -47,9 +63,11 @@ impl Display for InitFailureReason {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use InitFailureReason::*;
         let msg = match self {
-            NoWorkflows => "no workflows found in directory",
-            DirectoryNotFound => "directory not found",
-            NotADirectory => "not a directory"
+            NoWorkflows => "no workflows found in directory".to_owned(),
+            DirectoryNotFound => "directory not found".to_owned(),
+            NotADirectory => "not a directory".to_owned(),
+            BadPattern(e) => format!("bad glob pattern: {e}"),
+            UnreadableStdin(e) => format!("could not read files from stdin: {e}"),
         };
 
         write!(f, "{}", msg)
@@ -58,16 +76,19 @@ impl Display for InitFailureReason {
 
 #[derive(Debug)]
 pub struct Project {
-    _root: ProjectRoot,
+    root: ProjectRoot,
     // this is set if we needed to rut around to find the workflows directory
     entries: Vec<ProjectEntry>,
+    // explicitly named files that aren't part of the project
+    skipped: Vec<(PathBuf, Skip)>,
 }
 
 impl Project {
     pub fn new(root: ProjectRoot) -> Self {
         Project {
-            _root: root.into(),
+            root: root.into(),
             entries: Vec::with_capacity(8),
+            skipped: Vec::new(),
         }
     }
 
@@ -75,9 +96,25 @@ impl Project {
         self.entries.push(p)
     }
 
+    pub fn skip(&mut self, p: PathBuf, why: Skip) {
+        self.skipped.push((p, why))
+    }
+
+    pub fn skipped(&self) -> std::slice::Iter<(PathBuf, Skip)> {
+        self.skipped.iter()
+    }
+
+    pub fn root(&self) -> &ProjectRoot {
+        &self.root
+    }
+
     pub fn entries(&self) -> std::slice::Iter<ProjectEntry> {
         self.entries.iter()
     }
+
+    pub fn entries_mut(&mut self) -> std::slice::IterMut<ProjectEntry> {
+        self.entries.iter_mut()
+    }
 }
 
 #[derive(Debug)]
@@ -88,9 +125,54 @@ pub enum ProjectEntry {
         annotations: super::document::Annotations,
         workflow: crate::scavenge::ast::PossumNode<super::workflow::Workflow>,
     },
+    Action {
+        source: PathBuf,
+        document: super::document::Document,
+        annotations: super::document::Annotations,
+        action: crate::scavenge::ast::PossumNode<super::action::Action>,
+    },
     ParseFailure(PathBuf, ParseFailure),
 }
 
+impl ProjectEntry {
+    // the parts every successfully parsed document has
+    pub fn document(&self) -> Option<(&PathBuf, &Document, &Annotations)> {
+        match self {
+            ProjectEntry::Workflow {
+                source,
+                document,
+                annotations,
+                ..
+            }
+            | ProjectEntry::Action {
+                source,
+                document,
+                annotations,
+                ..
+            } => Some((source, document, annotations)),
+            ProjectEntry::ParseFailure(..) => None,
+        }
+    }
+
+    pub fn document_mut(&mut self) -> Option<(&PathBuf, &Document, &mut Annotations)> {
+        match self {
+            ProjectEntry::Workflow {
+                source,
+                document,
+                annotations,
+                ..
+            }
+            | ProjectEntry::Action {
+                source,
+                document,
+                annotations,
+                ..
+            } => Some((source, document, annotations)),
+            ProjectEntry::ParseFailure(..) => None,
+        }
+    }
+}
+
 
 #[derive(Clone, Debug)]
 pub enum ProjectRoot {
@@ -99,27 +181,32 @@ pub enum ProjectRoot {
 }
 
 impl ProjectRoot {
-    fn path(&self) -> PathBuf {
+    pub fn dir(&self) -> PathBuf {
         match self {
             ProjectRoot::Explicit(d) => d.to_owned(),
-            ProjectRoot::WorkingDirectory => std::env::current_dir().unwrap(),
+            ProjectRoot::WorkingDirectory => {
+                // possum might be ran from deep inside of the repo
+                let cwd = std::env::current_dir().unwrap();
+                cwd.ancestors()
+                    .find(|d| d.join(".git").exists())
+                    .map(Path::to_path_buf)
+                    .unwrap_or(cwd)
+            }
         }
-        .join(".github")
-        .join("workflows")
     }
 
     fn exists(&self) -> bool {
-        self.path().exists()
+        self.dir().exists()
     }
 
     fn is_dir(&self) -> bool {
-        self.path().is_dir()
+        self.dir().is_dir()
     }
 }
 
 impl Into<PathBuf> for &ProjectRoot {
     fn into(self) -> PathBuf {
-        self.path().clone()
+        self.dir().clone()
     }
 }
 
@@ -1,20 +1,24 @@
-use crate::document::{AsDocumentPointer, DocumentPointer};
+use crate::document::{AsDocumentPointer, DocumentPointer, DocumentSpan};
 use std::iter::{FromIterator, IntoIterator, Zip};
 use std::slice::Iter;
 
 #[derive(Debug)]
 pub struct PossumNode<T> {
-    location: DocumentPointer,
+    span: DocumentSpan,
     kind: PossumNodeKind<T>,
 }
 
 impl<T> PossumNode<T> {
-    pub fn new(location: DocumentPointer, kind: PossumNodeKind<T>) -> PossumNode<T> {
-        PossumNode { location, kind }
+    pub fn new(span: DocumentSpan, kind: PossumNodeKind<T>) -> PossumNode<T> {
+        PossumNode { span, kind }
     }
 
     pub fn loc(&self) -> DocumentPointer {
-        self.location.clone()
+        self.span.start()
+    }
+
+    pub fn span(&self) -> DocumentSpan {
+        self.span.clone()
     }
 
     pub fn kind(&self) -> &PossumNodeKind<T> {
@@ -32,14 +36,14 @@ impl<T> PossumNode<T> {
     where
         F: Fn(T) -> U,
     {
-        self.kind.map(f).at(&self.location)
+        self.kind.map(f).at(&self.span)
     }
 
     pub fn flatmap<F, U>(self, f: F) -> PossumNode<U>
     where
         F: Fn(T) -> PossumNodeKind<U>,
     {
-        self.kind.flatmap(f).at(&self.location)
+        self.kind.flatmap(f).at(&self.span)
     }
 }
 
@@ -47,12 +51,20 @@ impl<T> AsDocumentPointer for &PossumNode<T> {
     fn as_document_pointer(&self) -> DocumentPointer {
         self.loc()
     }
+
+    fn as_document_span(&self) -> DocumentSpan {
+        self.span()
+    }
 }
 
 impl<T> AsDocumentPointer for PossumNode<T> {
     fn as_document_pointer(&self) -> DocumentPointer {
         AsDocumentPointer::as_document_pointer(&self)
     }
+
+    fn as_document_span(&self) -> DocumentSpan {
+        AsDocumentPointer::as_document_span(&self)
+    }
 }
 
 #[derive(Debug)]
@@ -68,7 +80,7 @@ impl<T> PossumNodeKind<T> {
     where
         D: AsDocumentPointer,
     {
-        PossumNode::new(location.as_document_pointer(), self)
+        PossumNode::new(location.as_document_span(), self)
     }
 
     pub fn invalid(msg: impl ToString) -> PossumNodeKind<T> {
@@ -1,4 +1,5 @@
 use crate::document::{Annotation, AsDocumentPointer};
+use crate::lint::rules;
 
 use super::ast::PossumNodeKind;
 use super::yaml::YamlKind;
@@ -28,7 +29,7 @@ impl UnexpectedYaml {
     where
         P: AsDocumentPointer,
     {
-        Annotation::fatal(at, &self)
+        Annotation::fatal(&rules::UNEXPECTED_YAML, at, &self)
     }
 }
 
@@ -5,7 +5,7 @@ pub mod parser;
 pub mod parsers;
 pub mod yaml;
 
-pub use self::parser::{parse_single_document, ParseFailure, Parser, UnexpectedKey};
+pub use self::parser::{load_single_document, ParseFailure, Parser, UnexpectedKey};
 
 pub enum Fallible<T> {
     Success,
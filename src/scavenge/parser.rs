@@ -1,5 +1,7 @@
-use super::ast::{PossumNode, PossumNodeKind};
+use super::ast::PossumNodeKind;
+use super::yaml::Source;
 use crate::document::{Annotation, AsDocumentPointer, DocumentPointer};
+use crate::lint::rules;
 use std::fmt::{Display};
 use yaml_peg::parser::{Loader, PError};
 use yaml_peg::repr::Repr;
@@ -28,7 +30,7 @@ impl<'a> UnexpectedKey<'a> {
     where
         P: AsDocumentPointer,
     {
-        Annotation::error(loc, &self)
+        Annotation::error(&rules::UNEXPECTED_KEY, loc, &self)
     }
 }
 
@@ -44,15 +46,11 @@ impl<'a> From<&'a str> for UnexpectedKey<'a> {
     }
 }
 
-pub fn parse_single_document<'a, R, T, P>(
-    mut loader: Loader<'a, R>,
-    parser: &mut P,
-) -> Result<PossumNode<T>, ParseFailure>
+pub fn load_single_document<'a, R>(mut loader: Loader<'a, R>) -> Result<YamlNode<R>, ParseFailure>
 where
     R: Repr,
-    P: Parser<R, T>,
 {
-    let documents = loader
+    let mut documents = loader
         .parse()
         .map_err(|e| ParseFailure::InvalidDocument(e))?;
 
@@ -64,21 +62,28 @@ where
         Err(ParseFailure::TooManyDocuments(
             documents
                 .iter()
-                .map(AsDocumentPointer::as_document_pointer)
+                .map(|n| DocumentPointer::from(n.pos()))
                 .collect(),
         ))?
     }
 
-    let documents = &documents;
-    let d = documents.get(0).unwrap();
-    Ok(parser.parse_node(d).at(d))
+    Ok(documents.remove(0))
 }
 
 pub trait Parser<R, T>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<T>
+    // source is the document root came from, for the spans of its children
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<T>
     where
         R: Repr;
 }
+
+// a node for the parsers to be tested against, and where it came from
+#[cfg(test)]
+pub(crate) fn test_document(raw: &str) -> (YamlNode<yaml_peg::repr::RcRepr>, Source<'_>) {
+    let loader: Loader<'_, yaml_peg::repr::RcRepr> = Loader::new(raw.as_bytes());
+    let root = load_single_document(loader).expect("test documents are valid yaml");
+    (root, Source::new(raw.as_bytes()))
+}
@@ -1,9 +1,10 @@
 use crate::scavenge::ast::PossumNodeKind;
 use crate::scavenge::extraction::Extract;
 use crate::scavenge::Parser;
+use crate::scavenge::yaml::Source;
 use std::marker::PhantomData;
 use yaml_peg::repr::Repr;
-use yaml_peg::Node as YamlNode;
+use yaml_peg::{Node as YamlNode, Yaml};
 
 
 pub struct StringParser;
@@ -11,7 +12,7 @@ impl<R> Parser<R, String> for StringParser
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<String>
+    fn parse_node(&mut self, root: &YamlNode<R>, _: Source) -> PossumNodeKind<String>
     where
         R: Repr,
     {
@@ -19,6 +20,24 @@ where
     }
 }
 
+// numbers and booleans too, as the strings GitHub turns them into in env and with
+pub struct ScalarParser;
+impl<R> Parser<R, String> for ScalarParser
+where
+    R: Repr,
+{
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<String>
+    where
+        R: Repr,
+    {
+        match root.yaml() {
+            Yaml::Bool(b) => PossumNodeKind::Value(b.to_string()),
+            Yaml::Int(n) | Yaml::Float(n) => PossumNodeKind::Value(n.clone()),
+            _ => StringParser.parse_node(root, source),
+        }
+    }
+}
+
 pub struct ExprParser<T>(PhantomData<T>);
 impl<T> ExprParser<T> {
     pub fn new() -> ExprParser<T> {
@@ -30,11 +49,11 @@ impl<R, T> Parser<R, T> for ExprParser<T>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<T>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<T>
     where
         R: Repr,
     {
-        StringParser.parse_node(root).flatmap(PossumNodeKind::Expr)
+        StringParser.parse_node(root, source).flatmap(PossumNodeKind::Expr)
     }
 }
 
@@ -43,7 +62,7 @@ impl<R> Parser<R, bool> for BoolParser
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<bool>
+    fn parse_node(&mut self, root: &YamlNode<R>, _: Source) -> PossumNodeKind<bool>
     where
         R: Repr,
     {
@@ -56,7 +75,7 @@ impl<R> Parser<R, f64> for NumberParser
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<f64>
+    fn parse_node(&mut self, root: &YamlNode<R>, _: Source) -> PossumNodeKind<f64>
     where
         R: Repr,
     {
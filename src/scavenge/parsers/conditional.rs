@@ -1,6 +1,7 @@
 use super::concrete::ExprParser;
 use crate::scavenge::ast::PossumNodeKind;
 use crate::scavenge::parser::Parser;
+use crate::scavenge::yaml::Source;
 use std::marker::PhantomData;
 use yaml_peg::repr::Repr;
 use yaml_peg::Node as YamlNode;
@@ -44,13 +45,13 @@ where
     RHS: Parser<R, T>,
     D: Fn(&YamlNode<R>) -> PossumNodeKind<T>,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<T>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<T>
     where
         R: Repr,
     {
         self.lhs
-            .parse_node(root)
-            .recover(|| self.rhs.parse_node(root))
+            .parse_node(root, source)
+            .recover(|| self.rhs.parse_node(root, source))
             .recover(|| (self.default)(root))
     }
 }
@@ -85,11 +86,11 @@ where
     RHS: Parser<R, T>,
     D: Fn(&YamlNode<R>) -> PossumNodeKind<T>,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<T>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<T>
     where
         R: Repr,
     {
-        self.inner.parse_node(root)
+        self.inner.parse_node(root, source)
     }
 }
 
@@ -1,14 +1,15 @@
 use yaml_peg::repr::Repr;
 use yaml_peg::Node as YamlNode;
 
-use super::concrete::StringParser;
+use super::concrete::{ScalarParser, StringParser};
 use crate::scavenge::ast::PossumNodeKind;
 use crate::scavenge::ast::{PossumMap, PossumSeq};
 use crate::scavenge::extraction::Extract;
 use crate::scavenge::Parser;
+use crate::scavenge::yaml::Source;
 use std::marker::PhantomData;
 
-pub struct StringMapParser<R>(MapParser<R, String, String, StringParser, StringParser>)
+pub struct StringMapParser<R>(MapParser<R, String, String, StringParser, ScalarParser>)
 where
     R: Repr;
 
@@ -17,7 +18,7 @@ where
     R: Repr,
 {
     pub fn new() -> StringMapParser<R> {
-        StringMapParser(MapParser::new(StringParser, StringParser))
+        StringMapParser(MapParser::new(StringParser, ScalarParser))
     }
 }
 
@@ -25,11 +26,11 @@ impl<R> Parser<R, PossumMap<String, String>> for StringMapParser<R>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<PossumMap<String, String>>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<PossumMap<String, String>>
     where
         R: Repr,
     {
-        self.0.parse_node(root)
+        self.0.parse_node(root, source)
     }
 }
 
@@ -69,7 +70,7 @@ where
     KP: Parser<R, K>,
     VP: Parser<R, V>,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<PossumMap<K, V>>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<PossumMap<K, V>>
     where
         R: Repr,
     {
@@ -80,10 +81,10 @@ where
                 let mut map = PossumMap::empty();
 
                 for (key, value) in m.iter() {
-                    let k: PossumNodeKind<K> = self.keys.parse_node(key);
-                    let v: PossumNodeKind<V> = self.values.parse_node(value);
+                    let k: PossumNodeKind<K> = self.keys.parse_node(key, source);
+                    let v: PossumNodeKind<V> = self.values.parse_node(value, source);
 
-                    map.insert(k.at(key), v.at(value));
+                    map.insert(k.at(&source.span(key)), v.at(&source.span(value)));
                 }
 
                 Value(map)
@@ -120,7 +121,7 @@ where
     R: Repr,
     P: Parser<R, T>,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<PossumSeq<T>>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<PossumSeq<T>>
     where
         R: Repr,
     {
@@ -129,9 +130,41 @@ where
             Err(u) => Invalid(u.to_string()),
             Ok(seq) => Value(
                 seq.iter()
-                    .map(|elm| self.inner.parse_node(elm).at(elm))
+                    .map(|elm| self.inner.parse_node(elm, source).at(&source.span(elm)))
                     .collect(),
             ),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::StringMapParser;
+    use crate::scavenge::ast::PossumNodeKind;
+    use crate::scavenge::parser::test_document;
+    use crate::scavenge::Parser;
+
+    #[test]
+    fn string_maps_read_scalars_as_the_strings_github_sees() {
+        let (root, source) = test_document("NAME: text\nCOUNT: 3\nRATIO: 1.5\nDEBUG: true\nNESTED: [1]\n");
+        let map = match StringMapParser::new().parse_node(&root, source) {
+            PossumNodeKind::Value(map) => map,
+            _ => panic!("not parsed as a map"),
+        };
+
+        let values: Vec<_> = map
+            .iter()
+            .map(|(k, v)| (k.value().unwrap().as_str(), v.value().map(String::as_str)))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                ("NAME", Some("text")),
+                ("COUNT", Some("3")),
+                ("RATIO", Some("1.5")),
+                ("DEBUG", Some("true")),
+                ("NESTED", None),
+            ]
+        );
+    }
+}
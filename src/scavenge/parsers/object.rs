@@ -5,7 +5,7 @@ use yaml_peg::Node as YamlNode;
 
 use crate::{
     document::{Annotations, AsDocumentPointer},
-    scavenge::{ast::PossumNodeKind, extraction::Extract, Parser},
+    scavenge::{ast::PossumNodeKind, extraction::Extract, yaml::Source, Parser},
 };
 
 pub struct ObjectParser<'a, B, F, T>
@@ -24,6 +24,7 @@ pub trait Builder<T> : Into<T> {
         key: &str,
         value: &YamlNode<R>,
         pointer: &P,
+        source: Source,
         annotations: &'a mut Annotations,
     ) where
         P: AsDocumentPointer + 'a,
@@ -53,7 +54,7 @@ where
     B: Builder<T>,
     F: Fn() -> B
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<T>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<T>
     where
         R: Repr,
     {
@@ -64,8 +65,8 @@ where
                 let mut builder = (self.builder)();
                 for (key, value) in m.iter() {
                     match key.extract_str() {
-                        Err(u) => self.annotations.add(u.at(key)),
-                        Ok(s) => builder .build( s, value, key, self.annotations),
+                        Err(u) => self.annotations.add(u.at(&source.span(key))),
+                        Ok(s) => builder.build(s, value, &source.span(key), source, self.annotations),
                     }
                 }
 
@@ -4,6 +4,7 @@ use yaml_peg::Node as YamlNode;
 use crate::scavenge::ast::PossumNodeKind;
 use crate::scavenge::ast::PossumSeq;
 use crate::scavenge::parser::Parser;
+use crate::scavenge::yaml::Source;
 use std::marker::PhantomData;
 
 pub struct TransformParser<R, T, U, P, F>
@@ -42,11 +43,11 @@ where
     P: Parser<R, T>,
     F: Fn(T) -> U,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<U>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<U>
     where
         R: Repr,
     {
-        self.parser.parse_node(root).map(&self.transform)
+        self.parser.parse_node(root, source).map(&self.transform)
     }
 }
 
@@ -105,11 +106,11 @@ where
     P: Parser<R, T>,
     F: Fn(T) -> PossumNodeKind<U>,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<U>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<U>
     where
         R: Repr,
     {
-        self.parser.parse_node(root).flatmap(&self.transform)
+        self.parser.parse_node(root, source).flatmap(&self.transform)
     }
 }
 
@@ -144,11 +145,15 @@ where
     R: Repr,
     P: Parser<R, T>,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<PossumSeq<T>>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<PossumSeq<T>>
     where
         R: Repr,
     {
-        PossumNodeKind::Value(self.inner.parse_node(root).at(root).into())
+        // passing on the failure lets an OrParser try parsing a sequence
+        match self.inner.parse_node(root, source) {
+            PossumNodeKind::Invalid(msg) => PossumNodeKind::Invalid(msg),
+            single => PossumNodeKind::Value(single.at(&source.span(root)).into()),
+        }
     }
 }
 
@@ -189,3 +194,38 @@ where
         FlatMapParser::new(self, transform)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::scavenge::ast::{PossumNodeKind, PossumSeq};
+    use crate::scavenge::parser::test_document;
+    use crate::scavenge::parsers::{OrableParser, PluralizableParser, SeqParser, StringParser};
+    use crate::scavenge::Parser;
+
+    fn strings(kind: PossumNodeKind<PossumSeq<String>>) -> Option<Vec<String>> {
+        match kind {
+            PossumNodeKind::Value(seq) => Some(seq.iter().map(|s| s.value().unwrap().clone()).collect()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn a_single_value_is_a_sequence_of_one() {
+        let (root, source) = test_document("build");
+        assert_eq!(strings(StringParser.pluralize().parse_node(&root, source)), Some(vec!["build".to_owned()]));
+    }
+
+    #[test]
+    fn failures_are_passed_on() {
+        let (root, source) = test_document("[build, test]");
+        assert!(matches!(StringParser.pluralize().parse_node(&root, source), PossumNodeKind::Invalid(_)));
+
+        let mut either = StringParser
+            .pluralize()
+            .or(SeqParser::new(StringParser), |_| PossumNodeKind::invalid("neither"));
+        assert_eq!(strings(either.parse_node(&root, source)), Some(vec!["build".to_owned(), "test".to_owned()]));
+
+        let (root, source) = test_document("{build: test}");
+        assert!(matches!(either.parse_node(&root, source), PossumNodeKind::Invalid(msg) if msg == "neither"));
+    }
+}
@@ -1,4 +1,5 @@
-use crate::document::AsDocumentPointer;
+use super::extraction::Extract;
+use crate::document::{DocumentPointer, DocumentSpan, NodePaths};
 use yaml_peg::repr::Repr;
 use yaml_peg::{Node as YamlNode, Yaml};
 
@@ -47,20 +48,245 @@ where
     }
 }
 
-impl<R> AsDocumentPointer for &YamlNode<R>
+// The document nodes were loaded from. yaml_peg only records where a node
+// starts, so anything that needs to know where one ends reads it from here.
+#[derive(Clone, Copy, Debug)]
+pub struct Source<'a>(&'a [u8]);
+
+impl<'a> Source<'a> {
+    pub fn new(raw: &'a [u8]) -> Source<'a> {
+        Source(raw)
+    }
+
+    pub fn span<R>(&self, n: &YamlNode<R>) -> DocumentSpan
+    where
+        R: Repr,
+    {
+        extent(self.0, n)
+    }
+}
+
+// The end of a scalar is found by reading it from the document and
+// collections end with their furthest child. Trailing whitespace and
+// comments aren't included.
+fn extent<R>(raw: &[u8], n: &YamlNode<R>) -> DocumentSpan
 where
     R: Repr,
 {
-    fn as_document_pointer(&self) -> crate::document::DocumentPointer {
-        self.pos().into()
+    let start: DocumentPointer = n.pos().into();
+    let width = |len: usize| DocumentPointer(start.0 + len);
+
+    let end = match n.yaml() {
+        Yaml::Null | Yaml::Alias(_) => start,
+        Yaml::Bool(true) => width(4),
+        Yaml::Bool(false) => width(5),
+        Yaml::Int(raw) | Yaml::Float(raw) => width(raw.len()),
+        // only a node from another document isn't there
+        Yaml::Str(s) => scalar_end(raw, start.0, s).map_or(start, DocumentPointer),
+        Yaml::Seq(seq) => seq.iter().map(|elm| extent(raw, elm).end()).fold(start, Ord::max),
+        Yaml::Map(m) => m
+            .iter()
+            .map(|(k, v)| extent(raw, k).cover(&extent(raw, v)).end())
+            .fold(start, Ord::max),
+    };
+
+    DocumentSpan::new(start, end)
+}
+
+// Where a string scalar starting at start ends in raw, None if it isn't
+// there. The value is needed for plain scalars, which can only be told
+// apart from what follows them by what they decoded to.
+fn scalar_end(raw: &[u8], start: usize, value: &str) -> Option<usize> {
+    match raw.get(start)? {
+        b'"' => quoted_end(raw, start, |rest| match rest {
+            [b'\\', _, ..] => Some(2),
+            [b'"', ..] => None,
+            _ => Some(1),
+        }),
+        b'\'' => quoted_end(raw, start, |rest| match rest {
+            [b'\'', b'\'', ..] => Some(2),
+            [b'\'', ..] => None,
+            _ => Some(1),
+        }),
+        b'|' | b'>' => Some(block_end(raw, start)),
+        _ => plain_end(raw, start, value),
+    }
+}
+
+// after the closing quote, step says how far to move or None at the close
+fn quoted_end(raw: &[u8], start: usize, step: impl Fn(&[u8]) -> Option<usize>) -> Option<usize> {
+    let mut at = start + 1;
+    while at < raw.len() {
+        match step(&raw[at..]) {
+            Some(n) => at += n,
+            None => return Some(at + 1),
+        }
+    }
+    None
+}
+
+// The last line of content, which is every line after the header that's
+// blank or indented at least as far as the first line with anything on it.
+fn block_end(raw: &[u8], start: usize) -> usize {
+    let header_end = raw[start..]
+        .iter()
+        .position(|c| *c == b'\n')
+        .map_or(raw.len(), |n| start + n);
+    let header = &raw[start..header_end];
+    let mut end = start + header.iter().position(|c| *c == b' ' || *c == b'#').unwrap_or(header.len());
+
+    let line_indent = |line: &[u8]| line.iter().take_while(|c| **c == b' ').count();
+    let parent = raw[..start].iter().rposition(|c| *c == b'\n').map_or(0, |n| n + 1);
+    let parent_indent = line_indent(&raw[parent..]);
+
+    let mut indent = None;
+    let mut at = header_end + 1;
+    while at < raw.len() {
+        let line_end = raw[at..].iter().position(|c| *c == b'\n').map_or(raw.len(), |n| at + n);
+        let line = &raw[at..line_end];
+        if !line.iter().all(|c| c.is_ascii_whitespace()) {
+            let this = line_indent(line);
+            let wanted = *indent.get_or_insert(this);
+            if this < wanted || this <= parent_indent {
+                break;
+            }
+            end = at + line.iter().rposition(|c| !c.is_ascii_whitespace()).map_or(0, |n| n + 1);
+        }
+        at = line_end + 1;
+    }
+
+    end
+}
+
+// A plain scalar is there as it is, except that a scalar spread over
+// several lines has each line break and indent folded into one space.
+fn plain_end(raw: &[u8], start: usize, value: &str) -> Option<usize> {
+    let mut at = start;
+    let mut end = start;
+    let mut value = value.as_bytes();
+
+    while let Some(c) = value.first() {
+        match raw.get(at)? {
+            b if b == c => {
+                at += 1;
+                value = &value[1..];
+                if !c.is_ascii_whitespace() {
+                    end = at;
+                }
+            }
+            b if b.is_ascii_whitespace() && c.is_ascii_whitespace() => {
+                while raw.get(at).map_or(false, u8::is_ascii_whitespace) {
+                    at += 1;
+                }
+                while value.first().map_or(false, u8::is_ascii_whitespace) {
+                    value = &value[1..];
+                }
+            }
+            _ => return None,
+        }
     }
+
+    Some(end)
+}
+
+pub fn node_paths<R>(source: Source, root: &YamlNode<R>) -> NodePaths
+where
+    R: Repr,
+{
+    let mut paths = NodePaths::new();
+    walk_paths(source, root, "$".to_owned(), &mut paths);
+    paths
 }
 
-impl<R> AsDocumentPointer for YamlNode<R>
+fn walk_paths<R>(source: Source, n: &YamlNode<R>, path: String, paths: &mut NodePaths)
 where
     R: Repr,
 {
-    fn as_document_pointer(&self) -> crate::document::DocumentPointer {
-        AsDocumentPointer::as_document_pointer(&self)
+    match n.yaml() {
+        Yaml::Map(m) => {
+            for (k, v) in m.iter() {
+                let child = match k.extract_str() {
+                    Ok(key) => NodePaths::key(&path, key),
+                    Err(_) => NodePaths::key(&path, "?"),
+                };
+                paths.insert(source.span(k), child.clone());
+                walk_paths(source, v, child, paths);
+            }
+        }
+        Yaml::Seq(seq) => {
+            for (idx, elm) in seq.iter().enumerate() {
+                walk_paths(source, elm, NodePaths::index(&path, idx), paths);
+            }
+        }
+        _ => {}
+    }
+
+    paths.insert(source.span(n), path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scalar_end;
+    use crate::scavenge::extraction::Extract;
+    use crate::scavenge::parser::test_document;
+
+    fn end(raw: &str, value: &str) -> Option<usize> {
+        let start = raw.find('@').map_or(0, |n| n + 1);
+        scalar_end(raw.as_bytes(), start, value)
+    }
+
+    #[test]
+    fn plain_scalars_end_with_their_value() {
+        let raw = "k:@value # comment\n";
+        assert_eq!(end(raw, "value"), Some(raw.find(" #").unwrap()));
+    }
+
+    #[test]
+    fn folded_plain_scalars_end_on_their_last_line() {
+        let raw = "- @first\n  second\n- third\n";
+        assert_eq!(end(raw, "first second"), Some(raw.find("\n- ").unwrap()));
+    }
+
+    #[test]
+    fn quoted_scalars_end_after_their_quote() {
+        let raw = "k:@\"a \\\" b\\n\" # comment\n";
+        assert_eq!(end(raw, "a \" b\n"), Some(raw.find(" #").unwrap()));
+
+        let raw = "k:@'it''s' # comment\n";
+        assert_eq!(end(raw, "it's"), Some(raw.find(" #").unwrap()));
+    }
+
+    #[test]
+    fn block_scalars_end_on_their_last_line() {
+        let raw = "steps:\n  - run:@|\n      echo one\n\n        echo two\n\n    shell: bash\n";
+        assert_eq!(
+            end(raw, "echo one\n\n  echo two\n"),
+            Some(raw.find("two").unwrap() + 3)
+        );
+
+        let raw = "run:@>-\n  folded\n  lines\n";
+        assert_eq!(end(raw, "folded lines"), Some(raw.len() - 1));
+    }
+
+    // what a key's value spans in the document, read through its node
+    fn spanned<'a>(raw: &'a str, key: &str) -> &'a str {
+        let (root, source) = test_document(raw);
+        let map = root.extract_map().unwrap();
+        let (_, value) = map.iter().find(|(k, _)| k.extract_str().ok() == Some(key)).unwrap();
+        let span = source.span(value);
+        &raw[span.start().0..span.end().0]
+    }
+
+    #[test]
+    fn spans_end_where_their_scalar_does() {
+        let raw = "double: \"a \\\" b\" # comment\nsingle: 'it''s'\nnext: 1\n";
+        assert_eq!(spanned(raw, "double"), "\"a \\\" b\"");
+        assert_eq!(spanned(raw, "single"), "'it''s'");
+
+        let raw = "folded: >-\n  one\n  two\n\nnext: 1\n";
+        assert_eq!(spanned(raw, "folded"), ">-\n  one\n  two");
+
+        let raw = "literal: |\n  one\n\n    two # not a comment\nnext: 1\n";
+        assert_eq!(spanned(raw, "literal"), "|\n  one\n\n    two # not a comment");
     }
 }
@@ -0,0 +1,450 @@
+// Just enough of a POSIX shell lexer to look at the scripts in run: steps.
+// Scripts are split into simple commands, with their words unquoted and their
+// redirections, and every ${{ }} in them is found, heredocs included. Lines and
+// columns are 0 indexed and relative to the script.
+
+use crate::expression::embedded;
+use std::ops::Range;
+
+#[derive(Debug, Default)]
+pub struct Script {
+    pub commands: Vec<Command>,
+    pub expansions: Vec<Expansion>,
+}
+
+#[derive(Debug, Default)]
+pub struct Command {
+    pub line: usize,
+    pub words: Vec<Word>,
+    pub redirects: Vec<Redirect>,
+    // reads the output of the command before it
+    pub piped: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Word {
+    // with quotes and escapes removed
+    pub text: String,
+    pub line: usize,
+    pub col: usize,
+    // bytes of the script the word was written in, quotes included
+    pub range: Range<usize>,
+}
+
+#[derive(Debug)]
+pub struct Redirect {
+    pub op: String,
+    pub target: Word,
+}
+
+// A ${{ }}, which GitHub substitutes before the shell ever sees the script
+#[derive(Debug)]
+pub struct Expansion {
+    pub source: String,
+    pub line: usize,
+    pub col: usize,
+    pub quoted: bool,
+    // bytes of the script the whole ${{ }} is in
+    pub range: Range<usize>,
+    // index of the command it is part of
+    pub command: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Quote {
+    None,
+    Single,
+    Double,
+}
+
+impl Command {
+    // the command name, after any VAR=value assignments
+    pub fn name(&self) -> Option<&str> {
+        self.words
+            .iter()
+            .map(|w| w.text.as_str())
+            .find(|w| !is_assignment(w))
+    }
+
+    pub fn args(&self) -> impl Iterator<Item = &Word> {
+        self.words.iter().skip_while(|w| is_assignment(&w.text)).skip(1)
+    }
+}
+
+fn is_assignment(word: &str) -> bool {
+    match word.split_once('=') {
+        Some((name, _)) => {
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
+
+struct Lexer<'a> {
+    raw: &'a str,
+    // byte ranges of each ${{ }}
+    expressions: Vec<(usize, usize)>,
+    script: Script,
+    command: Command,
+    word: Option<Word>,
+    // the next word is the target of this redirection
+    redirect: Option<String>,
+    // heredocs whose bodies start on the next line
+    pending: Vec<(usize, String)>,
+    piped: bool,
+    // where the lexer is, which is where the current word ends
+    at: usize,
+    line: usize,
+    line_start: usize,
+}
+
+pub fn parse(raw: &str) -> Script {
+    let expressions = embedded(raw)
+        .map(|found| found.iter().map(|e| (e.start, e.end)).collect())
+        .unwrap_or_default();
+
+    let mut lexer = Lexer {
+        raw,
+        expressions,
+        script: Script::default(),
+        command: Command::default(),
+        word: None,
+        redirect: None,
+        pending: Vec::new(),
+        piped: false,
+        at: 0,
+        line: 0,
+        line_start: 0,
+    };
+    lexer.run();
+    lexer.script
+}
+
+impl<'a> Lexer<'a> {
+    fn run(&mut self) {
+        let bytes = self.raw.as_bytes();
+        let mut quote = Quote::None;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            self.at = i;
+            if let Some(&(start, end)) = self.expressions.iter().find(|(s, _)| *s == i) {
+                self.expansion(start, end, quote != Quote::None);
+                i = end;
+                continue;
+            }
+
+            let c = self.raw[i..].chars().next().unwrap_or_default();
+            let next = self.raw[i + c.len_utf8()..].chars().next();
+
+            match (quote, c) {
+                (Quote::Single, '\'') | (Quote::Double, '"') => {
+                    quote = Quote::None;
+                    self.push_str("", i);
+                }
+                (Quote::Single, c) => {
+                    self.push(c, i);
+                    if c == '\n' {
+                        self.newline(i);
+                    }
+                }
+                (Quote::Double, '\\') if matches!(next, Some('"' | '\\' | '$' | '`')) => {
+                    self.push(next.unwrap_or_default(), i);
+                    i += 1;
+                }
+                (Quote::Double, c) => {
+                    self.push(c, i);
+                    if c == '\n' {
+                        self.newline(i);
+                    }
+                }
+                (Quote::None, '\'') => {
+                    quote = Quote::Single;
+                    self.push_str("", i);
+                }
+                (Quote::None, '"') => {
+                    quote = Quote::Double;
+                    self.push_str("", i);
+                }
+                (Quote::None, '\\') if next == Some('\n') => {
+                    // a continued line is still the same command
+                    self.end_word();
+                    i += 1;
+                    self.newline(i);
+                }
+                (Quote::None, '\\') => {
+                    if let Some(n) = next {
+                        self.push(n, i);
+                        i += n.len_utf8();
+                    }
+                }
+                (Quote::None, '#') if self.word.is_none() => {
+                    i = self.comment(i);
+                    continue;
+                }
+                (Quote::None, '\n') => {
+                    self.end_command();
+                    self.newline(i);
+                    i = self.heredocs(i + 1);
+                    continue;
+                }
+                (Quote::None, ' ' | '\t') => self.end_word(),
+                (Quote::None, '>' | '<' | '&') if c != '&' || next == Some('>') => {
+                    self.redirect(&mut i);
+                    continue;
+                }
+                (Quote::None, '|' | '&') if next == Some(c) => {
+                    self.end_command();
+                    i += 1;
+                }
+                (Quote::None, '|') => {
+                    self.end_command();
+                    self.piped = true;
+                }
+                (Quote::None, ';' | '&') => self.end_command(),
+                (Quote::None, '(' | ')' | '{' | '}') if self.word.is_none() => self.end_command(),
+                (Quote::None, c) => self.push(c, i),
+            }
+
+            i += c.len_utf8();
+        }
+
+        self.at = self.raw.len();
+        self.end_command();
+    }
+
+    fn redirect(&mut self, i: &mut usize) {
+        // a file descriptor number belongs to the redirection
+        let fd = |w: &Word| !w.text.is_empty() && w.text.chars().all(|c| c.is_ascii_digit());
+        if self.word.as_ref().map_or(false, fd) {
+            self.word = None;
+        }
+        self.end_word();
+        let op: String = self.raw[*i..]
+            .chars()
+            .take_while(|c| matches!(c, '>' | '<' | '&' | '|' | '-'))
+            .collect();
+        *i += op.len();
+        self.redirect = Some(op);
+    }
+
+    fn col(&self, at: usize) -> usize {
+        at - self.line_start
+    }
+
+    fn newline(&mut self, at: usize) {
+        self.line += 1;
+        self.line_start = at + 1;
+    }
+
+    fn push_str(&mut self, s: &str, at: usize) {
+        let (line, col) = (self.line, self.col(at));
+        self.word
+            .get_or_insert_with(|| Word {
+                text: String::new(),
+                line,
+                col,
+                range: at..at,
+            })
+            .text
+            .push_str(s);
+    }
+
+    fn push(&mut self, c: char, at: usize) {
+        self.push_str(c.encode_utf8(&mut [0; 4]), at);
+    }
+
+    fn expansion(&mut self, start: usize, end: usize, quoted: bool) {
+        self.script.expansions.push(Expansion {
+            source: self.raw[start + 3..end - 2].to_owned(),
+            line: self.line,
+            col: self.col(start),
+            quoted,
+            range: start..end,
+            command: self.script.commands.len(),
+        });
+        let raw = self.raw;
+        self.push_str(&raw[start..end], start);
+    }
+
+    // Skips a comment, returning where it ends. GitHub substitutes ${{ }} in
+    // comments too, and a newline in the value ends the comment, so they
+    // are as unquoted as any others.
+    fn comment(&mut self, mut at: usize) -> usize {
+        while at < self.raw.len() && self.raw.as_bytes()[at] != b'\n' {
+            match self.expressions.iter().find(|(s, _)| *s == at) {
+                Some(&(start, end)) => {
+                    self.script.expansions.push(Expansion {
+                        source: self.raw[start + 3..end - 2].to_owned(),
+                        line: self.line,
+                        col: self.col(start),
+                        quoted: false,
+                        range: start..end,
+                        command: self.script.commands.len(),
+                    });
+                    at = end;
+                }
+                None => at += 1,
+            }
+        }
+        at
+    }
+
+    fn end_word(&mut self) {
+        let mut word = match self.word.take() {
+            Some(word) => word,
+            None => return,
+        };
+        word.range.end = self.at;
+
+        match self.redirect.take() {
+            Some(op) if op.starts_with("<<") && op != "<<<" => {
+                self.pending.push((self.script.commands.len(), word.text.clone()));
+                self.command.redirects.push(Redirect { op, target: word });
+            }
+            Some(op) => self.command.redirects.push(Redirect { op, target: word }),
+            None => self.command.words.push(word),
+        }
+    }
+
+    fn end_command(&mut self) {
+        self.end_word();
+        if self.command.words.is_empty() && self.command.redirects.is_empty() {
+            self.command.line = self.line;
+            return;
+        }
+        let line = self.line;
+        let mut command = std::mem::take(&mut self.command);
+        command.line = command
+            .words
+            .first()
+            .or(command.redirects.first().map(|r| &r.target))
+            .map_or(line, |w| w.line);
+        command.piped = std::mem::take(&mut self.piped);
+        self.script.commands.push(command);
+    }
+
+    // skips the bodies of any heredocs started on the line just finished,
+    // returning where the script carries on
+    fn heredocs(&mut self, mut at: usize) -> usize {
+        for (command, delimiter) in std::mem::take(&mut self.pending) {
+            while at < self.raw.len() {
+                let end = self.raw[at..].find('\n').map_or(self.raw.len(), |e| at + e);
+                let line = &self.raw[at..end];
+
+                if line.trim_start_matches('\t') == delimiter {
+                    at = end;
+                    self.newline(at);
+                    at += 1;
+                    break;
+                }
+
+                // ${{ }} is substituted whether or not the delimiter was quoted
+                let found: Vec<_> = self
+                    .expressions
+                    .iter()
+                    .copied()
+                    .filter(|(s, _)| *s >= at && *s < end)
+                    .collect();
+                for (start, e) in found {
+                    self.script.expansions.push(Expansion {
+                        source: self.raw[start + 3..e - 2].to_owned(),
+                        line: self.line,
+                        col: start - at,
+                        quoted: true,
+                        range: start..e,
+                        command,
+                    });
+                }
+
+                at = end;
+                self.newline(at);
+                at += 1;
+            }
+        }
+
+        at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(command: &Command) -> Vec<&str> {
+        command.words.iter().map(|w| w.text.as_str()).collect()
+    }
+
+    #[test]
+    fn quotes_and_escapes_are_removed_from_words() {
+        let script = parse(r#"echo "a b" 'c $d' e\ f"g"h"#);
+        assert_eq!(words(&script.commands[0]), ["echo", "a b", "c $d", "e fgh"]);
+        assert_eq!(script.commands[0].words[1].range, 5..10);
+    }
+
+    #[test]
+    fn expansions_know_whether_they_are_quoted() {
+        let script = parse("echo \"${{ a }}\" '${{ b }}' ${{ c }}");
+        let quoted: Vec<_> = script
+            .expansions
+            .iter()
+            .map(|e| (e.source.trim(), e.quoted))
+            .collect();
+        assert_eq!(quoted, [("a", true), ("b", true), ("c", false)]);
+        assert_eq!(script.expansions[2].range, 27..35);
+    }
+
+    #[test]
+    fn expansions_in_comments_are_unquoted() {
+        let script = parse("echo hi # x ${{ github.event.issue.title }}\n# ${{ inputs.y }}");
+        assert_eq!(script.commands.len(), 1);
+        let found: Vec<_> = script
+            .expansions
+            .iter()
+            .map(|e| (e.source.trim(), e.quoted, e.line, e.col))
+            .collect();
+        assert_eq!(
+            found,
+            [("github.event.issue.title", false, 0, 12), ("inputs.y", false, 1, 2)]
+        );
+    }
+
+    #[test]
+    fn heredoc_bodies_are_skipped_but_their_expansions_found() {
+        let script = parse("cat <<'EOF' > out\nbody ${{ a }}\n\tEOF\necho done");
+        assert_eq!(script.commands.len(), 2);
+        assert_eq!(script.commands[0].redirects[0].op, "<<");
+        assert_eq!(script.commands[0].redirects[1].target.text, "out");
+        assert_eq!(words(&script.commands[1]), ["echo", "done"]);
+        assert_eq!(script.commands[1].line, 3);
+
+        let e = &script.expansions[0];
+        assert_eq!((e.line, e.col, e.quoted, e.command), (1, 5, true, 0));
+    }
+
+    #[test]
+    fn redirects_and_tee_are_seen() {
+        let script = parse("echo \"x=${{ a }}\" >> \"$GITHUB_ENV\" 2>&1\necho y | tee -a $GITHUB_OUTPUT");
+        let redirects: Vec<_> = script.commands[0]
+            .redirects
+            .iter()
+            .map(|r| (r.op.as_str(), r.target.text.as_str()))
+            .collect();
+        assert_eq!(redirects, [(">>", "$GITHUB_ENV"), (">&", "1")]);
+
+        let tee = &script.commands[2];
+        assert!(tee.piped);
+        assert_eq!(tee.name(), Some("tee"));
+        assert_eq!(tee.args().map(|w| w.text.as_str()).collect::<Vec<_>>(), ["-a", "$GITHUB_OUTPUT"]);
+    }
+
+    #[test]
+    fn continued_lines_are_one_command() {
+        let script = parse("FOO=1 make \\\n  all && echo ok");
+        assert_eq!(script.commands.len(), 2);
+        assert_eq!(script.commands[0].name(), Some("make"));
+        assert_eq!(script.commands[0].words[2].text, "all");
+        assert_eq!((script.commands[0].words[2].line, script.commands[0].words[2].col), (1, 2));
+        assert_eq!(script.commands[1].line, 1);
+    }
+}
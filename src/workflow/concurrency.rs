@@ -0,0 +1,160 @@
+use super::job::Job;
+use super::on::EventKind;
+use super::{Concurrency, Workflow};
+use crate::scavenge::ast::{PossumNode, PossumNodeKind};
+
+// values that give each branch, pull request or run a group of its own
+const PER_REF: &[&str] = &[
+    "github.ref",
+    "github.head_ref",
+    "github.ref_name",
+    "github.sha",
+    "github.run_id",
+    "github.event.pull_request.number",
+    "github.event.number",
+    "github.event.pull_request.head.ref",
+];
+
+// events that mean a workflow is publishing something
+const RELEASE_EVENTS: &[EventKind] = &[EventKind::Release, EventKind::Deployment];
+
+impl Concurrency {
+    pub fn group(&self) -> Option<&str> {
+        match self {
+            Concurrency::Concurrency(group) => Some(group),
+            Concurrency::Group { group, .. } => group.as_ref()?.value().map(String::as_str),
+        }
+    }
+
+    // a Value when it is fixed, an Expr when it depends on the run
+    pub fn cancels_in_progress(&self) -> Option<&PossumNode<bool>> {
+        match self {
+            Concurrency::Concurrency(_) => None,
+            Concurrency::Group {
+                cancel_in_progress, ..
+            } => cancel_in_progress.as_ref(),
+        }
+    }
+}
+
+// A concurrency group as written, ${{ }} and all. Runs whose groups evaluate
+// to the same string queue behind, or cancel, each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupTemplate<'a>(pub &'a str);
+
+impl<'a> GroupTemplate<'a> {
+    pub fn is_static(&self) -> bool {
+        !self.0.contains("${{")
+    }
+
+    pub fn is_per_ref(&self) -> bool {
+        PER_REF.iter().any(|r| self.0.contains(r))
+    }
+
+    // github.workflow keeps workflows that share a template apart
+    pub fn is_per_workflow(&self) -> bool {
+        self.0.contains("github.workflow")
+    }
+}
+
+// workflows that publish something, where cancelling halfway is harmful
+pub fn is_release(workflow: &Workflow) -> bool {
+    let triggered = workflow
+        .on
+        .iter()
+        .filter_map(|on| on.value())
+        .any(|on| RELEASE_EVENTS.iter().any(|e| on.event(*e).is_some()));
+
+    let named = workflow
+        .name
+        .iter()
+        .filter_map(|n| n.value())
+        .any(|n| {
+            let n = n.to_lowercase();
+            n.contains("release") || n.contains("deploy") || n.contains("publish")
+        });
+
+    let environment = workflow
+        .jobs()
+        .filter_map(|(_, j)| j.value())
+        .any(|j: &Job| j.environment.is_some());
+
+    triggered || named || environment
+}
+
+// every concurrency setting in a workflow, with the job it belongs to
+pub fn groups(workflow: &Workflow) -> Vec<(Option<&PossumNode<String>>, &PossumNode<Concurrency>)> {
+    let jobs = workflow.jobs().filter_map(|(name, job)| {
+        let concurrency = job.value()?.concurrency.as_ref()?;
+        Some((Some(name), concurrency))
+    });
+
+    workflow
+        .concurrency
+        .iter()
+        .map(|c| (None, c))
+        .chain(jobs)
+        .filter(|(_, c)| matches!(c.kind(), PossumNodeKind::Value(_)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{groups, is_release, GroupTemplate};
+    use crate::workflow::parser::test_workflow;
+
+    #[test]
+    fn group_templates() {
+        // template, static, per ref, per workflow
+        let cases = [
+            ("deploy", true, false, false),
+            ("${{ github.workflow }}", false, false, true),
+            ("${{ github.workflow }}-${{ github.ref }}", false, true, true),
+            ("pr-${{ github.event.pull_request.number }}", false, true, false),
+            ("${{ inputs.environment }}", false, false, false),
+        ];
+        for (raw, fixed, per_ref, per_workflow) in cases {
+            let template = GroupTemplate(raw);
+            assert_eq!(
+                (template.is_static(), template.is_per_ref(), template.is_per_workflow()),
+                (fixed, per_ref, per_workflow),
+                "{raw}"
+            );
+        }
+    }
+
+    #[test]
+    fn releases_are_told_by_trigger_name_or_environment() {
+        let releases = [
+            "on: release\njobs:\n  build: {}\n",
+            "name: Publish docs\non: push\njobs:\n  build: {}\n",
+            "on: push\njobs:\n  ship:\n    environment: production\n",
+        ];
+        for raw in releases {
+            assert!(is_release(&test_workflow(raw)), "{raw}");
+        }
+        assert!(!is_release(&test_workflow("name: CI\non: push\njobs:\n  build: {}\n")));
+    }
+
+    #[test]
+    fn groups_of_the_workflow_and_its_jobs() {
+        let raw = "on: push\nconcurrency: ci-${{ github.ref }}\njobs:\n  build: {}\n  deploy:\n    concurrency:\n      group: deploy\n      cancel-in-progress: false\n";
+        let wf = test_workflow(raw);
+        let found: Vec<_> = groups(&wf)
+            .into_iter()
+            .map(|(job, c)| {
+                let c = c.value().unwrap();
+                let cancels = c.cancels_in_progress().and_then(|n| n.value()).copied();
+                (job.and_then(|j| j.value()).cloned(), c.group().unwrap().to_owned(), cancels)
+            })
+            .collect();
+
+        assert_eq!(
+            found,
+            [
+                (None, "ci-${{ github.ref }}".to_owned(), None),
+                (Some("deploy".to_owned()), "deploy".to_owned(), Some(false)),
+            ]
+        );
+    }
+}
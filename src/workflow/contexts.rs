@@ -0,0 +1,611 @@
+use super::job::{Job, Matrix};
+use super::on::{EventKind, WorkflowInput, WorkflowInputType};
+use super::Workflow;
+use crate::expression::schema::{Schema, Shape};
+use crate::scavenge::ast::PossumNode;
+use std::collections::BTreeMap;
+
+// The shapes of the contexts GitHub provides, following
+// https://docs.github.com/en/actions/learn-github-actions/contexts and the
+// webhook payloads behind github.event. Payload objects that are rarely
+// looked inside of are left as Any.
+
+fn s() -> Shape {
+    Shape::String
+}
+
+fn n() -> Shape {
+    Shape::Number
+}
+
+fn b() -> Shape {
+    Shape::Bool
+}
+
+fn any() -> Shape {
+    Shape::Any
+}
+
+fn strings() -> Shape {
+    Shape::map(s())
+}
+
+fn user() -> Shape {
+    Shape::object([
+        ("login", s()),
+        ("id", n()),
+        ("node_id", s()),
+        ("type", s()),
+        ("name", s()),
+        ("email", s()),
+        ("html_url", s()),
+        ("url", s()),
+        ("avatar_url", s()),
+        ("site_admin", b()),
+    ])
+}
+
+fn label() -> Shape {
+    Shape::object([
+        ("id", n()),
+        ("node_id", s()),
+        ("name", s()),
+        ("color", s()),
+        ("default", b()),
+        ("description", s()),
+        ("url", s()),
+    ])
+}
+
+fn git_ref() -> Shape {
+    Shape::object([
+        ("label", s()),
+        ("ref", s()),
+        ("sha", s()),
+        ("repo", any()),
+        ("user", user()),
+    ])
+}
+
+// the author or committer of a commit, which isn't a GitHub user
+fn git_user() -> Shape {
+    Shape::object([
+        ("name", s()),
+        ("email", s()),
+        ("username", s()),
+        ("date", s()),
+    ])
+}
+
+fn commit() -> Shape {
+    Shape::object([
+        ("id", s()),
+        ("tree_id", s()),
+        ("distinct", b()),
+        ("message", s()),
+        ("timestamp", s()),
+        ("url", s()),
+        ("author", git_user()),
+        ("committer", git_user()),
+        ("added", Shape::array(s())),
+        ("removed", Shape::array(s())),
+        ("modified", Shape::array(s())),
+    ])
+}
+
+fn pull_request() -> Shape {
+    Shape::object([
+        ("id", n()),
+        ("node_id", s()),
+        ("number", n()),
+        ("url", s()),
+        ("html_url", s()),
+        ("diff_url", s()),
+        ("patch_url", s()),
+        ("state", s()),
+        ("locked", b()),
+        ("title", s()),
+        ("body", s()),
+        ("user", user()),
+        ("labels", Shape::array(label())),
+        ("milestone", any()),
+        ("assignee", user()),
+        ("assignees", Shape::array(user())),
+        ("requested_reviewers", Shape::array(user())),
+        ("requested_teams", Shape::array(any())),
+        ("head", git_ref()),
+        ("base", git_ref()),
+        ("draft", b()),
+        ("merged", b()),
+        ("mergeable", b()),
+        ("rebaseable", b()),
+        ("mergeable_state", s()),
+        ("merged_by", user()),
+        ("merge_commit_sha", s()),
+        ("auto_merge", any()),
+        ("author_association", s()),
+        ("active_lock_reason", s()),
+        ("maintainer_can_modify", b()),
+        ("comments", n()),
+        ("review_comments", n()),
+        ("commits", n()),
+        ("additions", n()),
+        ("deletions", n()),
+        ("changed_files", n()),
+        ("created_at", s()),
+        ("updated_at", s()),
+        ("closed_at", s()),
+        ("merged_at", s()),
+    ])
+}
+
+fn issue() -> Shape {
+    Shape::object([
+        ("id", n()),
+        ("node_id", s()),
+        ("number", n()),
+        ("url", s()),
+        ("html_url", s()),
+        ("state", s()),
+        ("state_reason", s()),
+        ("locked", b()),
+        ("title", s()),
+        ("body", s()),
+        ("user", user()),
+        ("labels", Shape::array(label())),
+        ("assignee", user()),
+        ("assignees", Shape::array(user())),
+        ("milestone", any()),
+        ("comments", n()),
+        ("pull_request", any()),
+        ("author_association", s()),
+        ("created_at", s()),
+        ("updated_at", s()),
+        ("closed_at", s()),
+    ])
+}
+
+fn comment() -> Shape {
+    Shape::object([
+        ("id", n()),
+        ("node_id", s()),
+        ("url", s()),
+        ("html_url", s()),
+        ("body", s()),
+        ("user", user()),
+        ("author_association", s()),
+        ("created_at", s()),
+        ("updated_at", s()),
+        // review comments
+        ("path", s()),
+        ("position", n()),
+        ("line", n()),
+        ("commit_id", s()),
+        ("diff_hunk", s()),
+        ("in_reply_to_id", n()),
+        ("pull_request_review_id", n()),
+    ])
+}
+
+fn release() -> Shape {
+    Shape::object([
+        ("id", n()),
+        ("node_id", s()),
+        ("url", s()),
+        ("html_url", s()),
+        ("upload_url", s()),
+        ("tarball_url", s()),
+        ("zipball_url", s()),
+        ("tag_name", s()),
+        ("target_commitish", s()),
+        ("name", s()),
+        ("body", s()),
+        ("draft", b()),
+        ("prerelease", b()),
+        ("author", user()),
+        ("assets", Shape::array(any())),
+        ("created_at", s()),
+        ("published_at", s()),
+    ])
+}
+
+fn workflow_run() -> Shape {
+    Shape::object([
+        ("id", n()),
+        ("name", s()),
+        ("node_id", s()),
+        ("display_title", s()),
+        ("path", s()),
+        ("head_branch", s()),
+        ("head_sha", s()),
+        ("head_commit", commit()),
+        ("head_repository", any()),
+        ("repository", any()),
+        ("event", s()),
+        ("status", s()),
+        ("conclusion", s()),
+        ("workflow_id", n()),
+        ("run_number", n()),
+        ("run_attempt", n()),
+        ("html_url", s()),
+        ("url", s()),
+        ("pull_requests", Shape::array(any())),
+        ("actor", user()),
+        ("triggering_actor", user()),
+        ("created_at", s()),
+        ("updated_at", s()),
+        ("run_started_at", s()),
+    ])
+}
+
+// what every webhook payload can have
+fn common() -> Vec<(&'static str, Shape)> {
+    vec![
+        ("action", s()),
+        ("sender", user()),
+        ("repository", any()),
+        ("organization", any()),
+        ("installation", any()),
+        ("enterprise", any()),
+    ]
+}
+
+// github.event for a single event
+pub fn payload(kind: EventKind) -> Shape {
+    use EventKind::*;
+
+    let specific: Vec<(&str, Shape)> = match kind {
+        Push => vec![
+            ("ref", s()),
+            ("before", s()),
+            ("after", s()),
+            ("base_ref", s()),
+            ("compare", s()),
+            ("created", b()),
+            ("deleted", b()),
+            ("forced", b()),
+            ("commits", Shape::array(commit())),
+            ("head_commit", commit()),
+            ("pusher", user()),
+        ],
+        PullRequest | PullRequestTarget => vec![
+            ("number", n()),
+            ("pull_request", pull_request()),
+            ("label", label()),
+            ("assignee", user()),
+            ("requested_reviewer", user()),
+            ("requested_team", any()),
+            ("before", s()),
+            ("after", s()),
+            ("changes", any()),
+        ],
+        PullRequestReview => vec![
+            ("pull_request", pull_request()),
+            (
+                "review",
+                Shape::object([
+                    ("id", n()),
+                    ("node_id", s()),
+                    ("body", s()),
+                    ("state", s()),
+                    ("user", user()),
+                    ("html_url", s()),
+                    ("commit_id", s()),
+                    ("author_association", s()),
+                    ("submitted_at", s()),
+                ]),
+            ),
+            ("changes", any()),
+        ],
+        PullRequestReviewComment => vec![
+            ("pull_request", pull_request()),
+            ("comment", comment()),
+            ("changes", any()),
+        ],
+        Issues => vec![
+            ("issue", issue()),
+            ("label", label()),
+            ("assignee", user()),
+            ("milestone", any()),
+            ("changes", any()),
+        ],
+        IssueComment => vec![("issue", issue()), ("comment", comment()), ("changes", any())],
+        Release => vec![("release", release()), ("changes", any())],
+        WorkflowDispatch => vec![("inputs", strings()), ("ref", s()), ("workflow", s())],
+        WorkflowRun => vec![("workflow_run", workflow_run()), ("workflow", any())],
+        Schedule => vec![("schedule", s())],
+        RepositoryDispatch => vec![("branch", s()), ("client_payload", any())],
+        Create | Delete => vec![
+            ("ref", s()),
+            ("ref_type", s()),
+            ("master_branch", s()),
+            ("description", s()),
+            ("pusher_type", s()),
+        ],
+        Discussion => vec![("discussion", any()), ("answer", any()), ("label", label()), ("changes", any())],
+        DiscussionComment => vec![("discussion", any()), ("comment", comment()), ("changes", any())],
+        MergeGroup => vec![(
+            "merge_group",
+            Shape::object([
+                ("head_sha", s()),
+                ("head_ref", s()),
+                ("base_sha", s()),
+                ("base_ref", s()),
+                ("head_commit", commit()),
+            ]),
+        )],
+        CheckRun => vec![("check_run", any()), ("requested_action", any())],
+        CheckSuite => vec![("check_suite", any())],
+        Deployment => vec![("deployment", any()), ("workflow", any()), ("workflow_run", workflow_run())],
+        DeploymentStatus => vec![
+            ("deployment", any()),
+            (
+                "deployment_status",
+                Shape::object([
+                    ("id", n()),
+                    ("state", s()),
+                    ("environment", s()),
+                    ("environment_url", s()),
+                    ("target_url", s()),
+                    ("log_url", s()),
+                    ("description", s()),
+                    ("creator", user()),
+                    ("created_at", s()),
+                    ("updated_at", s()),
+                ]),
+            ),
+            ("check_run", any()),
+            ("workflow", any()),
+            ("workflow_run", workflow_run()),
+        ],
+        Label => vec![("label", label()), ("changes", any())],
+        Milestone => vec![("milestone", any()), ("changes", any())],
+        Fork => vec![("forkee", any())],
+        Gollum => vec![("pages", Shape::array(any()))],
+        PageBuild => vec![("build", any()), ("id", n())],
+        Project => vec![("project", any()), ("changes", any())],
+        ProjectCard => vec![("project_card", any()), ("changes", any())],
+        ProjectColumn => vec![("project_column", any()), ("changes", any())],
+        RegistryPackage => vec![("registry_package", any()), ("package", any())],
+        Status => vec![
+            ("sha", s()),
+            ("state", s()),
+            ("context", s()),
+            ("description", s()),
+            ("target_url", s()),
+            ("branches", Shape::array(any())),
+            ("commit", any()),
+            ("id", n()),
+            ("name", s()),
+        ],
+        BranchProtectionRule => vec![("rule", any()), ("changes", any())],
+        Watch | Public => vec![],
+        // the payload is whatever triggered the calling workflow
+        WorkflowCall => return any(),
+    };
+
+    Shape::object(common().into_iter().chain(specific))
+}
+
+fn github_context(events: &[EventKind]) -> Shape {
+    let event = events
+        .iter()
+        .map(|e| payload(*e))
+        .reduce(Shape::union)
+        .unwrap_or(Shape::Any);
+
+    Shape::object([
+        ("action", s()),
+        ("action_path", s()),
+        ("action_ref", s()),
+        ("action_repository", s()),
+        ("action_status", s()),
+        ("actor", s()),
+        ("actor_id", s()),
+        ("api_url", s()),
+        ("base_ref", s()),
+        ("env", s()),
+        ("event", event),
+        ("event_name", s()),
+        ("event_path", s()),
+        ("graphql_url", s()),
+        ("head_ref", s()),
+        ("job", s()),
+        ("path", s()),
+        ("ref", s()),
+        ("ref_name", s()),
+        ("ref_protected", b()),
+        ("ref_type", s()),
+        ("repository", s()),
+        ("repository_id", s()),
+        ("repository_owner", s()),
+        ("repository_owner_id", s()),
+        ("repositoryurl", s()),
+        ("retention_days", s()),
+        ("run_id", s()),
+        ("run_number", s()),
+        ("run_attempt", s()),
+        ("secret_source", s()),
+        ("server_url", s()),
+        ("sha", s()),
+        ("token", s()),
+        ("triggering_actor", s()),
+        ("workflow", s()),
+        ("workflow_ref", s()),
+        ("workflow_sha", s()),
+        ("workspace", s()),
+    ])
+}
+
+fn runner_context() -> Shape {
+    Shape::object([
+        ("name", s()),
+        ("os", s()),
+        ("arch", s()),
+        ("temp", s()),
+        ("tool_cache", s()),
+        ("debug", s()),
+        ("environment", s()),
+    ])
+}
+
+fn job_context() -> Shape {
+    let container = Shape::object([("id", s()), ("network", s())]);
+    let service = Shape::object([("id", s()), ("network", s()), ("ports", strings())]);
+
+    Shape::object([
+        ("check_run_id", n()),
+        ("container", container),
+        ("services", Shape::map(service)),
+        ("status", s()),
+    ])
+}
+
+fn strategy_context() -> Shape {
+    Shape::object([
+        ("fail-fast", b()),
+        ("job-index", n()),
+        ("job-total", n()),
+        ("max-parallel", n()),
+    ])
+}
+
+fn needs_context() -> Shape {
+    Shape::map(Shape::object([("outputs", strings()), ("result", s())]))
+}
+
+fn steps_context() -> Shape {
+    Shape::map(Shape::object([
+        ("outputs", strings()),
+        ("conclusion", s()),
+        ("outcome", s()),
+    ]))
+}
+
+// the keys a job's matrix defines, None if they can't be known
+pub fn matrix_keys(matrix: &Matrix) -> Option<Vec<&str>> {
+    let mut keys: Vec<&str> = matrix
+        .entries
+        .iter()
+        .filter_map(|(k, _)| k.value())
+        .map(String::as_str)
+        .collect();
+
+    if let Some(include) = matrix.include.as_ref() {
+        for extra in include.value()?.iter() {
+            keys.extend(extra.value()?.iter().filter_map(|(k, _)| k.value()).map(String::as_str));
+        }
+    }
+
+    keys.sort_unstable();
+    keys.dedup();
+    Some(keys)
+}
+
+// the matrix context of a job, Any when it can't be worked out
+pub fn matrix_context(job: &Job) -> Shape {
+    let matrix = match job.strategy.as_ref().and_then(|s| s.value()?.matrix.as_ref()) {
+        Some(m) => m,
+        None => return Shape::object([]),
+    };
+
+    match matrix.value().and_then(matrix_keys) {
+        Some(keys) => Shape::object(keys.into_iter().map(|k| (k, any()))),
+        None => any(),
+    }
+}
+
+// inputs declared by any of the given triggers, by name
+pub fn declared_inputs<'a>(
+    wf: &'a Workflow,
+    kinds: &[EventKind],
+) -> Vec<(&'a PossumNode<String>, &'a WorkflowInput)> {
+    let on = match wf.on.as_ref().and_then(|on| on.value()) {
+        Some(on) => on,
+        None => return Vec::new(),
+    };
+
+    on.events()
+        .filter(|(kind, _)| kind.value().map_or(false, |k| kinds.contains(k)))
+        .filter_map(|(_, event)| event.value()?.inputs.as_ref()?.value())
+        .flat_map(|inputs| inputs.iter())
+        .filter_map(|(name, input)| Some((name, input.value()?)))
+        .collect()
+}
+
+// inputs declared by workflow_dispatch and workflow_call
+pub fn inputs_context(wf: &Workflow) -> Shape {
+    let mut fields = BTreeMap::new();
+    for (name, input) in declared_inputs(wf, &[EventKind::WorkflowDispatch, EventKind::WorkflowCall]) {
+        let name = match name.value() {
+            Some(name) => name.clone(),
+            None => continue,
+        };
+        let shape = match input.input_type.as_ref().and_then(|t| t.value()) {
+            Some(WorkflowInputType::Bool) => b(),
+            Some(WorkflowInputType::Number) => n(),
+            Some(WorkflowInputType::Str | WorkflowInputType::Choice | WorkflowInputType::Environment) => s(),
+            None => any(),
+        };
+        let merged = match fields.remove(&name) {
+            Some(existing) => Shape::union(existing, shape),
+            None => shape,
+        };
+        fields.insert(name, merged);
+    }
+
+    Shape::Object(fields)
+}
+// every context as it is inside of job, or at the top of the workflow
+pub fn schema(wf: &Workflow, job: Option<&Job>) -> Schema {
+    let events: Vec<EventKind> = wf
+        .on
+        .as_ref()
+        .and_then(|on| on.value())
+        .map(|on| on.events().filter_map(|(k, _)| k.value().copied()).collect())
+        .unwrap_or_default();
+
+    let contexts = [
+        ("github", github_context(&events)),
+        ("env", strings()),
+        ("vars", strings()),
+        ("secrets", strings()),
+        ("inputs", inputs_context(wf)),
+        ("job", job_context()),
+        ("runner", runner_context()),
+        ("strategy", strategy_context()),
+        ("matrix", job.map_or_else(|| Shape::object([]), matrix_context)),
+        ("needs", needs_context()),
+        ("steps", steps_context()),
+    ];
+
+    Schema::new(contexts.into_iter().map(|(k, v)| (k.to_owned(), v)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::payload;
+    use crate::expression::schema::Shape;
+    use crate::workflow::on::EventKind;
+
+    fn at<'a>(shape: &'a Shape, path: &[&str]) -> Option<&'a Shape> {
+        path.iter().try_fold(shape, |shape, name| match shape {
+            Shape::Object(fields) => fields.get(*name),
+            Shape::Array(of) if *name == "*" => Some(of.as_ref()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn commit_authors_are_git_users() {
+        let push = payload(EventKind::Push);
+        for commit in [&["head_commit"][..], &["commits", "*"]] {
+            for who in ["author", "committer"] {
+                let path = |field| [commit, &[who, field]].concat();
+                assert_eq!(at(&push, &path("username")), Some(&Shape::String));
+                assert_eq!(at(&push, &path("date")), Some(&Shape::String));
+                assert_eq!(at(&push, &path("login")), None);
+            }
+        }
+    }
+}
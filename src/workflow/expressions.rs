@@ -0,0 +1,167 @@
+use super::job::{Environment, Job, JobSecrets, RunsOn};
+use super::{Concurrency, Workflow};
+use crate::document::{AsDocumentPointer, DocumentSpan};
+use crate::expression::parser::{Expr, ParseError};
+use crate::expression::{embedded, Condition};
+use crate::scavenge::ast::{PossumMap, PossumNode};
+
+// Where in a workflow an expression is, which decides the contexts it can use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Place {
+    RunName,
+    WorkflowEnv,
+    WorkflowConcurrency,
+    JobIf,
+    JobName,
+    RunsOn,
+    JobEnv,
+    Environment,
+    JobOutputs,
+    JobConcurrency,
+    JobWith,
+    JobSecrets,
+    StepIf,
+    Step,
+}
+
+impl Place {
+    // https://docs.github.com/en/actions/learn-github-actions/contexts#context-availability
+    pub fn contexts(&self) -> &'static [&'static str] {
+        use Place::*;
+        match self {
+            RunName | WorkflowConcurrency => &["github", "inputs", "vars"],
+            WorkflowEnv => &["github", "secrets", "inputs", "vars"],
+            JobIf => &["github", "needs", "vars", "inputs"],
+            JobName | RunsOn | Environment | JobConcurrency | JobWith => {
+                &["github", "needs", "strategy", "matrix", "vars", "inputs"]
+            }
+            JobEnv | JobSecrets => &["github", "needs", "strategy", "matrix", "vars", "secrets", "inputs"],
+            StepIf => &[
+                "github", "needs", "strategy", "matrix", "job", "runner", "env", "vars", "steps", "inputs",
+            ],
+            JobOutputs | Step => &[
+                "github", "needs", "strategy", "matrix", "job", "runner", "env", "vars", "secrets",
+                "steps", "inputs",
+            ],
+        }
+    }
+
+    pub fn is_condition(&self) -> bool {
+        matches!(self, Place::JobIf | Place::StepIf)
+    }
+}
+
+// A string that can hold expressions
+#[derive(Debug, Clone)]
+pub struct Site<'a> {
+    pub place: Place,
+    pub raw: &'a str,
+    pub at: DocumentSpan,
+}
+
+impl<'a> Site<'a> {
+    fn new(place: Place, raw: &'a str, at: &impl AsDocumentPointer) -> Site<'a> {
+        Site {
+            place,
+            raw,
+            at: at.as_document_span(),
+        }
+    }
+
+    fn node(place: Place, node: &'a PossumNode<String>) -> Option<Site<'a>> {
+        node.value().map(|raw| Site::new(place, raw, node))
+    }
+
+    // conditions don't need ${{ }}, everywhere else does
+    pub fn expressions(&self) -> Result<Vec<Expr>, ParseError> {
+        if self.place.is_condition() {
+            return Ok(match Condition::parse(self.raw)? {
+                Condition::Expression(e) => vec![e],
+                Condition::Template(_, parts) => parts,
+            });
+        }
+
+        embedded(self.raw)?.iter().map(|e| e.parse()).collect()
+    }
+}
+
+fn values<'a>(
+    place: Place,
+    map: &'a Option<PossumNode<PossumMap<String, String>>>,
+) -> impl Iterator<Item = Site<'a>> {
+    map.iter()
+        .filter_map(|m| m.value())
+        .flat_map(|m| m.iter())
+        .filter_map(move |(_, v)| Site::node(place, v))
+}
+
+fn concurrency(place: Place, node: &Option<PossumNode<Concurrency>>) -> Option<Site> {
+    let node = node.as_ref()?;
+    match node.value()? {
+        Concurrency::Concurrency(group) => Some(Site::new(place, group, node)),
+        Concurrency::Group { group, .. } => Site::node(place, group.as_ref()?),
+    }
+}
+
+// strings at the top of the workflow, outside of any job
+pub fn workflow_sites(wf: &Workflow) -> Vec<Site> {
+    let mut sites: Vec<Site> = wf
+        .run_name
+        .iter()
+        .filter_map(|n| Site::node(Place::RunName, n))
+        .collect();
+    sites.extend(values(Place::WorkflowEnv, &wf.env));
+    sites.extend(concurrency(Place::WorkflowConcurrency, &wf.concurrency));
+    sites
+}
+
+// strings in a job and its steps
+pub fn job_sites(job: &Job) -> Vec<Site> {
+    let mut sites = Vec::new();
+
+    sites.extend(job.cond.iter().filter_map(|n| Site::node(Place::JobIf, n)));
+    sites.extend(job.name.iter().filter_map(|n| Site::node(Place::JobName, n)));
+
+    if let Some(runs_on) = job.runs_on.as_ref() {
+        match runs_on.value() {
+            Some(RunsOn::Expression(raw)) => sites.push(Site::new(Place::RunsOn, raw, runs_on)),
+            Some(r) => sites.extend(r.labels().filter_map(|l| Site::node(Place::RunsOn, l))),
+            None => {}
+        }
+        if let Some(RunsOn::Group { group: Some(g), .. }) = runs_on.value() {
+            sites.extend(Site::node(Place::RunsOn, g));
+        }
+    }
+
+    sites.extend(values(Place::JobEnv, &job.env));
+
+    if let Some(environment) = job.environment.as_ref() {
+        match environment.value() {
+            Some(Environment::Bare(raw)) => sites.push(Site::new(Place::Environment, raw, environment)),
+            Some(Environment::Env { name, url }) => {
+                sites.extend(name.iter().filter_map(|n| Site::node(Place::Environment, n)));
+                // the url is evaluated after the job, like a step
+                sites.extend(url.iter().filter_map(|n| Site::node(Place::Step, n)));
+            }
+            None => {}
+        }
+    }
+
+    sites.extend(values(Place::JobOutputs, &job.outputs));
+    sites.extend(concurrency(Place::JobConcurrency, &job.concurrency));
+    sites.extend(values(Place::JobWith, &job.with));
+
+    if let Some(JobSecrets::Secrets(passed)) = job.secrets.as_ref().and_then(|s| s.value()) {
+        sites.extend(passed.iter().filter_map(|(_, v)| Site::node(Place::JobSecrets, v)));
+    }
+
+    for step in job.steps().filter_map(|s| s.value()) {
+        sites.extend(step.cond.iter().filter_map(|n| Site::node(Place::StepIf, n)));
+        let strings = step.name.iter().chain(step.run.iter()).chain(step.working_directory.iter());
+        sites.extend(strings.filter_map(|n| Site::node(Place::Step, n)));
+        sites.extend(values(Place::Step, &step.with));
+        sites.extend(values(Place::Step, &step.env));
+    }
+
+    sites
+}
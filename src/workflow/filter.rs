@@ -0,0 +1,316 @@
+use std::fmt::Display;
+
+// The glob syntax used by branch, tag and path filters:
+//
+//   *    anything but /
+//   **   anything at all, and **/ also matches no directories at all
+//   ?    zero or one of the preceding character
+//   +    one or more of the preceding character
+//   []   one of the listed characters or ranges, eg. [a-z0-9]
+//   !    at the start, excludes what an earlier pattern included
+//   \    escapes the next character
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterPattern {
+    negated: bool,
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Item {
+    atom: Atom,
+    repeat: Repeat,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Atom {
+    Char(char),
+    Class(Vec<(char, char)>),
+    Segment,
+    Path,
+    Dirs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Repeat {
+    Once,
+    Optional,
+    OneOrMore,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterError {
+    Empty,
+    NothingToRepeat(char),
+    UnclosedClass,
+    TrailingEscape,
+}
+
+impl Display for FilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterError::Empty => write!(f, "pattern is empty"),
+            FilterError::NothingToRepeat(c) => {
+                write!(f, "{c} must follow a character or [] to repeat")
+            }
+            FilterError::UnclosedClass => write!(f, "[ is never closed"),
+            FilterError::TrailingEscape => write!(f, "pattern ends with an escape"),
+        }
+    }
+}
+
+impl FilterPattern {
+    pub fn parse(raw: &str) -> Result<FilterPattern, FilterError> {
+        let (negated, body) = match raw.strip_prefix('!') {
+            Some(body) => (true, body),
+            None => (false, raw),
+        };
+
+        if body.is_empty() {
+            return Err(FilterError::Empty);
+        }
+
+        let mut items: Vec<Item> = Vec::new();
+        let mut chars = body.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            let atom = match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    // a whole **/ segment, so docs/**/*.md matches docs/README.md
+                    let whole = match items.last() {
+                        None => true,
+                        Some(last) => last.atom == Atom::Char('/') && last.repeat == Repeat::Once,
+                    };
+                    if whole && chars.peek() == Some(&'/') {
+                        chars.next();
+                        Atom::Dirs
+                    } else {
+                        Atom::Path
+                    }
+                }
+                '*' => Atom::Segment,
+                '?' | '+' => {
+                    let repeat = if c == '?' { Repeat::Optional } else { Repeat::OneOrMore };
+                    match items.last_mut() {
+                        Some(last)
+                            if last.repeat == Repeat::Once
+                                && matches!(last.atom, Atom::Char(_) | Atom::Class(_)) =>
+                        {
+                            last.repeat = repeat;
+                            continue;
+                        }
+                        _ => return Err(FilterError::NothingToRepeat(c)),
+                    }
+                }
+                '[' => {
+                    let mut ranges = Vec::new();
+                    loop {
+                        match chars.next() {
+                            None => return Err(FilterError::UnclosedClass),
+                            Some(']') => break,
+                            Some(start) => {
+                                let mut ahead = chars.clone();
+                                match (ahead.next(), ahead.next()) {
+                                    (Some('-'), Some(end)) if end != ']' => {
+                                        chars.next();
+                                        chars.next();
+                                        ranges.push((start, end));
+                                    }
+                                    _ => ranges.push((start, start)),
+                                }
+                            }
+                        }
+                    }
+                    Atom::Class(ranges)
+                }
+                '\\' => match chars.next() {
+                    Some(escaped) => Atom::Char(escaped),
+                    None => return Err(FilterError::TrailingEscape),
+                },
+                c => Atom::Char(c),
+            };
+
+            items.push(Item {
+                atom,
+                repeat: Repeat::Once,
+            });
+        }
+
+        Ok(FilterPattern { negated, items })
+    }
+
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+
+    // whether the pattern, ignoring any !, matches
+    pub fn matches(&self, value: &str) -> bool {
+        let value: Vec<char> = value.chars().collect();
+        matches(&self.items, &value)
+    }
+
+    // the text before the first wildcard, eg. refs/heads/ for refs/heads/*
+    pub fn literal_prefix(&self) -> String {
+        self.items
+            .iter()
+            .map_while(|i| match (&i.atom, i.repeat) {
+                (Atom::Char(c), Repeat::Once) => Some(*c),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn matches(items: &[Item], value: &[char]) -> bool {
+    let (item, rest) = match items.split_first() {
+        Some(split) => split,
+        None => return value.is_empty(),
+    };
+
+    match item.atom {
+        Atom::Segment => (0..=value.len())
+            .take_while(|k| *k == 0 || value[*k - 1] != '/')
+            .any(|k| matches(rest, &value[k..])),
+        Atom::Path => (0..=value.len()).any(|k| matches(rest, &value[k..])),
+        Atom::Dirs => (0..=value.len())
+            .filter(|k| *k == 0 || value[*k - 1] == '/')
+            .any(|k| matches(rest, &value[k..])),
+        _ => {
+            let one = |c: &char| match &item.atom {
+                Atom::Char(a) => a == c,
+                Atom::Class(ranges) => ranges.iter().any(|(lo, hi)| lo <= c && c <= hi),
+                _ => false,
+            };
+
+            match item.repeat {
+                Repeat::Once => value.first().map_or(false, one) && matches(rest, &value[1..]),
+                Repeat::Optional => {
+                    matches(rest, value)
+                        || (value.first().map_or(false, one) && matches(rest, &value[1..]))
+                }
+                Repeat::OneOrMore => {
+                    let run = value.iter().take_while(|c| one(c)).count();
+                    (1..=run).any(|k| matches(rest, &value[k..]))
+                }
+            }
+        }
+    }
+}
+
+// Patterns are applied in order and the last one to match decides, so a
+// negation only excludes what came before it.
+pub fn included<'a>(patterns: impl IntoIterator<Item = &'a FilterPattern>, value: &str) -> bool {
+    patterns
+        .into_iter()
+        .filter(|p| p.matches(value))
+        .last()
+        .map_or(false, |p| !p.is_negated())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(raw: &str) -> FilterPattern {
+        FilterPattern::parse(raw).unwrap()
+    }
+
+    fn patterns(raw: &[&str]) -> Vec<FilterPattern> {
+        raw.iter().map(|p| pattern(p)).collect()
+    }
+
+    // the examples from GitHub's filter pattern cheat sheet
+    #[test]
+    fn branches_and_tags() {
+        let table: &[(&str, &[&str], &[&str])] = &[
+            ("feature/*", &["feature/my-branch", "feature/your-branch"], &["feature/beta-a/my-branch"]),
+            ("feature/**", &["feature/beta-a/my-branch", "feature/your-branch", "feature/mona/the/octocat"], &["features"]),
+            ("main", &["main"], &["mainline", "main/x"]),
+            ("releases/mona-the-octocat", &["releases/mona-the-octocat"], &["releases/mona"]),
+            ("*", &["main", "releases"], &["releases/v1"]),
+            ("**", &["all/the/branches", "every/tag", "main"], &[]),
+            ("*feature", &["mona-feature", "feature", "ver-10-feature"], &["feature-1"]),
+            ("v2*", &["v2", "v2.0", "v2.9"], &["v1", "v2/beta"]),
+            ("v[12].[0-9]+.[0-9]+", &["v1.10.1", "v2.0.0"], &["v3.0.0", "v1.0", "v1.a.0"]),
+        ];
+
+        check(table);
+    }
+
+    #[test]
+    fn paths() {
+        let table: &[(&str, &[&str], &[&str])] = &[
+            ("*", &["README.md", "server.rb"], &["docs/README.md"]),
+            ("*.jsx?", &["page.js", "page.jsx"], &["page.jsxx", "src/page.js"]),
+            ("**", &["all/the/files.md"], &[]),
+            ("*.js", &["app.js", "index.js"], &["js/index.js"]),
+            ("**.js", &["index.js", "js/index.js", "src/js/app.js"], &["index.ts"]),
+            ("docs/*", &["docs/README.md", "docs/file.txt"], &["docs/mona/octocat.txt"]),
+            ("docs/**", &["docs/README.md", "docs/mona/octocat.txt"], &["README.md"]),
+            ("docs/**/*.md", &["docs/README.md", "docs/mona/hello-world.md", "docs/a/markdown/file.md"], &["docs/mona/hello.txt"]),
+            ("**/docs/**", &["docs/hello.md", "dir/docs/my-file.txt", "space/docs/plan/space.doc"], &["documents/hello.md"]),
+            ("**/README.md", &["README.md", "js/README.md"], &["README.txt"]),
+            ("**/*src/**", &["a/src/app.js", "my-src/code/js/app.js"], &["src.js"]),
+            ("**/*-post.md", &["my-post.md", "path/their-post.md"], &["post.md"]),
+            ("**/migrate-*.sql", &["migrate-10909.sql", "db/migrate-v1.0.sql", "db/sept/migrate-v1.sql"], &["migrate.sql"]),
+        ];
+
+        check(table);
+    }
+
+    fn check(table: &[(&str, &[&str], &[&str])]) {
+        for (raw, yes, no) in table {
+            let p = pattern(raw);
+            for value in yes.iter() {
+                assert!(p.matches(value), "{raw} should match {value}");
+            }
+            for value in no.iter() {
+                assert!(!p.matches(value), "{raw} should not match {value}");
+            }
+        }
+    }
+
+    #[test]
+    fn segment_stops_at_slash() {
+        let p = pattern("a/*/c");
+        assert!(p.matches("a/b/c"));
+        assert!(p.matches("a//c"));
+        assert!(!p.matches("a/b/b/c"));
+    }
+
+    #[test]
+    fn last_match_wins() {
+        let ps = patterns(&["*.md", "!README.md"]);
+        assert!(included(&ps, "hello.md"));
+        assert!(!included(&ps, "README.md"));
+        assert!(!included(&ps, "docs/hello.md"));
+
+        let ps = patterns(&["*.md", "!README.md", "README*"]);
+        assert!(included(&ps, "hello.md"));
+        assert!(included(&ps, "README.md"));
+        assert!(included(&ps, "README.doc"));
+
+        // a negation alone excludes everything
+        assert!(!included(&patterns(&["!main"]), "dev"));
+    }
+
+    #[test]
+    fn escapes_and_classes() {
+        assert!(pattern("a\\*b").matches("a*b"));
+        assert!(!pattern("a\\*b").matches("axb"));
+        assert!(pattern("[a-c-]x").matches("-x"));
+        assert!(pattern("[a-c]+").matches("abcba"));
+        assert_eq!(pattern("refs/heads/*").literal_prefix(), "refs/heads/");
+        assert!(pattern("!main").is_negated());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(FilterPattern::parse(""), Err(FilterError::Empty));
+        assert_eq!(FilterPattern::parse("!"), Err(FilterError::Empty));
+        assert_eq!(FilterPattern::parse("+a"), Err(FilterError::NothingToRepeat('+')));
+        assert_eq!(FilterPattern::parse("*?"), Err(FilterError::NothingToRepeat('?')));
+        assert_eq!(FilterPattern::parse("[ab"), Err(FilterError::UnclosedClass));
+        assert_eq!(FilterPattern::parse("ab\\"), Err(FilterError::TrailingEscape));
+    }
+}
@@ -1,4 +1,5 @@
 use super::Concurrency;
+use super::Defaults;
 use super::Permission;
 use crate::scavenge::ast::*;
 
@@ -9,7 +10,7 @@ possum_node_type!(
         permissions: Permission,
         needs: PossumSeq<String>,
         cond: String,
-        runs_on: PossumSeq<String>,
+        runs_on: RunsOn,
         environment: Environment,
         concurrency: Concurrency,
         outputs: PossumMap<String, String>,
@@ -19,10 +20,52 @@ possum_node_type!(
         continue_on_error: bool,
         uses: String,
         with: PossumMap<String, String>,
+        secrets: JobSecrets,
         strategy: Strategy,
+        defaults: Defaults,
     }
 );
 
+impl Job {
+    // every step that parsed
+    pub fn steps(&self) -> impl Iterator<Item = &PossumNode<Step>> {
+        self.steps
+            .iter()
+            .filter_map(|steps| steps.value())
+            .flat_map(|steps| steps.iter())
+    }
+}
+
+#[derive(Debug)]
+pub enum RunsOn {
+    Labels(PossumSeq<String>),
+    Group {
+        group: Option<PossumNode<String>>,
+        labels: Option<PossumNode<PossumSeq<String>>>,
+    },
+    Expression(String),
+}
+
+impl RunsOn {
+    // the labels a runner must have, whichever form was used
+    pub fn labels(&self) -> impl Iterator<Item = &PossumNode<String>> {
+        let labels = match self {
+            RunsOn::Labels(labels) => Some(labels),
+            RunsOn::Group { labels, .. } => labels.as_ref().and_then(|l| l.value()),
+            RunsOn::Expression(_) => None,
+        };
+
+        labels.into_iter().flat_map(|l| l.iter())
+    }
+}
+
+// what a job calling a reusable workflow passes as its secrets
+#[derive(Debug)]
+pub enum JobSecrets {
+    Inherit,
+    Secrets(PossumMap<String, String>),
+}
+
 #[derive(Debug)]
 pub enum Environment {
     Bare(String),
@@ -41,11 +84,39 @@ possum_node_type!(
         uses: String,
         run: String,
         shell: String,
+        working_directory: String,
+        continue_on_error: bool,
+        timeout_minutes: f64,
         with: PossumMap<String, String>,
         env: PossumMap<String, String>,
     }
 );
 
+impl Step {
+    // Remote actions and docker images that aren't pinned to a commit sha or
+    // image digest. Local actions come from the same commit as the workflow.
+    pub fn unpinned_action(&self) -> Option<&PossumNode<String>> {
+        let uses = self.uses.as_ref()?;
+        let raw = uses.value()?;
+
+        let pinned = if raw.starts_with("./") {
+            true
+        } else if let Some(image) = raw.strip_prefix("docker://") {
+            image.contains("@sha256:")
+        } else {
+            raw.split_once('@').map_or(false, |(_, r)| {
+                r.len() == 40 && r.chars().all(|c| c.is_ascii_hexdigit())
+            })
+        };
+
+        if pinned {
+            None
+        } else {
+            Some(uses)
+        }
+    }
+}
+
 possum_node_type!(
     #[derive(Debug, Default)]
     struct Strategy {
@@ -0,0 +1,52 @@
+use crate::document::{Annotatable, Annotation};
+use crate::lint::{rules, LintRule};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::concurrency::{groups, is_release, GroupTemplate};
+use crate::workflow::Workflow;
+
+pub struct ConcurrencyGroups;
+
+impl LintRule<Workflow> for ConcurrencyGroups {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        let release = is_release(wf);
+
+        for (_, concurrency) in groups(wf) {
+            let c = match concurrency.value() {
+                Some(c) => c,
+                None => continue,
+            };
+
+            if let Some(group) = c.group() {
+                let template = GroupTemplate(group);
+                if template.is_static() {
+                    annotations.annotate(Annotation::warn(
+                        &rules::SERIALIZED_CONCURRENCY,
+                        concurrency,
+                        &format!("concurrency group {group} is the same for every run, only one run at a time goes ahead"),
+                    ))
+                } else if !template.is_per_ref() {
+                    annotations.annotate(Annotation::warn(
+                        &rules::SERIALIZED_CONCURRENCY,
+                        concurrency,
+                        &format!("concurrency group {group} is shared by every branch, runs on one branch wait for the others"),
+                    ))
+                }
+            }
+
+            if let Some(cancel) = c.cancels_in_progress() {
+                if release && cancel.value() == Some(&true) {
+                    annotations.annotate(Annotation::warn(
+                        &rules::CANCELLED_RELEASE,
+                        cancel,
+                        &String::from("cancel-in-progress can stop a release or deployment part way through"),
+                    ))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::document::{Annotatable, Annotation};
+use crate::expression::Condition;
+use crate::lint::{rules, LintRule};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::Workflow;
+
+pub struct Conditions;
+
+impl LintRule<Workflow> for Conditions {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        let conds = wf
+            .jobs()
+            .filter_map(|(_, job)| job.value())
+            .flat_map(|job| {
+                let steps = job.steps().filter_map(|s| s.value()?.cond.as_ref());
+                job.cond.iter().chain(steps)
+            });
+
+        for cond in conds {
+            check(cond, annotations);
+        }
+    }
+}
+
+fn check(cond: &PossumNode<String>, annotations: &mut impl Annotatable) {
+    let raw = match cond.value() {
+        Some(raw) => raw,
+        None => return,
+    };
+
+    let parsed = match Condition::parse(raw) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            annotations.annotate(Annotation::error(&rules::INVALID_EXPRESSION, cond, &e.to_string()));
+            return;
+        }
+    };
+
+    if let Condition::Template(..) = parsed {
+        annotations.annotate(Annotation::warn(
+            &rules::CONSTANT_CONDITION,
+            cond,
+            &"condition is always true, the text outside ${{ }} makes it a non-empty string",
+        ));
+        return;
+    }
+
+    let constant = match parsed.constant() {
+        Ok(constant) => constant,
+        Err(e) => {
+            annotations.annotate(Annotation::error(&rules::INVALID_EXPRESSION, cond, &e.to_string()));
+            return;
+        }
+    };
+
+    match constant {
+        Some(true) => annotations.annotate(Annotation::warn(
+            &rules::CONSTANT_CONDITION,
+            cond,
+            &format!("condition {raw} is always true"),
+        )),
+        Some(false) => annotations.annotate(Annotation::warn(
+            &rules::CONSTANT_CONDITION,
+            cond,
+            &format!("condition {raw} is always false, so this never runs"),
+        )),
+        None => {}
+    }
+}
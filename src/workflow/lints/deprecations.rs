@@ -0,0 +1,75 @@
+use crate::document::{Annotatable, Annotation};
+use crate::lint::deprecations::deprecations;
+use crate::lint::{rules, LintRule};
+use crate::scavenge::ast::{PossumMap, PossumNode};
+use crate::workflow::job::Step;
+use crate::workflow::Workflow;
+
+// Actions on retired Node runtimes and environment variables that turn
+// deprecated behaviour back on, as listed in the deprecations table
+pub struct Deprecations;
+
+impl LintRule<Workflow> for Deprecations {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        env(&wf.env, annotations);
+
+        for job in wf.jobs().filter_map(|(_, j)| j.value()) {
+            env(&job.env, annotations);
+
+            for step in job.steps().filter_map(|s| s.value()) {
+                step_deprecations(step, annotations);
+            }
+        }
+    }
+}
+
+// shared with composite actions, whose steps are the same as a job's
+pub fn step_deprecations(step: &Step, annotations: &mut impl Annotatable) {
+    env(&step.env, annotations);
+
+    let uses = match step.uses.as_ref() {
+        Some(uses) => uses,
+        None => return,
+    };
+    let release = match uses.value().and_then(|u| deprecations().action(u)) {
+        Some(release) => release,
+        None => return,
+    };
+
+    annotations.annotate(Annotation::warn(
+        &rules::DEPRECATED_ACTION_RUNTIME,
+        uses,
+        &format!(
+            "{} runs on {}, which GitHub has retired, use {}",
+            uses.value().map_or("", |u| u.as_str()),
+            release.runtime,
+            release.replacement
+        ),
+    ));
+}
+
+fn env(env: &Option<PossumNode<PossumMap<String, String>>>, annotations: &mut impl Annotatable) {
+    let vars = env.iter().filter_map(|e| e.value()).flat_map(|e| e.iter());
+
+    for (name, value) in vars {
+        let var = match name.value().and_then(|n| deprecations().env(n)) {
+            Some(var) => var,
+            None => continue,
+        };
+        // setting it to false is the same as leaving it out
+        if value.value().map_or(false, |v| v.eq_ignore_ascii_case("false")) {
+            continue;
+        }
+
+        annotations.annotate(Annotation::warn(
+            &rules::DEPRECATED_ENV_VAR,
+            name,
+            &format!("{} is deprecated, {}", var.name, var.replacement),
+        ));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::document::{Annotatable, Annotation};
+use crate::expression::schema::{Problem, Schema};
+use crate::lint::{rules, LintRule};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::contexts::schema;
+use crate::workflow::expressions::{job_sites, workflow_sites, Site};
+use crate::workflow::Workflow;
+
+pub struct ContextReferences;
+
+impl LintRule<Workflow> for ContextReferences {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        let top = schema(wf, None);
+        for site in workflow_sites(wf) {
+            check(&site, &top, annotations);
+        }
+
+        for job in wf.jobs().filter_map(|(_, job)| job.value()) {
+            let inside = schema(wf, Some(job));
+            for site in job_sites(job) {
+                check(&site, &inside, annotations);
+            }
+        }
+    }
+}
+
+fn check(site: &Site, schema: &Schema, annotations: &mut impl Annotatable) {
+    let exprs = match site.expressions() {
+        Ok(exprs) => exprs,
+        // conditions are already reported by Conditions
+        Err(_) if site.place.is_condition() => return,
+        Err(e) => {
+            annotations.annotate(Annotation::error(&rules::INVALID_EXPRESSION, &site.at, &e.to_string()));
+            return;
+        }
+    };
+
+    let schema = schema.clone().available(site.place.contexts());
+    for expr in exprs.iter() {
+        for problem in schema.check(expr) {
+            // MatrixReferences and InputReferences know more about these
+            let delegated = matches!(problem.context().as_str(), "matrix" | "inputs")
+                || problem.reference().to_lowercase().starts_with("github.event.inputs");
+            if delegated && !matches!(problem, Problem::Unavailable { .. }) {
+                continue;
+            }
+
+            let msg = problem.to_string();
+            // payloads are only partly modelled, so properties are less certain
+            annotations.annotate(match problem {
+                Problem::Unavailable { .. } => Annotation::error(&rules::UNAVAILABLE_CONTEXT, &site.at, &msg),
+                Problem::UnknownContext { .. } => Annotation::error(&rules::UNKNOWN_CONTEXT, &site.at, &msg),
+                _ => Annotation::warn(&rules::UNKNOWN_CONTEXT, &site.at, &msg),
+            });
+        }
+    }
+}
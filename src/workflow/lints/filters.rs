@@ -0,0 +1,201 @@
+use crate::document::{Annotatable, Annotation};
+use crate::lint::{rules, LintRule};
+use crate::scavenge::ast::{PossumNode, PossumSeq};
+use crate::workflow::on::{EventKind, Globbed};
+use crate::workflow::Workflow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterKind {
+    Branches,
+    Tags,
+    Paths,
+}
+
+impl FilterKind {
+    fn name(&self) -> &'static str {
+        match self {
+            FilterKind::Branches => "branches",
+            FilterKind::Tags => "tags",
+            FilterKind::Paths => "paths",
+        }
+    }
+
+    // the events that look at this filter at all
+    fn applies_to(&self, event: EventKind) -> bool {
+        use EventKind::*;
+        match self {
+            FilterKind::Branches => matches!(
+                event,
+                Push | PullRequest | PullRequestTarget | WorkflowRun | MergeGroup
+            ),
+            FilterKind::Tags => event == Push,
+            FilterKind::Paths => matches!(event, Push | PullRequest | PullRequestTarget),
+        }
+    }
+}
+
+pub struct EventFilters;
+
+impl LintRule<Workflow> for EventFilters {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let on = match root.value().and_then(|wf| wf.on.as_ref()?.value()) {
+            Some(on) => on,
+            None => return,
+        };
+
+        for (kind, event) in on.events() {
+            let (kind, event) = match (kind.value(), event.value()) {
+                (Some(kind), Some(event)) => (*kind, event),
+                _ => continue,
+            };
+
+            let filters = [
+                (FilterKind::Branches, &event.branches, &event.branches_ignore),
+                (FilterKind::Tags, &event.tags, &event.tags_ignore),
+                (FilterKind::Paths, &event.paths, &event.paths_ignore),
+            ];
+
+            for (filter, include, ignore) in filters {
+                if let (Some(_), Some(ignore)) = (include, ignore) {
+                    annotations.annotate(Annotation::error(
+                        &rules::CONFLICTING_FILTERS,
+                        ignore,
+                        &format!(
+                            "{0} and {0}-ignore can't both be used on {kind}, use ! patterns in {0} instead",
+                            filter.name()
+                        ),
+                    ))
+                }
+
+                for node in include.iter().chain(ignore.iter()) {
+                    if !filter.applies_to(kind) {
+                        annotations.annotate(Annotation::warn(
+                            &rules::IGNORED_FILTER,
+                            node,
+                            &format!("{kind} events ignore {} filters", filter.name()),
+                        ));
+                        continue;
+                    }
+
+                    if let Some(patterns) = node.value() {
+                        lint_patterns(filter, patterns, annotations);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn lint_patterns(filter: FilterKind, patterns: &PossumSeq<Globbed>, annotations: &mut impl Annotatable) {
+    let mut seen_positive = false;
+
+    for node in patterns.iter() {
+        let globbed = match node.value() {
+            Some(g) => g,
+            None => continue,
+        };
+
+        let pattern = match globbed.pattern() {
+            Ok(p) => p,
+            Err(e) => {
+                annotations.annotate(Annotation::error(
+                    &rules::INVALID_FILTER,
+                    node,
+                    &format!("{} is not a valid filter: {e}", globbed.raw()),
+                ));
+                continue;
+            }
+        };
+
+        if pattern.is_negated() && !seen_positive {
+            annotations.annotate(Annotation::warn(
+                &rules::NEGATION_FIRST,
+                node,
+                &format!("{} comes before any pattern it could exclude from", globbed.raw()),
+            ));
+        }
+        seen_positive |= !pattern.is_negated();
+
+        // branches and tags are matched by name and paths from the root of
+        // the repository, so these can never match anything
+        let prefix = pattern.literal_prefix();
+        let never = match filter {
+            FilterKind::Branches | FilterKind::Tags if prefix.starts_with("refs/") => {
+                Some("names are matched without refs/")
+            }
+            FilterKind::Paths if prefix.starts_with('/') || prefix.starts_with("./") => {
+                Some("paths are matched relative to the repository root")
+            }
+            _ => None,
+        };
+
+        if let Some(why) = never {
+            annotations.annotate(Annotation::warn(
+                &rules::UNMATCHABLE_FILTER,
+                node,
+                &format!("{} can never match, {why}", globbed.raw()),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EventFilters;
+    use crate::lint::LintRule;
+    use crate::workflow::lints::test_lint;
+
+    fn messages(on: &str) -> Vec<String> {
+        let raw = format!("on:\n{on}jobs: {{}}\n");
+        let annotations = test_lint(&raw, |wf, _, a| EventFilters.lint(wf, a));
+        annotations.entries().map(|a| a.message().to_owned()).collect()
+    }
+
+    #[test]
+    fn include_and_ignore_together_are_reported() {
+        assert_eq!(
+            messages("  push:\n    branches: [main]\n    branches-ignore: [dev]\n"),
+            ["branches and branches-ignore can't both be used on push, use ! patterns in branches instead"]
+        );
+        assert!(messages("  push:\n    branches: [main]\n    paths-ignore: [docs/**]\n").is_empty());
+    }
+
+    #[test]
+    fn filters_the_event_ignores_are_reported() {
+        assert_eq!(
+            messages("  pull_request:\n    tags: [v1]\n  workflow_run:\n    workflows: [ci]\n    paths: [src]\n"),
+            ["pull_request events ignore tags filters", "workflow_run events ignore paths filters"]
+        );
+        assert!(messages("  push:\n    tags: [v1]\n    paths: [src]\n").is_empty());
+    }
+
+    #[test]
+    fn invalid_patterns_are_reported() {
+        assert_eq!(
+            messages("  push:\n    branches: ['[main']\n"),
+            ["[main is not a valid filter: [ is never closed"]
+        );
+        assert!(messages("  push:\n    branches: ['[mb]ain']\n").is_empty());
+    }
+
+    #[test]
+    fn negations_before_anything_to_negate_are_reported() {
+        assert_eq!(
+            messages("  push:\n    branches: ['!dev', main]\n"),
+            ["!dev comes before any pattern it could exclude from"]
+        );
+        assert!(messages("  push:\n    branches: ['**', '!dev']\n").is_empty());
+    }
+
+    #[test]
+    fn patterns_that_can_never_match_are_reported() {
+        assert_eq!(
+            messages("  push:\n    branches: [refs/heads/main]\n    paths-ignore: [./docs/**]\n"),
+            [
+                "refs/heads/main can never match, names are matched without refs/",
+                "./docs/** can never match, paths are matched relative to the repository root",
+            ]
+        );
+        assert!(messages("  push:\n    branches: [main]\n    paths: [docs/**]\n").is_empty());
+    }
+}
@@ -0,0 +1,280 @@
+use crate::document::{Annotatable, Annotation};
+use crate::expression::parser::{BinaryOp, Expr, Literal};
+use crate::lint::{closest, rules, LintRule};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::contexts::declared_inputs;
+use crate::workflow::expressions::{job_sites, workflow_sites, Site};
+use crate::workflow::on::{EventKind, WorkflowInput, WorkflowInputType};
+use crate::workflow::Workflow;
+
+pub struct InputReferences;
+
+// an input as it was declared, None when no trigger that declares inputs is used
+type Declared<'a> = Option<Vec<(&'a str, &'a WorkflowInput)>>;
+
+impl LintRule<Workflow> for InputReferences {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        let triggered = |kind: EventKind| wf.on.as_ref().and_then(|on| on.value()?.event(kind)).is_some();
+
+        let both = [EventKind::WorkflowDispatch, EventKind::WorkflowCall];
+        let inputs: Declared = both
+            .iter()
+            .any(|k| triggered(*k))
+            .then(|| named(declared_inputs(wf, &both)));
+        let event_inputs: Declared = triggered(EventKind::WorkflowDispatch)
+            .then(|| named(declared_inputs(wf, &[EventKind::WorkflowDispatch])));
+
+        let sites = workflow_sites(wf)
+            .into_iter()
+            .chain(wf.jobs().filter_map(|(_, j)| j.value()).flat_map(job_sites));
+
+        for site in sites {
+            let exprs = match site.expressions() {
+                Ok(exprs) => exprs,
+                Err(_) => continue,
+            };
+
+            for expr in exprs.iter() {
+                self.references(&site, expr, &inputs, &event_inputs, annotations);
+                self.comparisons(&site, expr, &inputs, annotations);
+                if site.place.is_condition() {
+                    self.truthiness(&site, expr, &event_inputs, annotations);
+                }
+            }
+        }
+    }
+}
+
+impl InputReferences {
+    fn references(
+        &self,
+        site: &Site,
+        expr: &Expr,
+        inputs: &Declared,
+        event_inputs: &Declared,
+        annotations: &mut impl Annotatable,
+    ) {
+        let mut found = Vec::new();
+        expr.walk(&mut |e| {
+            if let Some(reference) = reference(e) {
+                if !found.contains(&reference) {
+                    found.push(reference);
+                }
+            }
+        });
+
+        for reference in found {
+            let (declared, event) = match reference {
+                Reference::Inputs(_) => (inputs, false),
+                Reference::EventInputs(_) => (event_inputs, true),
+            };
+            let name = reference.name();
+
+            let msg = match declared {
+                None if event => format!(
+                    "{reference} is always empty, github.event.inputs is only set by workflow_dispatch"
+                ),
+                None => format!(
+                    "{reference} is always empty, the workflow isn't triggered by workflow_dispatch or workflow_call"
+                ),
+                Some(declared) if !declares(declared, name) && event && declares(inputs.iter().flatten(), name) => {
+                    format!("{reference} isn't set, github.event.inputs only has workflow_dispatch inputs, use inputs.{name}")
+                }
+                Some(declared) if !declares(declared, name) => {
+                    let prefix = if event { "github.event.inputs" } else { "inputs" };
+                    match closest(name, declared.iter().map(|(n, _)| *n)) {
+                        Some(close) => format!("{reference} isn't declared, did you mean {prefix}.{close}?"),
+                        None => format!("{reference} isn't declared as an input of the workflow"),
+                    }
+                }
+                Some(_) => continue,
+            };
+
+            annotations.annotate(Annotation::error(&rules::UNDECLARED_INPUT, &site.at, &msg));
+        }
+    }
+
+    // github.event.inputs are always strings, inputs keep the declared type,
+    // and comparing a string to a boolean is never true
+    fn comparisons(&self, site: &Site, expr: &Expr, inputs: &Declared, annotations: &mut impl Annotatable) {
+        expr.walk(&mut |e| {
+            let (l, r) = match e {
+                Expr::Binary(l, BinaryOp::Eq | BinaryOp::Ne, r) => (l.as_ref(), r.as_ref()),
+                _ => return,
+            };
+
+            for (side, other) in [(l, r), (r, l)] {
+                let msg = match (reference(side), other) {
+                    (Some(r @ Reference::EventInputs(_)), Expr::Literal(Literal::Bool(b))) => format!(
+                        "{r} is a string, comparing it to {b} is never true, compare it to '{b}' or use inputs.{}",
+                        r.name()
+                    ),
+                    (Some(r @ Reference::Inputs(name)), Expr::Literal(Literal::Str(s)))
+                        if is_bool(inputs, name) && matches!(s.as_str(), "true" | "false") =>
+                    {
+                        format!("{r} is a boolean, comparing it to '{s}' is never true, compare it to {s}")
+                    }
+                    _ => continue,
+                };
+                annotations.annotate(Annotation::warn(&rules::STRING_INPUT_AS_BOOLEAN, &site.at, &msg));
+            }
+        });
+    }
+
+    // a boolean dispatch input is 'false' in github.event.inputs, which is truthy
+    fn truthiness(&self, site: &Site, expr: &Expr, event_inputs: &Declared, annotations: &mut impl Annotatable) {
+        let mut tested = Vec::new();
+        operands(expr, &mut tested);
+
+        for e in tested {
+            if let Some(r @ Reference::EventInputs(name)) = reference(e) {
+                if is_bool(event_inputs, name) {
+                    annotations.annotate(Annotation::warn(
+                        &rules::STRING_INPUT_AS_BOOLEAN,
+                        &site.at,
+                        &format!("{r} is the string 'false' when unchecked, which is true, use inputs.{name} instead"),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Reference<'a> {
+    Inputs(&'a str),
+    EventInputs(&'a str),
+}
+
+impl<'a> Reference<'a> {
+    fn name(&self) -> &'a str {
+        match self {
+            Reference::Inputs(n) | Reference::EventInputs(n) => n,
+        }
+    }
+}
+
+impl<'a> std::fmt::Display for Reference<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reference::Inputs(n) => write!(f, "inputs.{n}"),
+            Reference::EventInputs(n) => write!(f, "github.event.inputs.{n}"),
+        }
+    }
+}
+
+fn reference(e: &Expr) -> Option<Reference> {
+    let path = e.path()?;
+    let is = |i: usize, name: &str| path[i].eq_ignore_ascii_case(name);
+
+    match path.as_slice() {
+        [_, name] if is(0, "inputs") && *name != "*" => Some(Reference::Inputs(name)),
+        [_, _, _, name] if is(0, "github") && is(1, "event") && is(2, "inputs") && *name != "*" => {
+            Some(Reference::EventInputs(name))
+        }
+        _ => None,
+    }
+}
+
+fn named<'a>(inputs: Vec<(&'a PossumNode<String>, &'a WorkflowInput)>) -> Vec<(&'a str, &'a WorkflowInput)> {
+    inputs.into_iter().filter_map(|(n, i)| Some((n.value()?.as_str(), i))).collect()
+}
+
+fn declares<'a>(declared: impl IntoIterator<Item = &'a (&'a str, &'a WorkflowInput)>, name: &str) -> bool {
+    declared.into_iter().any(|(n, _)| n.eq_ignore_ascii_case(name))
+}
+
+fn is_bool(declared: &Declared, name: &str) -> bool {
+    declared.iter().flatten().any(|(n, input)| {
+        n.eq_ignore_ascii_case(name)
+            && input.input_type.as_ref().and_then(|t| t.value()) == Some(&WorkflowInputType::Bool)
+    })
+}
+
+// the parts of a condition whose truthiness decides it
+fn operands<'a>(expr: &'a Expr, out: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::Binary(l, BinaryOp::And | BinaryOp::Or, r) => {
+            operands(l, out);
+            operands(r, out);
+        }
+        Expr::Not(e) => operands(e, out),
+        e => out.push(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InputReferences;
+    use crate::lint::LintRule;
+    use crate::workflow::lints::test_lint;
+
+    const DISPATCH: &str = "  workflow_dispatch:\n    inputs:\n      dry-run:\n        type: boolean\n      environment:\n        type: string\n";
+    const CALL: &str = "  workflow_call:\n    inputs:\n      version:\n        type: string\n";
+
+    fn messages(on: &str, steps: &str) -> Vec<String> {
+        let raw = format!("on:\n{on}jobs:\n  deploy:\n    steps:\n{steps}");
+        let annotations = test_lint(&raw, |wf, _, a| InputReferences.lint(wf, a));
+        annotations.entries().map(|a| a.message().to_owned()).collect()
+    }
+
+    #[test]
+    fn undeclared_inputs_are_reported() {
+        let steps = "      - run: echo ${{ inputs.enviroment }} ${{ inputs.region }}\n      - run: echo ${{ github.event.inputs.version }}\n";
+        assert_eq!(
+            messages(&format!("{DISPATCH}{CALL}"), steps),
+            [
+                "inputs.enviroment isn't declared, did you mean inputs.environment?",
+                "inputs.region isn't declared as an input of the workflow",
+                "github.event.inputs.version isn't set, github.event.inputs only has workflow_dispatch inputs, use inputs.version",
+            ]
+        );
+
+        let steps = "      - run: echo ${{ inputs.environment }} ${{ inputs.VERSION }} ${{ github.event.inputs.dry-run }}\n";
+        assert!(messages(&format!("{DISPATCH}{CALL}"), steps).is_empty());
+    }
+
+    #[test]
+    fn inputs_without_a_trigger_that_sets_them_are_reported() {
+        let steps = "      - run: echo ${{ inputs.version }} ${{ github.event.inputs.version }}\n";
+        assert_eq!(
+            messages("  push:\n", steps),
+            [
+                "inputs.version is always empty, the workflow isn't triggered by workflow_dispatch or workflow_call",
+                "github.event.inputs.version is always empty, github.event.inputs is only set by workflow_dispatch",
+            ]
+        );
+    }
+
+    #[test]
+    fn comparing_inputs_to_the_wrong_kind_of_boolean_is_reported() {
+        let steps = "      - if: github.event.inputs.environment == true\n        run: deploy\n      - if: inputs.dry-run != 'false'\n        run: deploy\n";
+        assert_eq!(
+            messages(DISPATCH, steps),
+            [
+                "github.event.inputs.environment is a string, comparing it to true is never true, compare it to 'true' or use inputs.environment",
+                "inputs.dry-run is a boolean, comparing it to 'false' is never true, compare it to false",
+            ]
+        );
+
+        let steps = "      - if: github.event.inputs.environment == 'true' && inputs.dry-run == false\n        run: deploy\n";
+        assert!(messages(DISPATCH, steps).is_empty());
+    }
+
+    #[test]
+    fn boolean_event_inputs_tested_for_truth_are_reported() {
+        let steps = "      - if: ${{ !github.event.inputs.dry-run }}\n        run: deploy\n";
+        assert_eq!(
+            messages(DISPATCH, steps),
+            ["github.event.inputs.dry-run is the string 'false' when unchecked, which is true, use inputs.dry-run instead"]
+        );
+
+        let steps = "      - if: ${{ !inputs.dry-run && github.event.inputs.environment }}\n        run: deploy\n";
+        assert!(messages(DISPATCH, steps).is_empty());
+    }
+}
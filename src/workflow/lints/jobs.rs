@@ -1,5 +1,5 @@
 use crate::document::{Annotatable, Annotation, AsDocumentPointer};
-use crate::lint::LintRule;
+use crate::lint::{rules, LintRule};
 use crate::scavenge::ast::{PossumMap, PossumNode};
 use crate::workflow::job::Job;
 
@@ -10,7 +10,11 @@ impl EmptyJobs {
     where
         P: AsDocumentPointer,
     {
-        Annotation::error(p, &String::from("No jobs present in this workflow"))
+        Annotation::error(
+            &rules::EMPTY_JOBS,
+            p,
+            &String::from("No jobs present in this workflow"),
+        )
     }
 }
 
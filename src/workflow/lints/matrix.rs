@@ -0,0 +1,130 @@
+use crate::document::{Annotatable, Annotation};
+use crate::expression::parser::Expr;
+use crate::lint::{closest, rules, LintRule};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::contexts::matrix_keys;
+use crate::workflow::expressions::job_sites;
+use crate::workflow::Workflow;
+
+pub struct MatrixReferences;
+
+impl LintRule<Workflow> for MatrixReferences {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        for (name, job) in wf.jobs() {
+            let (name, job) = match (name.value(), job.value()) {
+                (Some(name), Some(job)) => (name, job),
+                _ => continue,
+            };
+
+            let matrix = job.strategy.as_ref().and_then(|s| s.value()?.matrix.as_ref());
+            let keys = match matrix {
+                // a matrix from an expression could have any keys
+                Some(m) => match m.value().and_then(matrix_keys) {
+                    Some(keys) => Some(keys),
+                    None => continue,
+                },
+                None => None,
+            };
+
+            // using matrix where it isn't available is reported with the other contexts
+            let sites = job_sites(job)
+                .into_iter()
+                .filter(|s| s.place.contexts().contains(&"matrix"));
+
+            for site in sites {
+                let exprs = match site.expressions() {
+                    Ok(exprs) => exprs,
+                    Err(_) => continue,
+                };
+
+                for key in references(&exprs) {
+                    let annotation = match (&keys, matrix) {
+                        (None, _) => Annotation::error(
+                            &rules::UNDEFINED_MATRIX_KEY,
+                            &site.at,
+                            &format!("matrix.{key} is always empty, job {name} has no strategy.matrix"),
+                        ),
+                        (Some(keys), Some(m)) if !keys.iter().any(|k| k.eq_ignore_ascii_case(key)) => {
+                            let msg = match closest(key, keys.iter().copied()) {
+                                Some(close) => format!("matrix.{key} isn't defined, did you mean matrix.{close}?"),
+                                None if keys.is_empty() => format!("matrix.{key} isn't defined, the matrix has no keys"),
+                                None => format!("matrix.{key} isn't defined, the matrix has {}", keys.join(", ")),
+                            };
+                            Annotation::error(&rules::UNDEFINED_MATRIX_KEY, &site.at, &msg)
+                                .related(m, &"matrix defined here")
+                        }
+                        _ => continue,
+                    };
+                    annotations.annotate(annotation);
+                }
+            }
+        }
+    }
+}
+
+// the X of each matrix.X or matrix['X']
+fn references(exprs: &[Expr]) -> Vec<&str> {
+    let mut keys = Vec::new();
+    for expr in exprs {
+        expr.walk(&mut |e| match e.path().as_deref() {
+            Some([context, key]) if context.eq_ignore_ascii_case("matrix") && *key != "*" => {
+                if !keys.contains(key) {
+                    keys.push(*key);
+                }
+            }
+            _ => {}
+        });
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MatrixReferences;
+    use crate::lint::LintRule;
+    use crate::workflow::lints::test_lint;
+
+    fn messages(job: &str) -> Vec<String> {
+        let raw = format!("on: push\njobs:\n  test:\n{job}");
+        let annotations = test_lint(&raw, |wf, _, a| MatrixReferences.lint(wf, a));
+        annotations.entries().map(|a| a.message().to_owned()).collect()
+    }
+
+    const MATRIX: &str = "    strategy:\n      matrix:\n        os: [ubuntu-latest, windows-latest]\n        node: [20, 22]\n        include:\n          - os: ubuntu-latest\n            experimental: true\n";
+
+    #[test]
+    fn keys_without_a_matrix_are_reported() {
+        assert_eq!(
+            messages("    runs-on: ${{ matrix.os }}\n"),
+            ["matrix.os is always empty, job test has no strategy.matrix"]
+        );
+        assert!(messages("    runs-on: ubuntu-latest\n").is_empty());
+    }
+
+    #[test]
+    fn undefined_keys_are_reported() {
+        let job = format!("{MATRIX}    runs-on: ${{{{ matrix.so }}}}\n    steps:\n      - run: echo ${{{{ matrix['version'] }}}}\n");
+        assert_eq!(
+            messages(&job),
+            [
+                "matrix.so isn't defined, did you mean matrix.os?",
+                "matrix.version isn't defined, the matrix has experimental, node, os",
+            ]
+        );
+    }
+
+    #[test]
+    fn defined_keys_are_not() {
+        let job = format!("{MATRIX}    runs-on: ${{{{ matrix.OS }}}}\n    continue-on-error: ${{{{ matrix.experimental == true }}}}\n    steps:\n      - run: echo ${{{{ matrix.node }}}} ${{{{ toJSON(matrix) }}}}\n");
+        assert!(messages(&job).is_empty());
+
+        // a matrix from an expression could have any keys
+        let job = "    strategy:\n      matrix: ${{ fromJSON(needs.plan.outputs.matrix) }}\n    runs-on: ${{ matrix.anything }}\n";
+        assert!(messages(job).is_empty());
+    }
+}
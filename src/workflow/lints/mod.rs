@@ -1,14 +1,157 @@
-use crate::{document::{Annotation, AsDocumentPointer}, lint::LintViolation};
+use crate::{document::{Annotation, Annotatable, AsDocumentPointer}, lint::{rules, LintRule, LintViolation}};
+use crate::config::Options;
+use crate::document::Document;
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::Workflow;
+pub mod concurrency;
+pub mod conditions;
+pub mod deprecations;
+pub mod expressions;
+pub mod filters;
+pub mod inputs;
 pub mod jobs;
+pub mod matrix;
+pub mod oidc;
+pub mod permissions;
+pub mod runners;
+pub mod scripts;
+pub mod secrets;
+pub mod timeouts;
 pub mod workflows;
 
-pub struct MissingProperty<'a>(&'a str);
+pub fn lint_workflow(
+    workflow: &PossumNode<Workflow>,
+    document: &Document,
+    options: &Options,
+    annotations: &mut impl Annotatable,
+) {
+    workflows::MissingWorkflowProperties.lint(workflow, annotations);
+    workflows::UnquotedOnKey(document).lint(workflow, annotations);
+
+    permissions::MissingPermissions(document).lint(workflow, annotations);
+    permissions::UnusedWritePermissions.lint(workflow, annotations);
+    oidc::IdTokenAudit.lint(workflow, annotations);
+    secrets::SecretsExposure(options).lint(workflow, annotations);
+    secrets::UndeclaredSecrets.lint(workflow, annotations);
+    runners::RunnerLabels(options).lint(workflow, annotations);
+    timeouts::Timeouts(options).lint(workflow, annotations);
+    concurrency::ConcurrencyGroups.lint(workflow, annotations);
+    filters::EventFilters.lint(workflow, annotations);
+    conditions::Conditions.lint(workflow, annotations);
+    expressions::ContextReferences.lint(workflow, annotations);
+    matrix::MatrixReferences.lint(workflow, annotations);
+    inputs::InputReferences.lint(workflow, annotations);
+    scripts::Scripts(document).lint(workflow, annotations);
+    deprecations::Deprecations.lint(workflow, annotations);
+
+    if let Some(wf) = workflow.value() {
+        let job_permissions = wf.jobs().filter_map(|(_, job)| job.value()?.permissions.as_ref());
+        for p in wf.permissions.iter().chain(job_permissions) {
+            permissions::PermissionGrants.lint(p, annotations);
+        }
+    }
+
+    if let Some(jobs) = workflow.value().and_then(|wf| wf.jobs.as_ref()) {
+        jobs::EmptyJobs.lint(jobs, annotations);
+    }
+}
+
+pub struct MissingProperty<'a>(pub(crate) &'a str);
 
 impl<'a> LintViolation for MissingProperty<'a> {
 
     fn at(&self, loc: &impl AsDocumentPointer) -> Annotation
     {
-        Annotation::error(loc, &format!("missing required property: {}", self.0))
+        Annotation::error(
+            &rules::MISSING_PROPERTY,
+            loc,
+            &format!("missing required property: {}", self.0),
+        )
     }
 }
 
+// the annotations some lints give a workflow, read as possum would read it
+#[cfg(test)]
+pub(crate) fn test_lint(
+    raw: &str,
+    lint: impl FnOnce(&PossumNode<Workflow>, &Document, &mut crate::document::Annotations),
+) -> crate::document::Annotations {
+    use crate::document::Annotations;
+    use crate::scavenge::yaml::node_paths;
+    use crate::scavenge::Parser;
+    use crate::workflow::WorkflowParser;
+
+    let (root, source) = crate::scavenge::parser::test_document(raw);
+    let document = Document::new(raw).with_paths(node_paths(source, &root));
+    let workflow = WorkflowParser::new(&mut Annotations::new())
+        .parse_node(&root, source)
+        .at(&source.span(&root));
+
+    let mut annotations = Annotations::new();
+    lint(&workflow, &document, &mut annotations);
+    annotations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_lint;
+    use crate::document::Document;
+    use crate::scavenge::parser::test_document;
+    use yaml_peg::repr::Repr;
+    use yaml_peg::{Node as YamlNode, Yaml};
+
+    // the data in a document without the way it was written, to compare
+    // documents that should be the same workflow
+    fn data(raw: &str) -> String {
+        fn walk<R: Repr>(n: &YamlNode<R>) -> String {
+            match n.yaml() {
+                Yaml::Map(m) => {
+                    let entries: Vec<_> = m.iter().map(|(k, v)| format!("{}: {}", walk(k), walk(v))).collect();
+                    format!("{{{}}}", entries.join(", "))
+                }
+                Yaml::Seq(seq) => format!("[{}]", seq.iter().map(walk).collect::<Vec<_>>().join(", ")),
+                Yaml::Str(s) => format!("{s:?}"),
+                Yaml::Int(s) | Yaml::Float(s) => s.to_string(),
+                Yaml::Bool(b) => b.to_string(),
+                Yaml::Null => "null".to_owned(),
+                Yaml::Alias(a) => format!("*{a}"),
+            }
+        }
+        walk(&test_document(raw).0)
+    }
+
+    // the document after every fix all the workflow lints offer
+    fn fixed(raw: &str, unsafe_fixes: bool) -> String {
+        let annotations = test_lint(raw, |wf, doc, a| {
+            super::lint_workflow(wf, doc, &Default::default(), a)
+        });
+        let fixes = annotations.entries().map(|a| a.fix(unsafe_fixes)).filter(|f| !f.is_empty());
+        String::from_utf8(Document::new(raw).apply(fixes).0).unwrap()
+    }
+
+    #[test]
+    fn safe_fixes_leave_the_workflow_alone() {
+        let raw = "on: push\npermissions: {}\njobs:\n  build:\n    runs-on: ubuntu-latest\n    timeout-minutes: 5\n    steps:\n      - run: echo \"::set-output name=sha::abc\"\n      - run: echo ${{ github.head_ref }}\n";
+        let after = fixed(raw, false);
+        assert!(after.starts_with("\"on\": push\n"));
+        assert!(after.contains("- run: echo ${{ github.head_ref }}\n"));
+
+        // set-output is the one fix that's meant to change the data
+        let expected = raw.replace("echo \"::set-output name=sha::abc\"", "echo \"sha=abc\" >> \"$GITHUB_OUTPUT\"");
+        assert_ne!(data(&after), data(raw));
+        assert_eq!(data(&after), data(&expected));
+    }
+
+    #[test]
+    fn unsafe_fixes_are_only_applied_when_asked_for() {
+        let raw = "\"on\": push\npermissions: {}\njobs:\n  build:\n    runs-on: ubuntu-latest\n    timeout-minutes: 5\n    steps:\n      - run: echo ${{ github.head_ref }}\n";
+        assert_eq!(fixed(raw, false), raw);
+
+        let expected = raw.replace(
+            "      - run: echo ${{ github.head_ref }}\n",
+            "      - env:\n          HEAD_REF: ${{ github.head_ref }}\n        run: echo \"$HEAD_REF\"\n",
+        );
+        assert_eq!(fixed(raw, true), expected);
+        assert_eq!(data(&fixed(raw, true)), data(&expected));
+    }
+}
@@ -0,0 +1,158 @@
+use super::permissions::requests_oidc_token;
+use crate::document::{Annotatable, Annotation, DocumentSpan};
+use crate::lint::{rules, LintRule};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::job::Job;
+use crate::workflow::on::EventKind;
+use crate::workflow::permissions::EffectivePermissions;
+use crate::workflow::{Grant, Permission, Scope, Workflow};
+
+// events where untrusted code can end up running with the token
+const UNTRUSTED_EVENTS: [EventKind; 2] = [EventKind::PullRequestTarget, EventKind::WorkflowRun];
+
+// id-token: write lets a job mint OIDC tokens that cloud providers trade for
+// credentials, so it should only go to jobs that need it and control what runs.
+pub struct IdTokenAudit;
+
+impl LintRule<Workflow> for IdTokenAudit {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        for (name, job) in wf.jobs() {
+            let (name, job) = match (name.value(), job.value()) {
+                (Some(name), Some(job)) => (name, job),
+                _ => continue,
+            };
+
+            if EffectivePermissions::of(wf, job).grant(Scope::IdToken) != Some(Grant::Write) {
+                continue;
+            }
+
+            let at = match granted_at(wf, job) {
+                Some(at) => at,
+                None => continue,
+            };
+
+            // a reusable workflow may well use it, we can't see inside. An
+            // unused grant is still exposed, so it's checked below too.
+            if job.uses.is_none() && !job.steps().filter_map(|s| s.value()).any(requests_oidc_token) {
+                annotations.annotate(Annotation::warn(
+                    &rules::UNUSED_ID_TOKEN,
+                    &at,
+                    &format!("{name} is granted id-token: write but never requests an OIDC token"),
+                ));
+            }
+
+            let triggers: Vec<_> = wf
+                .on
+                .iter()
+                .filter_map(|on| on.value())
+                .flat_map(|on| UNTRUSTED_EVENTS.iter().filter_map(|e| on.event(*e)))
+                .collect();
+
+            let unpinned: Vec<_> = job
+                .steps()
+                .filter_map(|s| s.value()?.unpinned_action())
+                .collect();
+
+            if triggers.is_empty() && unpinned.is_empty() {
+                continue;
+            }
+
+            let mut annotation = if triggers.is_empty() {
+                Annotation::warn(
+                    &rules::EXPOSED_ID_TOKEN,
+                    &at,
+                    &format!("{name} can mint OIDC tokens and runs actions that aren't pinned"),
+                )
+            } else {
+                Annotation::error(
+                    &rules::EXPOSED_ID_TOKEN,
+                    &at,
+                    &format!("{name} can mint OIDC tokens in a workflow triggered by untrusted events"),
+                )
+            };
+
+            for t in triggers {
+                let event = t.value().map(ToString::to_string).unwrap_or_default();
+                annotation = annotation.related(t, &format!("triggered by {event}"));
+            }
+
+            for u in unpinned {
+                let action = u.value().cloned().unwrap_or_default();
+                annotation = annotation.related(u, &format!("{action} is not pinned to a commit sha"));
+            }
+
+            annotations.annotate(annotation);
+        }
+    }
+}
+
+// the id-token key if it was listed, otherwise the whole grant
+fn granted_at(wf: &Workflow, job: &Job) -> Option<DocumentSpan> {
+    let permissions = job.permissions.as_ref().or(wf.permissions.as_ref())?;
+
+    let key = match permissions.value() {
+        Some(Permission::IndividualGrants(grants)) => grants
+            .iter()
+            .map(|(k, _)| k)
+            .find(|k| k.value().map(String::as_str) == Some("id-token")),
+        _ => None,
+    };
+
+    Some(match key {
+        Some(k) => k.span(),
+        None => permissions.span(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IdTokenAudit;
+    use crate::lint::LintRule;
+    use crate::workflow::lints::test_lint;
+
+    const SHA: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    fn messages(on: &str, steps: &str) -> Vec<String> {
+        let raw = format!("on: {on}\npermissions:\n  id-token: write\njobs:\n  deploy:\n    steps:\n{steps}");
+        let annotations = test_lint(&raw, |wf, _, a| IdTokenAudit.lint(wf, a));
+        annotations.entries().map(|a| a.message().to_owned()).collect()
+    }
+
+    #[test]
+    fn unused_id_tokens_are_reported() {
+        assert_eq!(
+            messages("push", "      - run: make deploy\n"),
+            ["deploy is granted id-token: write but never requests an OIDC token"]
+        );
+
+        let steps = format!("      - uses: aws-actions/configure-aws-credentials@{SHA}\n");
+        assert!(messages("push", &steps).is_empty());
+
+        // what a reusable workflow does with it can't be seen
+        let raw = "on: push\njobs:\n  deploy:\n    permissions:\n      id-token: write\n    uses: ./.github/workflows/deploy.yml\n";
+        assert_eq!(test_lint(raw, |wf, _, a| IdTokenAudit.lint(wf, a)).entries().count(), 0);
+    }
+
+    #[test]
+    fn id_tokens_near_untrusted_code_are_reported() {
+        assert_eq!(
+            messages("push", "      - uses: aws-actions/configure-aws-credentials@v4\n"),
+            ["deploy can mint OIDC tokens and runs actions that aren't pinned"]
+        );
+
+        let steps = format!("      - uses: aws-actions/configure-aws-credentials@{SHA}\n");
+        assert_eq!(
+            messages("pull_request_target", &steps),
+            ["deploy can mint OIDC tokens in a workflow triggered by untrusted events"]
+        );
+
+        // read only can't mint anything
+        let raw = "on: pull_request_target\npermissions:\n  id-token: read\njobs:\n  deploy:\n    steps:\n      - uses: aws-actions/configure-aws-credentials@v4\n";
+        assert_eq!(test_lint(raw, |wf, _, a| IdTokenAudit.lint(wf, a)).entries().count(), 0);
+    }
+}
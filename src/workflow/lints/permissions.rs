@@ -0,0 +1,371 @@
+use crate::document::{Annotatable, Annotation, Document, Edit};
+use crate::lint::{closest, rules, LintRule};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::job::{Job, Step};
+use crate::workflow::{Grant, Permission, Scope, Workflow};
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+
+// Without top level permissions every job that doesn't set its own gets the
+// repository's default token, which is often write to everything. The fix
+// grants read on contents, which checking out needs. Jobs that used to write
+// with the default token would lose that, so it's only applied when asked.
+pub struct MissingPermissions<'a>(pub &'a Document);
+
+impl<'a> LintRule<Workflow> for MissingPermissions<'a> {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        if wf.permissions.is_some() {
+            return;
+        }
+
+        let defaulted: Vec<_> = wf
+            .jobs()
+            .filter(|(_, job)| job.value().map_or(true, |j| j.permissions.is_none()))
+            .filter_map(|(name, _)| name.value())
+            .map(String::as_str)
+            .collect();
+
+        if defaulted.is_empty() {
+            return;
+        }
+
+        let mut annotation = Annotation::warn(
+            &rules::MISSING_PERMISSIONS,
+            root,
+            &format!(
+                "workflow has no permissions, so {} will use the default token scope",
+                defaulted.join(", ")
+            ),
+        );
+
+        // above jobs:, as long as it's at the start of its line
+        let jobs = self.0.key_of("$.jobs").map(|k| k.start());
+        if let Some(at) = jobs.filter(|at| at.0 == 0 || self.0.raw().get(at.0 - 1) == Some(&b'\n')) {
+            annotation = annotation
+                .edit(Edit::insert(&at, "permissions:\n  contents: read\n"))
+                .unsafe_fix();
+        }
+
+        annotations.annotate(annotation)
+    }
+}
+
+// write-all and scope names, wherever permissions are granted
+pub struct PermissionGrants;
+
+impl LintRule<Permission> for PermissionGrants {
+    fn lint(&self, root: &PossumNode<Permission>, annotations: &mut impl Annotatable) {
+        match root.value() {
+            Some(Permission::GlobalGrant(Grant::Write)) => annotations.annotate(Annotation::warn(
+                &rules::WRITE_ALL_PERMISSIONS,
+                root,
+                &String::from("write-all grants write access to every scope"),
+            )),
+            Some(Permission::IndividualGrants(grants)) => {
+                for (scope, _) in grants.iter() {
+                    match scope.value() {
+                        Some(s) if Scope::from_str(s).is_err() => {
+                            let scopes: Vec<_> = Scope::iter().map(|s| s.to_string()).collect();
+                            let msg = match closest(s, scopes.iter().map(String::as_str)) {
+                                Some(close) => format!("{s} is not a permission scope, did you mean {close}?"),
+                                None => format!("{s} is not a permission scope, scopes are {}", scopes.join(", ")),
+                            };
+                            annotations.annotate(Annotation::error(&rules::UNKNOWN_PERMISSION_SCOPE, scope, &msg))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Write grants on scopes that nothing in the job looks like it needs. We can
+// only guess from the actions and commands that are known to use each scope.
+pub struct UnusedWritePermissions;
+
+impl LintRule<Workflow> for UnusedWritePermissions {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        let jobs: Vec<_> = wf.jobs().filter_map(|(_, job)| job.value()).collect();
+
+        if let Some(permissions) = wf.permissions.as_ref() {
+            // only jobs without their own permissions inherit the workflow's
+            let inheriting: Vec<_> = jobs.iter().filter(|j| j.permissions.is_none()).collect();
+            unused_writes(permissions, annotations, |scope| {
+                inheriting.iter().any(|job| job_uses(job, scope))
+            });
+        }
+
+        for job in jobs {
+            if let Some(permissions) = job.permissions.as_ref() {
+                unused_writes(permissions, annotations, |scope| job_uses(job, scope));
+            }
+        }
+    }
+}
+
+fn unused_writes(
+    permissions: &PossumNode<Permission>,
+    annotations: &mut impl Annotatable,
+    used: impl Fn(Scope) -> bool,
+) {
+    let grants = match permissions.value() {
+        Some(Permission::IndividualGrants(grants)) => grants,
+        _ => return,
+    };
+
+    for (key, grant) in grants.iter() {
+        let scope = match key.value().map(|k| Scope::from_str(k)) {
+            Some(Ok(scope)) => scope,
+            _ => continue,
+        };
+
+        // id-token has a lint of its own
+        if scope == Scope::IdToken {
+            continue;
+        }
+
+        if grant.value() == Some(&Grant::Write) && !used(scope) {
+            annotations.annotate(Annotation::warn(
+                &rules::UNUSED_WRITE_PERMISSION,
+                key,
+                &format!("{scope}: write is granted but nothing appears to need it"),
+            ))
+        }
+    }
+}
+
+fn job_uses(job: &Job, scope: Scope) -> bool {
+    // reusable workflows get the caller's token and could do anything with it
+    job.uses.is_some() || job.steps().filter_map(|s| s.value()).any(|s| step_uses(s, scope))
+}
+
+// Having the token isn't enough to mint an OIDC token, only these can
+pub(crate) fn requests_oidc_token(step: &Step) -> bool {
+    let (actions, commands) = needs(Scope::IdToken);
+    uses_any(step, actions, commands)
+}
+
+fn step_uses(step: &Step, scope: Scope) -> bool {
+    let uses = step
+        .uses
+        .as_ref()
+        .and_then(|u| u.value())
+        .map(|u| u.split('@').next().unwrap_or_default().to_lowercase());
+    let run = step.run.as_ref().and_then(|r| r.value());
+
+    let passes_token = step
+        .with
+        .iter()
+        .chain(step.env.iter())
+        .filter_map(|m| m.value())
+        .flat_map(|m| m.iter())
+        .filter_map(|(_, v)| v.value())
+        .any(|v| v.contains("github.token") || v.contains("secrets.GITHUB_TOKEN"));
+
+    let generic = uses.as_deref().map_or(false, |u| u == "actions/github-script")
+        || run.map_or(false, |r| {
+            r.contains("gh api") || r.contains("api.github.com") || r.contains("GITHUB_TOKEN")
+        });
+
+    if passes_token || generic {
+        return true;
+    }
+
+    let (actions, commands) = needs(scope);
+    uses_any(step, actions, commands)
+}
+
+fn uses_any(step: &Step, actions: &[&str], commands: &[&str]) -> bool {
+    let uses = step
+        .uses
+        .as_ref()
+        .and_then(|u| u.value())
+        .map(|u| u.split('@').next().unwrap_or_default().to_lowercase());
+    let run = step.run.as_ref().and_then(|r| r.value());
+
+    uses.map_or(false, |u| {
+        actions.iter().any(|a| u == *a || u.starts_with(&format!("{a}/")))
+    }) || run.map_or(false, |r| commands.iter().any(|c| r.contains(c)))
+}
+
+// actions and shell commands known to need write on each scope
+fn needs(scope: Scope) -> (&'static [&'static str], &'static [&'static str]) {
+    match scope {
+        Scope::Actions => (
+            &["geekyeggo/delete-artifact", "c-hive/gha-remove-artifacts"],
+            &["gh run", "gh workflow", "gh cache"],
+        ),
+        Scope::Attestations => (
+            &["actions/attest", "actions/attest-build-provenance", "actions/attest-sbom"],
+            &["gh attestation"],
+        ),
+        Scope::Checks => (
+            &[
+                "dorny/test-reporter",
+                "mikepenz/action-junit-report",
+                "enricomi/publish-unit-test-result-action",
+                "louisbrunner/checks-action",
+            ],
+            &[],
+        ),
+        Scope::Contents => (
+            &[
+                "actions/create-release",
+                "actions/upload-release-asset",
+                "softprops/action-gh-release",
+                "ncipollo/release-action",
+                "stefanzweifel/git-auto-commit-action",
+                "endbug/add-and-commit",
+                "ad-m/github-push-action",
+                "peter-evans/create-pull-request",
+                "googleapis/release-please-action",
+                "google-github-actions/release-please-action",
+                "changesets/action",
+                "jamesives/github-pages-deploy-action",
+                "peaceiris/actions-gh-pages",
+            ],
+            &["git push", "gh release", "semantic-release"],
+        ),
+        Scope::Deployments => (
+            &["chrnorm/deployment-action", "chrnorm/deployment-status", "bobheadxi/deployments"],
+            &[],
+        ),
+        Scope::Discussions => (&["abirismyname/create-discussion"], &[]),
+        Scope::IdToken => (
+            &[
+                "aws-actions/configure-aws-credentials",
+                "google-github-actions/auth",
+                "azure/login",
+                "hashicorp/vault-action",
+                "pypa/gh-action-pypi-publish",
+                "sigstore/cosign-installer",
+                "actions/attest",
+                "actions/attest-build-provenance",
+                "actions/attest-sbom",
+                "actions/deploy-pages",
+            ],
+            &["ACTIONS_ID_TOKEN_REQUEST", "--provenance", "cosign"],
+        ),
+        Scope::Issues => (
+            &[
+                "actions/stale",
+                "peter-evans/create-or-update-comment",
+                "actions-ecosystem/action-add-labels",
+                "jasonetco/create-an-issue",
+                "dessant/lock-threads",
+                "googleapis/release-please-action",
+                "google-github-actions/release-please-action",
+            ],
+            &["gh issue", "gh label"],
+        ),
+        Scope::Packages => (
+            &["docker/login-action", "docker/build-push-action", "actions/delete-package-versions"],
+            &["docker push", "ghcr.io", "npm publish", "mvn deploy", "gradle publish", "nuget push"],
+        ),
+        Scope::Pages => (&["actions/deploy-pages"], &[]),
+        Scope::PullRequests => (
+            &[
+                "actions/labeler",
+                "peter-evans/create-pull-request",
+                "peter-evans/create-or-update-comment",
+                "peter-evans/enable-pull-request-automerge",
+                "marocchino/sticky-pull-request-comment",
+                "thollander/actions-comment-pull-request",
+                "dependabot/fetch-metadata",
+                "hmarr/auto-approve-action",
+                "pascalgn/automerge-action",
+                "googleapis/release-please-action",
+                "google-github-actions/release-please-action",
+                "changesets/action",
+            ],
+            &["gh pr"],
+        ),
+        // projects (classic), the newer projects need a token GITHUB_TOKEN can't be
+        Scope::RepositoryProjects => (&[], &[]),
+        Scope::SecurityEvents => (
+            &["github/codeql-action", "ossf/scorecard-action"],
+            &["gh code-scanning"],
+        ),
+        Scope::Statuses => (
+            &["myrotvorets/set-commit-status-action", "ouzi-dev/commit-status-updater"],
+            &[],
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MissingPermissions, PermissionGrants};
+    use crate::document::Document;
+    use crate::lint::LintRule;
+    use crate::workflow::lints::test_lint;
+    use crate::workflow::parser::test_workflow;
+    use crate::workflow::permissions::EffectivePermissions;
+    use crate::workflow::{Grant, Scope};
+
+    const DEFAULTED: &str = "on: push\njobs:\n  build:\n    runs-on: ubuntu-latest\n    steps:\n      - uses: actions/checkout@v4\n";
+
+    #[test]
+    fn jobs_without_any_permissions_are_reported() {
+        let annotations = test_lint(DEFAULTED, |wf, doc, a| MissingPermissions(doc).lint(wf, a));
+        let messages: Vec<_> = annotations.entries().map(|a| a.message()).collect();
+        assert_eq!(messages, ["workflow has no permissions, so build will use the default token scope"]);
+
+        let own = DEFAULTED.replace("    runs-on:", "    permissions: read-all\n    runs-on:");
+        let annotations = test_lint(&own, |wf, doc, a| MissingPermissions(doc).lint(wf, a));
+        assert_eq!(annotations.entries().count(), 0);
+    }
+
+    #[test]
+    fn the_fix_keeps_checkout_working() {
+        let annotations = test_lint(DEFAULTED, |wf, doc, a| MissingPermissions(doc).lint(wf, a));
+        let fix = annotations.entries().next().unwrap();
+        assert!(fix.fix(false).is_empty());
+
+        let (fixed, applied) = Document::new(DEFAULTED).apply([fix.fix(true)]);
+        let fixed = String::from_utf8(fixed).unwrap();
+        assert_eq!(applied, 1);
+        assert_eq!(fixed, format!("on: push\npermissions:\n  contents: read\n{}", &DEFAULTED[9..]));
+
+        let wf = test_workflow(&fixed);
+        let (_, job) = wf.jobs().next().unwrap();
+        let granted: Vec<_> = EffectivePermissions::of(&wf, job.value().unwrap()).granted().collect();
+        assert_eq!(granted, [(Scope::Contents, Grant::Read)]);
+    }
+
+    fn grants(raw: &str) -> Vec<String> {
+        let annotations = test_lint(raw, |wf, _, a| {
+            let permissions = wf.value().and_then(|wf| wf.permissions.as_ref()).unwrap();
+            PermissionGrants.lint(permissions, a)
+        });
+        annotations.entries().map(|a| a.message().to_owned()).collect()
+    }
+
+    #[test]
+    fn unknown_scopes_are_told_what_the_scopes_are() {
+        assert_eq!(grants("permissions:\n  contents: read\n  repository-projects: write\n"), Vec::<String>::new());
+        assert_eq!(grants("permissions:\n  content: read\n"), ["content is not a permission scope, did you mean contents?"]);
+
+        let listed = grants("permissions:\n  wiki: write\n");
+        assert!(listed[0].starts_with("wiki is not a permission scope, scopes are actions, attestations,"));
+        assert!(listed[0].contains("repository-projects"));
+    }
+
+    #[test]
+    fn write_all_is_reported() {
+        assert_eq!(grants("permissions: write-all\n"), ["write-all grants write access to every scope"]);
+        assert!(grants("permissions: read-all\n").is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+use crate::config::Options;
+use crate::document::{Annotatable, Annotation};
+use crate::lint::deprecations::deprecations;
+use crate::lint::{closest, rules, LintRule};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::job::RunsOn;
+use crate::workflow::on::EventKind;
+use crate::workflow::Workflow;
+
+// GitHub hosted runner images
+const HOSTED: &[&str] = &[
+    "ubuntu-latest",
+    "ubuntu-24.04",
+    "ubuntu-22.04",
+    "ubuntu-24.04-arm",
+    "ubuntu-22.04-arm",
+    "ubuntu-slim",
+    "windows-latest",
+    "windows-2025",
+    "windows-2022",
+    "windows-11-arm",
+    "macos-latest",
+    "macos-26",
+    "macos-15",
+    "macos-14",
+    "macos-latest-large",
+    "macos-15-large",
+    "macos-14-large",
+    "macos-latest-xlarge",
+    "macos-15-xlarge",
+    "macos-14-xlarge",
+];
+
+// labels every self hosted runner is given
+const SELF_HOSTED: &[&str] = &["self-hosted", "linux", "windows", "macos", "x64", "arm", "arm64"];
+
+// events anyone can cause on a public repository
+const PUBLIC_EVENTS: &[EventKind] = &[
+    EventKind::PullRequest,
+    EventKind::PullRequestTarget,
+    EventKind::PullRequestReview,
+    EventKind::PullRequestReviewComment,
+    EventKind::IssueComment,
+    EventKind::Issues,
+    EventKind::Discussion,
+    EventKind::DiscussionComment,
+    EventKind::Fork,
+    EventKind::Watch,
+];
+
+// A job whose runs-on matches no runner sits queued until it times out, so
+// labels are checked against GitHub's images and the configured runner labels.
+pub struct RunnerLabels<'a>(pub &'a Options);
+
+impl<'a> LintRule<Workflow> for RunnerLabels<'a> {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        let public_triggers: Vec<_> = wf
+            .on
+            .iter()
+            .filter_map(|on| on.value())
+            .flat_map(|on| PUBLIC_EVENTS.iter().filter_map(|e| on.event(*e)))
+            .collect();
+
+        for (_, job) in wf.jobs() {
+            let runs_on = match job.value().and_then(|j| j.runs_on.as_ref()?.value()) {
+                Some(runs_on) => runs_on,
+                None => continue,
+            };
+
+            let labels: Vec<_> = runs_on
+                .labels()
+                .filter_map(|l| l.value().map(|v| (l, v.to_lowercase())))
+                .filter(|(_, v)| !v.contains("${{"))
+                .collect();
+
+            let self_hosted = labels.iter().any(|(_, l)| l == "self-hosted");
+
+            // runner groups are usually larger runners with names of their own
+            let check_unknown = match runs_on {
+                RunsOn::Labels(_) => !self_hosted || !self.0.runner_labels().is_empty(),
+                _ => false,
+            };
+
+            for (node, label) in labels.iter() {
+                if let Some(runner) = deprecations().runner(label) {
+                    annotations.annotate(Annotation::warn(
+                        &rules::DEPRECATED_RUNNER,
+                        *node,
+                        &format!("{label} is deprecated, use {}", runner.replacement),
+                    ));
+                } else if check_unknown && !self.known(label, self_hosted) {
+                    let msg = match self.closest(label) {
+                        Some(close) => format!("no runner has the label {label}, did you mean {close}?"),
+                        None => format!("no runner has the label {label}"),
+                    };
+                    annotations.annotate(Annotation::error(&rules::UNKNOWN_RUNNER_LABEL, *node, &msg));
+                }
+
+                if label == "self-hosted" && !public_triggers.is_empty() {
+                    let mut annotation = Annotation::warn(
+                        &rules::SELF_HOSTED_PUBLIC_TRIGGER,
+                        *node,
+                        &String::from("self hosted runner can be reached by anyone who can open a pull request or issue"),
+                    );
+                    for t in public_triggers.iter() {
+                        let event = t.value().map(ToString::to_string).unwrap_or_default();
+                        annotation = annotation.related(*t, &format!("triggered by {event}"));
+                    }
+                    annotations.annotate(annotation);
+                }
+            }
+        }
+    }
+}
+
+impl<'a> RunnerLabels<'a> {
+    fn candidates(&self) -> impl Iterator<Item = &str> {
+        HOSTED
+            .iter()
+            .chain(SELF_HOSTED.iter())
+            .copied()
+            .chain(self.0.runner_labels().iter().map(String::as_str))
+    }
+
+    fn known(&self, label: &str, self_hosted: bool) -> bool {
+        let configured = self.0.runner_labels().iter().any(|l| l.eq_ignore_ascii_case(label));
+        configured
+            || HOSTED.contains(&label)
+            || (self_hosted && SELF_HOSTED.contains(&label))
+    }
+
+    // suggestions are only useful for typos, not for entirely different labels
+    fn closest(&self, label: &str) -> Option<&str> {
+        closest(label, self.candidates())
+    }
+}
@@ -0,0 +1,575 @@
+use crate::document::{Annotatable, Annotation, Document, DocumentPointer, DocumentSpan, Edit};
+use crate::expression::parser::Expr;
+use crate::expression::schema::{Schema, Shape};
+use crate::lint::deprecations::{deprecations, Command as DeprecatedCommand};
+use crate::lint::{rules, LintRule};
+use crate::scavenge::ast::{PossumMap, PossumNode};
+use crate::shell::{self, Command, Expansion, Script, Word};
+use crate::workflow::contexts::schema;
+use crate::workflow::expressions::Place;
+use crate::workflow::job::{Job, Step};
+use crate::workflow::{Defaults, Workflow};
+use std::ops::Range;
+
+// files the runner reads back after each step
+const COMMAND_FILES: &[&str] = &["GITHUB_ENV", "GITHUB_OUTPUT", "GITHUB_PATH"];
+
+// variables the runner, the shell or the tools it runs already give a
+// meaning, a fix must not set any of them for the script
+const RESERVED_PREFIXES: &[&str] = &["GITHUB_", "RUNNER_", "ACTIONS_", "BASH", "LC_", "LD_"];
+const RESERVED: &[&str] = &[
+    "CDPATH", "CI", "ENV", "HOME", "HOSTNAME", "IFS", "LANG", "LOGNAME", "OLDPWD", "OPTARG",
+    "OPTIND", "PATH", "PS1", "PS2", "PS4", "PWD", "SHELL", "SHELLOPTS", "SHLVL", "TERM", "TMPDIR",
+    "TZ", "UID", "USER",
+];
+
+// Scripts are pasted together with any ${{ }} before the shell runs them, so
+// bash and sh run steps are lexed to find expansions and writes that let
+// their values run as code. Deprecated workflow commands are looked for in
+// every run step, whatever its shell. Findings are placed on the line of the script
+// they are on, which needs the document to find.
+pub struct Scripts<'a>(pub &'a Document);
+
+// how a run step's script is run
+struct Shell<'a> {
+    // the shell: that chose it, None for the default
+    node: Option<&'a PossumNode<String>>,
+    errexit: bool,
+}
+
+// a run step's script and where it is in the document
+struct StepScript<'s> {
+    step: &'s Step,
+    // env: of the job and workflow, which the step's own env: would shadow
+    inherited: Vec<&'s str>,
+    run: &'s PossumNode<String>,
+    raw: &'s str,
+    script: Script,
+    // where each line of the script starts in the document
+    lines: Vec<Option<DocumentPointer>>,
+}
+
+impl<'s> StepScript<'s> {
+    // falls back to the run: when the line can't be found
+    fn at(&self, line: usize, col: usize) -> DocumentPointer {
+        match self.lines.get(line) {
+            Some(Some(start)) => DocumentPointer(start.0 + col),
+            _ => self.run.loc(),
+        }
+    }
+
+    // Where some bytes of the script are in the document, only when they
+    // are there exactly as they are in the script so they can be edited.
+    fn span(&self, document: &Document, range: Range<usize>) -> Option<DocumentSpan> {
+        let text = self.raw.get(range.clone())?;
+        let line = self.raw[..range.start].matches('\n').count();
+        let col = range.start - self.raw[..range.start].rfind('\n').map_or(0, |n| n + 1);
+        let start = (*self.lines.get(line)?)?;
+
+        let span = DocumentSpan::new(
+            DocumentPointer(start.0 + col),
+            DocumentPointer(start.0 + col + text.len()),
+        );
+        (document.slice(span).ok()? == text.as_bytes()).then_some(span)
+    }
+}
+
+impl<'a> LintRule<Workflow> for Scripts<'a> {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        for (_, job) in wf.jobs() {
+            let job = match job.value() {
+                Some(job) => job,
+                None => continue,
+            };
+            let schema = schema(wf, Some(job)).available(Place::Step.contexts());
+
+            for step in job.steps().filter_map(|s| s.value()) {
+                let (run, raw) = match step.run.as_ref().and_then(|r| Some((r, r.value()?))) {
+                    Some(run) => run,
+                    None => continue,
+                };
+                let shell = match effective_shell(wf, job, step) {
+                    Some(shell) => shell,
+                    None => {
+                        self.deprecated_lines(run, raw, annotations);
+                        continue;
+                    }
+                };
+
+                let script = StepScript {
+                    step,
+                    inherited: env_names(&job.env).chain(env_names(&wf.env)).collect(),
+                    run,
+                    raw,
+                    script: shell::parse(raw),
+                    lines: self.0.locate_lines(run.loc(), raw),
+                };
+
+                self.unquoted(&script, &schema, annotations);
+                self.errexit(&script, &shell, annotations);
+                self.command_files(&script, &schema, annotations);
+                self.deprecated(&script, annotations);
+            }
+        }
+    }
+}
+
+impl<'a> Scripts<'a> {
+    fn unquoted(&self, s: &StepScript, schema: &Schema, annotations: &mut impl Annotatable) {
+        for expansion in s.script.expansions.iter().filter(|e| !e.quoted) {
+            let expr = match Expr::parse(&expansion.source) {
+                Ok(expr) => expr,
+                Err(_) => continue,
+            };
+            if harmless(schema, &expr) {
+                continue;
+            }
+
+            let mut annotation = Annotation::warn(
+                &rules::UNQUOTED_EXPRESSION,
+                &s.at(expansion.line, expansion.col),
+                &format!("${{{{ {expr} }}}} is substituted into the script unquoted"),
+            );
+            // the quoted variable isn't split into words or globbed like the
+            // substituted text was, which a script could depend on
+            for edit in self.move_to_env(s, expansion, &expr).into_iter().flatten() {
+                annotation = annotation.edit(edit).unsafe_fix();
+            }
+            annotations.annotate(annotation);
+        }
+    }
+
+    // The fix for an unquoted expression, which passes it to the script
+    // through env: and quotes the variable. None when that can't be done
+    // without rewriting more than a line.
+    fn move_to_env(&self, s: &StepScript, expansion: &Expansion, expr: &Expr) -> Option<Vec<Edit>> {
+        let name = env_name(expr)?;
+        let value = format!("${{{{ {} }}}}", expansion.source.trim());
+        let mut edits = vec![Edit::replace(
+            &s.span(self.0, expansion.range.clone())?,
+            format!("\"${name}\""),
+        )];
+
+        let existing = s.step.env.as_ref().and_then(|e| e.value());
+        let same = existing.and_then(|env| env.iter().find(|(k, _)| k.value() == Some(&name)));
+
+        match (existing, same) {
+            // already moved for an earlier use of the same expression
+            (_, Some((_, v))) if v.value() == Some(&value) => {}
+            (_, Some(_)) => return None,
+            // setting it would change what the runner, the shell or the rest
+            // of the script sees
+            _ if reserved(&name)
+                || s.inherited.contains(&name.as_str())
+                || mentions(s.raw, &name) =>
+            {
+                return None
+            }
+            (Some(env), None) => {
+                // above the first variable, which has to start its own line
+                let (first, _) = env.iter().next()?;
+                let indent = self.indent(&first.loc(), false)?;
+                edits.push(Edit::insert(&first.loc(), format!("{name}: {value}\n{indent}")));
+            }
+            (None, None) => {
+                // above run:, which can follow the - of the step
+                let path = self.0.path_of(s.run)?;
+                let key = self.0.key_of(path)?;
+                let indent = self.indent(&key.start(), true)?;
+                edits.push(Edit::insert(
+                    &key.start(),
+                    format!("env:\n{indent}  {name}: {value}\n{indent}"),
+                ));
+            }
+        }
+
+        Some(edits)
+    }
+
+    // spaces as wide as the line up to the pointer, if that's indentation
+    fn indent(&self, at: &DocumentPointer, after_dash: bool) -> Option<String> {
+        let raw = self.0.raw();
+        let line_start = raw[..at.0].iter().rposition(|c| *c == b'\n').map_or(0, |n| n + 1);
+        let before = &raw[line_start..at.0];
+        let blank = before.iter().all(|c| *c == b' ' || (after_dash && *c == b'-'));
+        blank.then(|| " ".repeat(before.len()))
+    }
+
+    fn errexit(&self, s: &StepScript, shell: &Shell, annotations: &mut impl Annotatable) {
+        let node = match shell.node {
+            Some(node) if !shell.errexit => node,
+            _ => return,
+        };
+        let commands = &s.script.commands;
+        let lines = s.raw.lines().filter(|l| !l.trim().is_empty()).count();
+        if lines < 2 || commands.len() < 2 || commands.iter().any(sets_errexit) {
+            return;
+        }
+
+        annotations.annotate(
+            Annotation::warn(
+                &rules::MISSING_SET_E,
+                s.run,
+                &format!(
+                    "shell `{}` doesn't stop when a command fails and the script doesn't `set -e`",
+                    node.value().map_or("", |s| s.as_str())
+                ),
+            )
+            .related(node, &"shell set here"),
+        );
+    }
+
+    fn command_files(&self, s: &StepScript, schema: &Schema, annotations: &mut impl Annotatable) {
+        let commands = &s.script.commands;
+        for (idx, command) in commands.iter().enumerate() {
+            let file = match written_file(command) {
+                Some(file) => file,
+                None => continue,
+            };
+
+            // tee writes whatever is piped into it
+            let mut first = idx;
+            if command.name() == Some("tee") {
+                while first > 0 && commands[first].piped {
+                    first -= 1;
+                }
+            }
+
+            let expansions = s.script.expansions.iter().filter(|e| (first..=idx).contains(&e.command));
+            for expansion in expansions {
+                let expr = match Expr::parse(&expansion.source) {
+                    Ok(expr) => expr,
+                    Err(_) => continue,
+                };
+                let untrusted = match untrusted(&expr) {
+                    Some(untrusted) if !harmless(schema, &expr) => untrusted,
+                    _ => continue,
+                };
+
+                annotations.annotate(Annotation::error(
+                    &rules::UNSANITIZED_ENV_WRITE,
+                    &s.at(expansion.line, expansion.col),
+                    &format!("{untrusted} is written to ${file}, a newline in it can add any line it likes"),
+                ));
+            }
+        }
+    }
+
+    fn deprecated(&self, s: &StepScript, annotations: &mut impl Annotatable) {
+        let commands = &s.script.commands;
+        for (idx, command) in commands.iter().enumerate() {
+            for word in command.words.iter() {
+                let deprecated = match deprecations().command(&word.text) {
+                    Some((_, deprecated)) => deprecated,
+                    None => continue,
+                };
+
+                let mut annotation = deprecated_command(&s.at(word.line, word.col), deprecated);
+
+                // only `echo "::command ..."` on its own, where the output
+                // can simply be sent to the file instead
+                let alone = command.name() == Some("echo")
+                    && command.words.last().map(|w| w.range.clone()) == Some(word.range.clone())
+                    && command.redirects.is_empty()
+                    && !commands.get(idx + 1).map_or(false, |c| c.piped);
+                let edits = alone.then(|| self.write_to_file(s, word, deprecated)).flatten();
+                for edit in edits.into_iter().flatten() {
+                    annotation = annotation.edit(edit);
+                }
+
+                annotations.annotate(annotation);
+            }
+        }
+    }
+
+    // Other shells, like pwsh, aren't lexed but the runner reads the commands
+    // from their output all the same, so each line is searched for them
+    fn deprecated_lines(&self, run: &PossumNode<String>, raw: &str, annotations: &mut impl Annotatable) {
+        let lines = self.0.locate_lines(run.loc(), raw);
+        for (idx, line) in raw.lines().enumerate() {
+            let (col, deprecated) = match deprecations().command(line) {
+                Some(found) => found,
+                None => continue,
+            };
+            let at = match lines.get(idx) {
+                Some(Some(start)) => DocumentPointer(start.0 + col),
+                _ => run.loc(),
+            };
+            annotations.annotate(deprecated_command(&at, deprecated));
+        }
+    }
+
+    // `::set-output name=x::value` becomes `x=value` and `::add-path::dir`
+    // becomes `dir`, appended to the command's file
+    fn write_to_file(&self, s: &StepScript, word: &Word, deprecated: &DeprecatedCommand) -> Option<Vec<Edit>> {
+        let file = deprecated.file.as_ref()?;
+        let source = s.raw.get(word.range.clone())?;
+        let start = source.find(&format!("::{}", deprecated.name))?;
+        let rest = &source[start + 2 + deprecated.name.len()..];
+
+        let (len, replacement) = if let Some(named) = rest.strip_prefix(" name=") {
+            let name_len = named.find("::")?;
+            (" name=".len() + name_len + 2, format!("{}=", &named[..name_len]))
+        } else if rest.starts_with("::") {
+            (2, String::new())
+        } else {
+            return None;
+        };
+
+        let command_start = word.range.start + start;
+        let command_end = command_start + 2 + deprecated.name.len() + len;
+        Some(vec![
+            Edit::replace(&s.span(self.0, command_start..command_end)?, replacement),
+            Edit::insert(&s.span(self.0, word.range.clone())?.end(), format!(" >> \"${file}\"")),
+        ])
+    }
+}
+
+// The shell for a step, None for shells other than bash and sh. Without a
+// shell: steps use bash, or pwsh on windows.
+fn effective_shell<'a>(wf: &'a Workflow, job: &'a Job, step: &'a Step) -> Option<Shell<'a>> {
+    let defaults = |d: &'a Option<PossumNode<Defaults>>| {
+        d.as_ref()?.value()?.run.as_ref()?.value()?.shell.as_ref()
+    };
+    let node = step
+        .shell
+        .as_ref()
+        .or_else(|| defaults(&job.defaults))
+        .or_else(|| defaults(&wf.defaults));
+
+    let node = match node {
+        Some(node) => node,
+        None => {
+            let windows = job
+                .runs_on
+                .as_ref()
+                .and_then(|r| r.value())
+                .map_or(false, |r| {
+                    r.labels()
+                        .filter_map(|l| l.value())
+                        .any(|l| l.to_lowercase().contains("windows"))
+                });
+            return (!windows).then_some(Shell {
+                node: None,
+                errexit: true,
+            });
+        }
+    };
+
+    let errexit = match node.value()?.as_str() {
+        // bash runs with -eo pipefail and sh with -e
+        "bash" | "sh" => true,
+        custom if custom.contains("{0}") => {
+            let mut words = custom.split_whitespace();
+            let program = words.next()?.rsplit('/').next()?;
+            if !matches!(program, "bash" | "sh" | "dash" | "zsh" | "ksh") {
+                return None;
+            }
+            errexit_flag(words)
+        }
+        _ => return None,
+    };
+
+    Some(Shell {
+        node: Some(node),
+        errexit,
+    })
+}
+
+fn deprecated_command(at: &DocumentPointer, deprecated: &DeprecatedCommand) -> Annotation {
+    Annotation::warn(
+        &rules::DEPRECATED_WORKFLOW_COMMAND,
+        at,
+        &format!("::{} is deprecated, {}", deprecated.name, deprecated.replacement),
+    )
+}
+
+// -e, -eu, -o errexit and the like
+fn errexit_flag<'a>(mut words: impl Iterator<Item = &'a str>) -> bool {
+    while let Some(word) = words.next() {
+        if word == "-o" {
+            if words.next() == Some("errexit") {
+                return true;
+            }
+        } else if word.starts_with('-') && !word.starts_with("--") && word.contains('e') {
+            return true;
+        }
+    }
+    false
+}
+
+fn sets_errexit(command: &Command) -> bool {
+    command.name() == Some("set") && errexit_flag(command.args().map(|w| w.text.as_str()))
+}
+
+// which of $GITHUB_ENV and friends a command writes to
+fn written_file(command: &Command) -> Option<&'static str> {
+    let file = |target: &str| {
+        let name = target.strip_prefix('$')?;
+        let name = name
+            .strip_prefix('{')
+            .and_then(|n| n.strip_suffix('}'))
+            .unwrap_or(name);
+        COMMAND_FILES.iter().copied().find(|f| *f == name)
+    };
+
+    let redirected = command
+        .redirects
+        .iter()
+        .filter(|r| r.op == ">" || r.op == ">>")
+        .find_map(|r| file(&r.target.text));
+    let teed = match command.name() {
+        Some("tee") => command.args().find_map(|w| file(&w.text)),
+        _ => None,
+    };
+
+    redirected.or(teed)
+}
+
+// the first reference to something anyone opening a pull request or issue
+// can choose the value of
+fn untrusted(expr: &Expr) -> Option<String> {
+    let mut found = None;
+    expr.walk(&mut |e| {
+        if found.is_some() {
+            return;
+        }
+        let path = match e.path() {
+            Some(path) => path,
+            None => return,
+        };
+        let owned: Vec<String> = path.iter().map(|p| p.to_lowercase()).collect();
+        let lower: Vec<&str> = owned.iter().map(|p| p.as_str()).collect();
+        if matches!(
+            lower[..],
+            ["github", "event", _, ..] | ["github", "head_ref"] | ["inputs", _, ..]
+        ) {
+            found = Some(path.join("."));
+        }
+    });
+    found
+}
+
+// literals, numbers and booleans can't break out of the command
+fn harmless(schema: &Schema, expr: &Expr) -> bool {
+    matches!(expr, Expr::Literal(_)) || matches!(schema.shape_of(expr), Shape::Number | Shape::Bool)
+}
+
+// github.event.pull_request.title is PULL_REQUEST_TITLE and inputs.name is
+// INPUTS_NAME, None for anything but a plain reference. The names are short
+// enough to collide, github.path is PATH, so move_to_env checks them.
+fn env_name(expr: &Expr) -> Option<String> {
+    let path = expr.path().filter(|p| !p.contains(&"*"))?;
+    let path = match path[..] {
+        ["github", "event", ref rest @ ..] | ["github", ref rest @ ..] if !rest.is_empty() => rest,
+        ref all => all,
+    };
+
+    let name: String = path
+        .join("_")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    (!name.starts_with(|c: char| c.is_ascii_digit())).then_some(name)
+}
+
+fn reserved(name: &str) -> bool {
+    RESERVED.contains(&name) || RESERVED_PREFIXES.iter().any(|p| name.starts_with(p))
+}
+
+fn env_names<'a>(env: &'a Option<PossumNode<PossumMap<String, String>>>) -> impl Iterator<Item = &'a str> {
+    env.iter()
+        .filter_map(|e| e.value())
+        .flat_map(|e| e.iter())
+        .filter_map(|(k, _)| k.value().map(|k| k.as_str()))
+}
+
+// whether the script already uses the name for anything, as a variable it
+// reads or sets or as a word in a command
+fn mentions(script: &str, name: &str) -> bool {
+    script
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .any(|word| word == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scripts;
+    use crate::document::{Annotations, Document};
+    use crate::lint::LintRule;
+    use crate::workflow::lints::test_lint;
+
+    fn lint(raw: &str) -> Annotations {
+        test_lint(raw, |wf, doc, a| Scripts(doc).lint(wf, a))
+    }
+
+    // the line and column each finding starts at, counting from 1
+    fn positions(raw: &str) -> Vec<(usize, usize)> {
+        lint(raw)
+            .entries()
+            .map(|a| {
+                let at = a.span().start().0;
+                let line_start = raw[..at].rfind('\n').map_or(0, |n| n + 1);
+                (raw[..at].matches('\n').count() + 1, at - line_start + 1)
+            })
+            .collect()
+    }
+
+    // the document after every fix, unsafe or not
+    fn fixed(raw: &str) -> String {
+        let annotations = lint(raw);
+        let fixes = annotations.entries().map(|a| a.fix(true)).filter(|f| !f.is_empty());
+        String::from_utf8(Document::new(raw).apply(fixes).0).unwrap()
+    }
+
+    const STEPS: &str = "on: push\njobs:\n  build:\n    steps:\n";
+
+    #[test]
+    fn findings_are_on_the_line_of_the_script_they_are_on() {
+        let literal = format!("{STEPS}      - run: |\n          echo one\n          echo ${{{{ github.head_ref }}}}\n");
+        assert_eq!(positions(&literal), [(7, 16)]);
+
+        let nested = format!("{STEPS}      - name: greet\n        run: echo ${{{{ github.head_ref }}}}\n");
+        assert_eq!(positions(&nested), [(6, 19)]);
+
+        // folding joins the lines, so only the run: itself can be pointed at
+        let folded = format!("{STEPS}      - run: >\n          echo one\n          ${{{{ github.head_ref }}}}\n");
+        assert_eq!(positions(&folded), [(5, 14)]);
+    }
+
+    #[test]
+    fn unquoted_expressions_move_to_env() {
+        let raw = format!("{STEPS}      - run: echo ${{{{ github.head_ref }}}} ${{{{ github.head_ref }}}}\n        env:\n          CI: true\n");
+        let expected = format!("{STEPS}      - run: echo \"$HEAD_REF\" \"$HEAD_REF\"\n        env:\n          HEAD_REF: ${{{{ github.head_ref }}}}\n          CI: true\n");
+        // both add the same variable, so the second is fixed by the next run
+        // which finds it already there
+        assert_eq!(fixed(&fixed(&raw)), expected);
+
+        // a name the script, the runner or an existing env: already uses
+        for taken in [
+            format!("{STEPS}      - run: echo $HEAD_REF ${{{{ github.head_ref }}}}\n"),
+            format!("{STEPS}      - run: echo ${{{{ github.path }}}}\n"),
+            format!("{STEPS}      - run: echo ${{{{ github.head_ref }}}}\n        env:\n          HEAD_REF: main\n"),
+        ] {
+            assert_eq!(lint(&taken).entries().count(), 1, "{taken}");
+            assert_eq!(fixed(&taken), taken);
+        }
+    }
+
+    #[test]
+    fn deprecated_commands_write_to_their_file() {
+        let raw = format!("{STEPS}      - run: |\n          echo \"::set-output name=sha::$(git rev-parse HEAD)\"\n          echo \"::add-path::/opt/bin\"\n");
+        let expected = format!("{STEPS}      - run: |\n          echo \"sha=$(git rev-parse HEAD)\" >> \"$GITHUB_OUTPUT\"\n          echo \"/opt/bin\" >> \"$GITHUB_PATH\"\n");
+        assert_eq!(fixed(&raw), expected);
+
+        // the output is piped elsewhere, so it can't be sent to the file
+        let piped = format!("{STEPS}      - run: echo \"::set-output name=a::b\" | tee log\n");
+        assert_eq!(lint(&piped).entries().count(), 1);
+        assert_eq!(fixed(&piped), piped);
+    }
+}
@@ -0,0 +1,341 @@
+use crate::config::Options;
+use crate::document::{Annotatable, Annotation};
+use crate::expression::embedded;
+use crate::expression::parser::{Expr, Literal};
+use crate::lint::{rules, LintRule};
+use crate::scavenge::ast::{PossumMap, PossumNode};
+use crate::workflow::job::{Job, JobSecrets, Step};
+use crate::workflow::on::EventKind;
+use crate::workflow::Workflow;
+use std::fmt::Display;
+
+// GitHub's own actions, on top of any configured trusted owners
+const FIRST_PARTY_OWNERS: [&str; 2] = ["actions", "github"];
+
+// events that run with secrets on behalf of forks
+const FORK_EVENTS: [EventKind; 2] = [EventKind::PullRequestTarget, EventKind::WorkflowRun];
+
+// always available and already handed to every action, so not worth tracking
+const GITHUB_TOKEN: &str = "GITHUB_TOKEN";
+
+pub struct SecretsExposure<'a>(pub &'a Options);
+
+impl<'a> LintRule<Workflow> for SecretsExposure<'a> {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        let fork_triggers: Vec<_> = wf
+            .on
+            .iter()
+            .filter_map(|on| on.value())
+            .flat_map(|on| FORK_EVENTS.iter().filter_map(|e| on.event(*e)))
+            .collect();
+
+        for (name, job) in wf.jobs() {
+            let job = match job.value() {
+                Some(job) => job,
+                None => continue,
+            };
+
+            for step in job.steps().filter_map(|s| s.value()) {
+                self.lint_step(step, annotations);
+            }
+
+            if let (Some(secrets), Some(uses)) = (job.secrets.as_ref(), job.uses.as_ref()) {
+                let remote = uses.value().map_or(false, |u| !u.starts_with("./"));
+                if remote && matches!(secrets.value(), Some(JobSecrets::Inherit)) {
+                    annotations.annotate(Annotation::warn(
+                        &rules::INHERITED_SECRETS,
+                        secrets,
+                        &format!(
+                            "every secret is passed to {}",
+                            uses.value().cloned().unwrap_or_default()
+                        ),
+                    ))
+                }
+            }
+
+            let uses_secrets = job_strings(job)
+                .iter()
+                .any(|s| s.value().map_or(false, |v| !secret_refs(v).is_empty()))
+                || matches!(job.secrets.as_ref().and_then(|s| s.value()), Some(JobSecrets::Inherit));
+
+            if uses_secrets && !fork_triggers.is_empty() {
+                let job_name = name.value().cloned().unwrap_or_default();
+                let mut annotation = Annotation::warn(
+                    &rules::SECRETS_ON_FORK_TRIGGER,
+                    name,
+                    &format!("{job_name} uses secrets and can be triggered from a fork"),
+                );
+                for t in fork_triggers.iter() {
+                    let event = t.value().map(ToString::to_string).unwrap_or_default();
+                    annotation = annotation.related(*t, &format!("triggered by {event}"));
+                }
+                annotations.annotate(annotation);
+            }
+        }
+    }
+}
+
+impl<'a> SecretsExposure<'a> {
+    fn lint_step(&self, step: &Step, annotations: &mut impl Annotatable) {
+        if let Some(run) = step.run.as_ref() {
+            for secret in run.value().map(|r| secret_refs(r)).unwrap_or_default() {
+                annotations.annotate(Annotation::warn(
+                    &rules::SECRET_IN_RUN,
+                    run,
+                    &format!("{secret} is interpolated into the script, pass it through env instead"),
+                ))
+            }
+        }
+
+        let action = match step.unpinned_action() {
+            Some(action) => action,
+            None => return,
+        };
+
+        let raw = action.value().cloned().unwrap_or_default();
+        let owner = raw.split('/').next().unwrap_or_default();
+        let trusted = FIRST_PARTY_OWNERS
+            .iter()
+            .copied()
+            .chain(self.0.trusted_action_owners().iter().map(String::as_str))
+            .any(|o| o.eq_ignore_ascii_case(owner));
+
+        if trusted {
+            return;
+        }
+
+        for value in map_values(&step.with).chain(map_values(&step.env)) {
+            for secret in value.value().map(|v| secret_refs(v)).unwrap_or_default() {
+                annotations.annotate(
+                    Annotation::warn(
+                        &rules::SECRET_TO_UNPINNED_ACTION,
+                        value,
+                        &format!("{secret} is passed to {raw}, which isn't pinned to a commit sha"),
+                    )
+                    .related(action, &"pin this to a full commit sha"),
+                )
+            }
+        }
+    }
+}
+
+// A reusable workflow only receives the secrets it declares, unless the
+// caller inherits them, so anything else is empty when it is called. When
+// other events trigger it too the repository's secrets are there for those
+// runs, so it's only worth a note.
+pub struct UndeclaredSecrets;
+
+impl LintRule<Workflow> for UndeclaredSecrets {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        let events: Vec<_> = wf
+            .on
+            .iter()
+            .filter_map(|on| on.value())
+            .flat_map(|on| on.events())
+            .collect();
+        let call = events
+            .iter()
+            .find(|(kind, _)| kind.value() == Some(&EventKind::WorkflowCall));
+        let only_called = events.len() == 1;
+
+        let declared: Vec<&str> = match call {
+            Some((_, event)) => event
+                .value()
+                .and_then(|e| e.secrets.as_ref())
+                .and_then(|s| s.value())
+                .into_iter()
+                .flat_map(|s| s.iter())
+                .filter_map(|(name, _)| name.value().map(String::as_str))
+                .collect(),
+            None => return,
+        };
+
+        let strings = map_values(&wf.env).chain(
+            wf.jobs()
+                .filter_map(|(_, job)| job.value())
+                .flat_map(job_strings),
+        );
+
+        for s in strings {
+            for secret in s.value().map(|v| secret_refs(v)).unwrap_or_default() {
+                // all of them is whatever the caller passed, declared or not
+                let name = match secret {
+                    SecretRef::Named(name) => name,
+                    SecretRef::All => continue,
+                };
+                if declared.iter().any(|d| d.eq_ignore_ascii_case(&name)) {
+                    continue;
+                }
+                annotations.annotate(if only_called {
+                    Annotation::warn(
+                        &rules::UNDECLARED_SECRET,
+                        s,
+                        &format!("secrets.{name} is not declared by workflow_call and will be empty unless the caller inherits secrets"),
+                    )
+                } else {
+                    Annotation::info(
+                        &rules::UNDECLARED_SECRET,
+                        s,
+                        &format!("secrets.{name} is not declared by workflow_call and will be empty when this workflow is called, unless the caller inherits secrets"),
+                    )
+                })
+            }
+        }
+    }
+}
+
+fn map_values(
+    map: &Option<PossumNode<PossumMap<String, String>>>,
+) -> impl Iterator<Item = &PossumNode<String>> {
+    map.iter()
+        .filter_map(|m| m.value())
+        .flat_map(|m| m.iter())
+        .map(|(_, v)| v)
+}
+
+// every string in a job that could refer to a secret
+fn job_strings(job: &Job) -> Vec<&PossumNode<String>> {
+    let mut strings: Vec<_> = job
+        .cond
+        .iter()
+        .chain(map_values(&job.env))
+        .chain(map_values(&job.with))
+        .chain(map_values(&job.outputs))
+        .collect();
+
+    if let Some(JobSecrets::Secrets(passed)) = job.secrets.as_ref().and_then(|s| s.value()) {
+        strings.extend(passed.iter().map(|(_, v)| v));
+    }
+
+    for step in job.steps().filter_map(|s| s.value()) {
+        strings.extend(
+            step.cond
+                .iter()
+                .chain(step.run.iter())
+                .chain(map_values(&step.with))
+                .chain(map_values(&step.env)),
+        );
+    }
+
+    strings
+}
+
+// A secret an expression reads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SecretRef {
+    // secrets.NAME or secrets['NAME']
+    Named(String),
+    // the secrets context itself, or indexed by something only known when it runs
+    All,
+}
+
+impl Display for SecretRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretRef::Named(name) => write!(f, "secrets.{name}"),
+            SecretRef::All => write!(f, "every secret"),
+        }
+    }
+}
+
+// The secrets read by the ${{ }} expressions in raw. Expressions that don't
+// parse are left to the expression lints.
+pub(crate) fn secret_refs(raw: &str) -> Vec<SecretRef> {
+    let mut found = Vec::new();
+    for expr in embedded(raw).unwrap_or_default().iter().filter_map(|e| e.parse().ok()) {
+        collect_secrets(&expr, &mut found);
+    }
+    found
+}
+
+fn collect_secrets(expr: &Expr, found: &mut Vec<SecretRef>) {
+    let is_secrets = |e: &Expr| matches!(e, Expr::Context(name) if name.eq_ignore_ascii_case("secrets"));
+    let mut named = |name: &str| {
+        if !name.eq_ignore_ascii_case(GITHUB_TOKEN) {
+            found.push(SecretRef::Named(name.to_owned()))
+        }
+    };
+
+    match expr {
+        Expr::Property(e, name) if is_secrets(e) => named(name),
+        Expr::Index(e, i) if is_secrets(e) => match i.as_ref() {
+            Expr::Literal(Literal::Str(name)) => named(name),
+            i => {
+                found.push(SecretRef::All);
+                collect_secrets(i, found);
+            }
+        },
+        e if is_secrets(e) => found.push(SecretRef::All),
+        Expr::Literal(_) | Expr::Context(_) => {}
+        Expr::Property(e, _) | Expr::Filter(e) | Expr::Not(e) => collect_secrets(e, found),
+        Expr::Index(e, i) => {
+            collect_secrets(e, found);
+            collect_secrets(i, found);
+        }
+        Expr::Binary(l, _, r) => {
+            collect_secrets(l, found);
+            collect_secrets(r, found);
+        }
+        Expr::Call(_, args) => args.iter().for_each(|a| collect_secrets(a, found)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{secret_refs, SecretRef, SecretsExposure, UndeclaredSecrets};
+    use crate::config::Options;
+    use crate::lint::LintRule;
+    use crate::workflow::lints::test_lint;
+
+    fn names(raw: &str) -> Vec<String> {
+        secret_refs(raw).iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn secrets_are_found_in_expressions() {
+        assert_eq!(names("echo ${{ secrets.TOKEN }} ${{ secrets['NPM-TOKEN'] }}"), ["secrets.TOKEN", "secrets.NPM-TOKEN"]);
+        assert_eq!(names("${{ format('{0}:{1}', secrets.USER, secrets['PASS']) }}"), ["secrets.USER", "secrets.PASS"]);
+        assert_eq!(names("${{ toJSON(secrets) }}"), ["every secret"]);
+        assert_eq!(names("${{ secrets[matrix.name] }}"), ["every secret"]);
+        assert_eq!(names("${{ SECRETS.deploy_key }}"), ["secrets.deploy_key"]);
+    }
+
+    #[test]
+    fn only_the_secrets_context_is_a_secret() {
+        assert!(secret_refs("echo secrets.TOKEN ${{ github.secrets }} ${{ inputs.secrets }}").is_empty());
+        assert!(secret_refs("${{ secrets.GITHUB_TOKEN }} ${{ secrets['github_token'] }}").is_empty());
+        assert_eq!(secret_refs("${{ 'secrets.NOT }} one' }} ${{ secrets.ONE }}"), [SecretRef::Named("ONE".to_owned())]);
+        // reported by the expression lints instead
+        assert!(secret_refs("${{ secrets.ONE ( }}").is_empty());
+    }
+
+    #[test]
+    fn secrets_in_run_are_reported() {
+        let raw = "on: push\njobs:\n  build:\n    steps:\n      - run: echo '${{ toJSON(secrets) }}'\n      - run: echo \"$TOKEN\"\n        env:\n          TOKEN: ${{ secrets.TOKEN }}\n";
+        let options = Options::default();
+        let annotations = test_lint(raw, |wf, _, a| SecretsExposure(&options).lint(wf, a));
+        let messages: Vec<_> = annotations.entries().map(|a| a.message().to_owned()).collect();
+        assert_eq!(messages, ["every secret is interpolated into the script, pass it through env instead"]);
+    }
+
+    #[test]
+    fn undeclared_secrets_are_reported_by_name() {
+        let raw = "on:\n  workflow_call:\n    secrets:\n      TOKEN: {}\njobs:\n  build:\n    steps:\n      - uses: ./deploy\n        with:\n          token: ${{ secrets['token'] }}\n          key: ${{ secrets.KEY }}\n          all: ${{ toJSON(secrets) }}\n";
+        let annotations = test_lint(raw, |wf, _, a| UndeclaredSecrets.lint(wf, a));
+        let messages: Vec<_> = annotations.entries().map(|a| a.message().to_owned()).collect();
+        assert_eq!(
+            messages,
+            ["secrets.KEY is not declared by workflow_call and will be empty unless the caller inherits secrets"]
+        );
+    }
+}
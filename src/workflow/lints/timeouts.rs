@@ -0,0 +1,160 @@
+use crate::config::Options;
+use crate::document::{Annotatable, Annotation};
+use crate::lint::{rules, LintRule};
+use crate::scavenge::ast::PossumNode;
+use crate::workflow::Workflow;
+
+// what GitHub uses when a job doesn't say
+const DEFAULT_TIMEOUT: f64 = 360.0;
+
+pub struct Timeouts<'a>(pub &'a Options);
+
+impl<'a> LintRule<Workflow> for Timeouts<'a> {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
+
+        for (name, job) in wf.jobs() {
+            let job = match job.value() {
+                Some(job) => job,
+                None => continue,
+            };
+
+            // reusable workflows set their own timeouts
+            if job.uses.is_some() {
+                continue;
+            }
+
+            let job_timeout = match job.timeout_minutes.as_ref() {
+                None => {
+                    let job_name = name.value().cloned().unwrap_or_default();
+                    annotations.annotate(Annotation::warn(
+                        &rules::MISSING_TIMEOUT,
+                        name,
+                        &format!("{job_name} has no timeout-minutes and can run for {DEFAULT_TIMEOUT} minutes"),
+                    ));
+                    None
+                }
+                Some(t) => self.check(t, annotations),
+            };
+
+            for step in job.steps().filter_map(|s| s.value()) {
+                let timeout = match step.timeout_minutes.as_ref() {
+                    Some(t) => t,
+                    None => continue,
+                };
+
+                let minutes = match self.check(timeout, annotations) {
+                    Some(m) => m,
+                    None => continue,
+                };
+
+                let limit = job_timeout.unwrap_or(DEFAULT_TIMEOUT);
+                if minutes > limit {
+                    let mut annotation = Annotation::warn(
+                        &rules::STEP_TIMEOUT_EXCEEDS_JOB,
+                        timeout,
+                        &format!("step timeout of {minutes} minutes is longer than its job's {limit}"),
+                    );
+                    if let Some(t) = job.timeout_minutes.as_ref() {
+                        annotation = annotation.related(t, &"job timeout-minutes");
+                    }
+                    annotations.annotate(annotation);
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Timeouts<'a> {
+    // the timeout in minutes, if it is one
+    fn check(&self, timeout: &PossumNode<f64>, annotations: &mut impl Annotatable) -> Option<f64> {
+        let minutes = *timeout.value()?;
+
+        if minutes <= 0.0 || minutes.fract() != 0.0 {
+            annotations.annotate(Annotation::error(
+                &rules::INVALID_TIMEOUT,
+                timeout,
+                &format!("timeout-minutes must be a positive whole number, found {minutes}"),
+            ));
+            return None;
+        }
+
+        if let Some(max) = self.0.max_timeout_minutes() {
+            if minutes > max {
+                annotations.annotate(Annotation::warn(
+                    &rules::EXCESSIVE_TIMEOUT,
+                    timeout,
+                    &format!("timeout of {minutes} minutes is above the configured maximum of {max}"),
+                ));
+            }
+        }
+
+        Some(minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Timeouts;
+    use crate::config::Options;
+    use crate::lint::LintRule;
+    use crate::workflow::lints::test_lint;
+
+    fn messages(options: &Options, jobs: &str) -> Vec<String> {
+        let raw = format!("on: push\njobs:\n{jobs}");
+        let annotations = test_lint(&raw, |wf, _, a| Timeouts(options).lint(wf, a));
+        annotations.entries().map(|a| a.message().to_owned()).collect()
+    }
+
+    #[test]
+    fn jobs_without_a_timeout_are_reported() {
+        let options = Options::default();
+        assert_eq!(
+            messages(&options, "  build:\n    runs-on: ubuntu-latest\n"),
+            ["build has no timeout-minutes and can run for 360 minutes"]
+        );
+        assert!(messages(&options, "  build:\n    timeout-minutes: 10\n").is_empty());
+        assert!(messages(&options, "  call:\n    uses: ./.github/workflows/build.yml\n").is_empty());
+    }
+
+    #[test]
+    fn step_timeouts_longer_than_their_job_are_reported() {
+        let options = Options::default();
+        let jobs = "  build:\n    timeout-minutes: 10\n    steps:\n      - run: make\n        timeout-minutes: 20\n      - run: make test\n        timeout-minutes: 10\n";
+        assert_eq!(messages(&options, jobs), ["step timeout of 20 minutes is longer than its job's 10"]);
+
+        // against the default when the job doesn't set one
+        let jobs = "  build:\n    steps:\n      - run: make\n        timeout-minutes: 400\n";
+        assert_eq!(
+            messages(&options, jobs)[1..],
+            ["step timeout of 400 minutes is longer than its job's 360"]
+        );
+    }
+
+    #[test]
+    fn timeouts_must_be_positive_whole_numbers() {
+        let options = Options::default();
+        let jobs = "  build:\n    timeout-minutes: 0\n    steps:\n      - run: make\n        timeout-minutes: 1.5\n";
+        assert_eq!(
+            messages(&options, jobs),
+            [
+                "timeout-minutes must be a positive whole number, found 0",
+                "timeout-minutes must be a positive whole number, found 1.5",
+            ]
+        );
+    }
+
+    #[test]
+    fn timeouts_above_the_configured_maximum_are_reported() {
+        let options: Options = toml::from_str("max-timeout-minutes = 60").unwrap();
+        assert_eq!(
+            messages(&options, "  build:\n    timeout-minutes: 90\n"),
+            ["timeout of 90 minutes is above the configured maximum of 60"]
+        );
+        assert!(messages(&options, "  build:\n    timeout-minutes: 60\n").is_empty());
+        assert!(messages(&Options::default(), "  build:\n    timeout-minutes: 90\n").is_empty());
+    }
+}
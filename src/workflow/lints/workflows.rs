@@ -1,6 +1,6 @@
 use crate::workflow::Workflow;
-use crate::document::Annotatable;
-use crate::lint::{LintRule, LintViolation};
+use crate::document::{Annotatable, Annotation, Document, Edit};
+use crate::lint::{rules, LintRule, LintViolation};
 use crate::scavenge::ast::PossumNode;
 use super::MissingProperty;
 
@@ -9,7 +9,10 @@ pub struct MissingWorkflowProperties;
 impl LintRule<Workflow> for MissingWorkflowProperties {
     fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable)
     {
-        let wf = root.value().unwrap();
+        let wf = match root.value() {
+            Some(wf) => wf,
+            None => return,
+        };
 
         if wf.on.is_none() {
             annotations.annotate(MissingProperty("on").at(root))
@@ -20,3 +23,28 @@ impl LintRule<Workflow> for MissingWorkflowProperties {
         }
     }
 }
+
+// YAML 1.1 reads a bare on as true, so tools other than GitHub see a workflow
+// without any triggers
+pub struct UnquotedOnKey<'a>(pub &'a Document);
+
+impl<'a> LintRule<Workflow> for UnquotedOnKey<'a> {
+    fn lint(&self, root: &PossumNode<Workflow>, annotations: &mut impl Annotatable) {
+        let key = match self.0.key_of("$.on") {
+            Some(key) => key,
+            None => return,
+        };
+
+        let bare = self.0.slice(key).map_or(false, |k| k == b"on");
+        if bare && root.value().map_or(false, |wf| wf.on.is_some()) {
+            annotations.annotate(
+                Annotation::info(
+                    &rules::UNQUOTED_ON_KEY,
+                    &key,
+                    &String::from("on is true to YAML 1.1 parsers, quote it as \"on\""),
+                )
+                .edit(Edit::replace(&key, "\"on\"")),
+            );
+        }
+    }
+}
@@ -1,7 +1,13 @@
+pub mod concurrency;
+pub mod contexts;
+pub mod expressions;
+pub mod filter;
 pub mod job;
 pub mod lints;
 pub mod on;
 pub mod parser;
+pub mod permissions;
+pub mod simulate;
 
 pub use self::parser::WorkflowParser;
 use crate::scavenge::ast::*;
@@ -17,6 +23,32 @@ possum_node_type! {
         permissions: Permission,
         concurrency: Concurrency,
         env: PossumMap<String, String>,
+        defaults: Defaults,
+    }
+}
+
+possum_node_type! {
+    #[derive(Debug, Default)]
+    struct Defaults {
+        run: RunDefaults,
+    }
+}
+
+possum_node_type! {
+    #[derive(Debug, Default)]
+    struct RunDefaults {
+        shell: String,
+        working_directory: String,
+    }
+}
+
+impl Workflow {
+    // every job that parsed alongside its id
+    pub fn jobs(&self) -> impl Iterator<Item = (&PossumNode<String>, &PossumNode<job::Job>)> {
+        self.jobs
+            .iter()
+            .filter_map(|jobs| jobs.value())
+            .flat_map(|jobs| jobs.iter())
     }
 }
 
@@ -35,9 +67,33 @@ pub enum Permission {
     IndividualGrants(PossumMap<String, Grant>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum::Display)]
+#[strum(serialize_all = "lowercase")]
 pub enum Grant {
+    #[strum(serialize = "none")]
+    Deny,
     Read,
     Write,
-    Deny,
+}
+
+// the scopes GITHUB_TOKEN can be granted
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum::Display, strum::EnumString, strum::EnumIter,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Scope {
+    Actions,
+    Attestations,
+    Checks,
+    Contents,
+    Deployments,
+    Discussions,
+    IdToken,
+    Issues,
+    Packages,
+    Pages,
+    PullRequests,
+    RepositoryProjects,
+    SecurityEvents,
+    Statuses,
 }
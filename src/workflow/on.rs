@@ -1,5 +1,6 @@
 use std::{fmt::Display, str::FromStr};
 
+use super::filter::{FilterError, FilterPattern};
 use crate::scavenge::ast::*;
 
 #[derive(Debug)]
@@ -15,8 +16,19 @@ impl Trigger {
     }
 
     pub fn add_event_name(&mut self, kind: PossumNode<EventKind>) {
-        let loc = kind.loc();
-        self.add_event(kind, PossumNodeKind::Empty.at(&loc));
+        let span = kind.span();
+        self.add_event(kind, PossumNodeKind::Empty.at(&span));
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = (&PossumNode<EventKind>, &PossumNode<Event>)> {
+        self.0.iter()
+    }
+
+    // the node naming the event, if the workflow is triggered by it
+    pub fn event(&self, kind: EventKind) -> Option<&PossumNode<EventKind>> {
+        self.events()
+            .map(|(k, _)| k)
+            .find(|k| k.value() == Some(&kind))
     }
 }
 
@@ -82,11 +94,20 @@ impl Globbed {
     {
         Globbed(s.into())
     }
+
+    pub fn raw(&self) -> &str {
+        &self.0
+    }
+
+    pub fn pattern(&self) -> Result<FilterPattern, FilterError> {
+        FilterPattern::parse(&self.0)
+    }
 }
 
 possum_node_type!(
     #[derive(Debug, Default)]
     struct Event {
+        types: PossumSeq<String>,
         branches: PossumSeq<Globbed>,
         branches_ignore: PossumSeq<Globbed>,
         paths: PossumSeq<Globbed>,
@@ -105,7 +126,7 @@ impl Event {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, strum::Display, strum::EnumString)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, strum::Display, strum::EnumString)]
 #[strum(serialize_all = "snake_case")]
 pub enum EventKind {
     BranchProtectionRule,
@@ -169,12 +190,18 @@ pub enum WorkflowInputDefault {
     Bool(bool),
 }
 
-#[derive(Debug, strum::EnumString)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString)]
 pub enum WorkflowInputType {
+    #[strum(serialize = "string")]
     Str,
+    #[strum(serialize = "number")]
     Number,
+    #[strum(serialize = "boolean")]
     Bool,
+    #[strum(serialize = "choice")]
     Choice,
+    #[strum(serialize = "environment")]
+    Environment,
 }
 
 impl WorkflowInputType {
@@ -222,3 +249,26 @@ impl Display for BadEvent {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::WorkflowInputType;
+
+    #[test]
+    fn input_types_are_read_as_written_in_workflows() {
+        let types = [
+            ("string", WorkflowInputType::Str),
+            ("number", WorkflowInputType::Number),
+            ("boolean", WorkflowInputType::Bool),
+            ("choice", WorkflowInputType::Choice),
+            ("environment", WorkflowInputType::Environment),
+        ];
+        for (raw, expected) in types {
+            assert_eq!(WorkflowInputType::fromstr(raw).ok(), Some(expected));
+        }
+
+        // the variant names aren't types GitHub knows
+        assert!(WorkflowInputType::fromstr("Str").is_err());
+        assert!(WorkflowInputType::fromstr("bool").is_err());
+    }
+}
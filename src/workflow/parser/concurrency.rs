@@ -2,7 +2,7 @@ use crate::document::{Annotation, Annotations};
 use crate::scavenge::ast::PossumNodeKind;
 use crate::scavenge::extraction::{ExpectedYaml, Extract};
 use crate::scavenge::parsers::{OrableParser, StringParser, TransformableParser};
-use crate::scavenge::yaml::YamlKind;
+use crate::scavenge::yaml::{Source, YamlKind};
 use crate::scavenge::{Parser, UnexpectedKey};
 use crate::workflow::Concurrency;
 use yaml_peg::repr::Repr;
@@ -21,7 +21,7 @@ impl<'a, R> Parser<R, Concurrency> for ConcurrencyParser<'a>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &yaml_peg::Node<R>) -> PossumNodeKind<Concurrency>
+    fn parse_node(&mut self, root: &yaml_peg::Node<R>, source: Source) -> PossumNodeKind<Concurrency>
     where
         R: Repr,
     {
@@ -32,7 +32,7 @@ where
                         .but_found(root)
                 )
             })
-            .parse_node(root)
+            .parse_node(root, source)
     }
 }
 
@@ -49,13 +49,13 @@ impl<'a, R> Parser<R, Concurrency> for ConcurrencyStringParser
 where
     R: Repr + 'a,
 {
-    fn parse_node(&mut self, root: &yaml_peg::Node<R>) -> PossumNodeKind<Concurrency>
+    fn parse_node(&mut self, root: &yaml_peg::Node<R>, source: Source) -> PossumNodeKind<Concurrency>
     where
         R: Repr,
     {
         StringParser
             .to(|s| Concurrency::Concurrency(s))
-            .parse_node(root)
+            .parse_node(root, source)
     }
 }
 
@@ -63,7 +63,7 @@ impl<'a, R> Parser<R, Concurrency> for ConcurrencyMapParser<'a>
 where
     R: Repr + 'a,
 {
-    fn parse_node(&mut self, root: &yaml_peg::Node<R>) -> PossumNodeKind<Concurrency>
+    fn parse_node(&mut self, root: &yaml_peg::Node<R>, source: Source) -> PossumNodeKind<Concurrency>
     where
         R: Repr,
     {
@@ -75,25 +75,31 @@ where
 
                 for (key, value) in concur.iter() {
                     match key.extract_str() {
-                        Err(u) => self.annotate(u.at(key)),
+                        Err(u) => self.annotate(u.at(&source.span(key))),
                         Ok(k) => match k.to_lowercase().as_str() {
                             "group" => {
                                 group = Some({
                                     let g: PossumNodeKind<String> =
                                         value.extract_str().map(ToOwned::to_owned).into();
-                                    g.at(value)
+                                    g.at(&source.span(value))
                                 })
                             }
 
                             "cancel-in-progress" => {
                                 cancel_in_progress = Some({
-                                    let c: PossumNodeKind<bool> = value.extract_bool().into();
-                                    c.at(value)
+                                    // eg. ${{ github.ref != 'refs/heads/main' }}
+                                    let c: PossumNodeKind<bool> = match value.extract_str() {
+                                        Ok(e) if e.trim_start().starts_with("${{") => {
+                                            PossumNodeKind::Expr(e.to_owned())
+                                        }
+                                        _ => value.extract_bool().into(),
+                                    };
+                                    c.at(&source.span(value))
                                 })
                             }
 
                             u @ _ => {
-                                self.annotate(UnexpectedKey::from(u).at(value));
+                                self.annotate(UnexpectedKey::from(u).at(&source.span(value)));
                             }
                         },
                     }
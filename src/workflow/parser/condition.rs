@@ -0,0 +1,31 @@
+use crate::scavenge::ast::PossumNodeKind;
+use crate::scavenge::extraction::ExpectedYaml;
+use crate::scavenge::yaml::{Source, YamlKind};
+use crate::scavenge::Parser;
+use yaml_peg::repr::Repr;
+use yaml_peg::{Node as YamlNode, Yaml};
+
+// `if: true` is a yaml bool but still an expression
+pub struct ConditionParser;
+
+impl<R> Parser<R, String> for ConditionParser
+where
+    R: Repr,
+{
+    fn parse_node(&mut self, root: &YamlNode<R>, _: Source) -> PossumNodeKind<String>
+    where
+        R: Repr,
+    {
+        use PossumNodeKind::*;
+        match root.yaml() {
+            Yaml::Str(s) => Value(s.clone()),
+            Yaml::Bool(b) => Value(b.to_string()),
+            Yaml::Int(n) | Yaml::Float(n) => Value(n.clone()),
+            u => Invalid(
+                ExpectedYaml::AnyOf(vec![YamlKind::Str, YamlKind::Bool])
+                    .but_found(u)
+                    .to_string(),
+            ),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::document::{Annotations, AsDocumentPointer};
+use crate::scavenge::parsers::{Builder, ObjectParser, StringParser};
+use crate::scavenge::{Parser, UnexpectedKey};
+use crate::scavenge::yaml::Source;
+use crate::workflow::{Defaults, RunDefaults};
+use yaml_peg::repr::Repr;
+use yaml_peg::Node as YamlNode;
+
+#[derive(Default)]
+pub struct DefaultsBuilder {
+    defaults: Defaults,
+}
+
+impl Into<Defaults> for DefaultsBuilder {
+    fn into(self) -> Defaults {
+        self.defaults
+    }
+}
+
+impl Builder<Defaults> for DefaultsBuilder {
+    fn build<'a, P, R>(
+        &mut self,
+        key: &str,
+        value: &YamlNode<R>,
+        pointer: &P,
+        source: Source,
+        annotations: &'a mut Annotations,
+    ) where
+        P: AsDocumentPointer + 'a,
+        R: Repr,
+    {
+        match key {
+            "run" => {
+                self.defaults.run = Some(
+                    ObjectParser::new(RunDefaultsBuilder::default, annotations)
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
+                );
+            }
+            s => annotations.add(UnexpectedKey::from(s).at(pointer)),
+        }
+    }
+}
+
+#[derive(Default)]
+struct RunDefaultsBuilder {
+    run: RunDefaults,
+}
+
+impl Into<RunDefaults> for RunDefaultsBuilder {
+    fn into(self) -> RunDefaults {
+        self.run
+    }
+}
+
+impl Builder<RunDefaults> for RunDefaultsBuilder {
+    fn build<'a, P, R>(
+        &mut self,
+        key: &str,
+        value: &YamlNode<R>,
+        pointer: &P,
+        source: Source,
+        annotations: &'a mut Annotations,
+    ) where
+        P: AsDocumentPointer + 'a,
+        R: Repr,
+    {
+        match key {
+            "shell" => {
+                self.run.shell = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "working-directory" => {
+                self.run.working_directory = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            s => annotations.add(UnexpectedKey::from(s).at(pointer)),
+        }
+    }
+}
@@ -2,13 +2,17 @@ use super::input::InputParser;
 use crate::document::Annotations;
 use crate::document::AsDocumentPointer;
 use crate::scavenge::ast::PossumNodeKind;
+use crate::scavenge::extraction::ExpectedYaml;
 use crate::scavenge::parsers::BoolParser;
 use crate::scavenge::parsers::Builder;
 use crate::scavenge::parsers::MapParser;
 use crate::scavenge::parsers::ObjectParser;
+use crate::scavenge::parsers::OrableParser;
+use crate::scavenge::parsers::PluralizableParser;
 use crate::scavenge::parsers::SeqParser;
 use crate::scavenge::parsers::StringParser;
 use crate::scavenge::parsers::TransformableParser;
+use crate::scavenge::yaml::{Source, YamlKind};
 use crate::scavenge::{Parser, UnexpectedKey};
 use crate::workflow::on::{self, Globbed};
 use yaml_peg::repr::Repr;
@@ -28,11 +32,11 @@ impl<'a, R> Parser<R, on::Event> for EventParser<'a>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<on::Event>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<on::Event>
     where
         R: Repr,
     {
-        ObjectParser::new(EventBuilder::default, &mut self.0).parse_node(root)
+        ObjectParser::new(EventBuilder::default, &mut self.0).parse_node(root, source)
     }
 }
 
@@ -53,6 +57,7 @@ impl Builder<on::Event> for EventBuilder {
         key: &str,
         value: &YamlNode<R>,
         _: &P,
+        source: Source,
         annotations: &'a mut Annotations,
     ) where
         P: AsDocumentPointer + 'a,
@@ -60,47 +65,61 @@ impl Builder<on::Event> for EventBuilder {
     {
         let mut glob_parser = SeqParser::new(StringParser.to(Globbed::new));
         match key {
+            "types" => {
+                self.event.types = Some(
+                    StringParser
+                        .pluralize()
+                        .or(SeqParser::new(StringParser), |unexpected| {
+                            PossumNodeKind::invalid(
+                                ExpectedYaml::AnyOf(vec![YamlKind::Str, YamlKind::Seq])
+                                    .but_found(unexpected),
+                            )
+                        })
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
+                );
+            }
             "branches" => {
-                self.event.branches = Some(glob_parser.parse_node(value).at(value));
+                self.event.branches = Some(glob_parser.parse_node(value, source).at(&source.span(value)));
             }
 
             "branches-ignore" => {
-                self.event.branches_ignore = Some(glob_parser.parse_node(value).at(value));
+                self.event.branches_ignore = Some(glob_parser.parse_node(value, source).at(&source.span(value)));
             }
             "paths" => {
-                self.event.paths = Some(glob_parser.parse_node(value).at(value));
+                self.event.paths = Some(glob_parser.parse_node(value, source).at(&source.span(value)));
             }
             "paths-ignore" => {
-                self.event.paths_ignore = Some(glob_parser.parse_node(value).at(value));
+                self.event.paths_ignore = Some(glob_parser.parse_node(value, source).at(&source.span(value)));
             }
             "tags" => {
-                self.event.tags = Some(glob_parser.parse_node(value).at(value));
+                self.event.tags = Some(glob_parser.parse_node(value, source).at(&source.span(value)));
             }
             "tags-ignore" => {
-                self.event.tags_ignore = Some(glob_parser.parse_node(value).at(value));
+                self.event.tags_ignore = Some(glob_parser.parse_node(value, source).at(&source.span(value)));
             }
             "inputs" => {
                 self.event.inputs = Some(
                     MapParser::new(StringParser, InputParser::new(annotations))
-                        .parse_node(value)
-                        .at(value),
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
                 );
             }
             "outputs" => {
                 self.event.outputs = Some(
                     MapParser::new(StringParser, WorkflowOutputParser(annotations))
-                        .parse_node(value)
-                        .at(value),
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
                 );
             }
             "secrets" => {
                 self.event.secrets = Some(
                     MapParser::new(StringParser, InheritedSecretParser(annotations))
-                        .parse_node(value)
-                        .at(value),
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
                 );
             }
-            s => annotations.add(UnexpectedKey::from(s).at(value)),
+            s => annotations.add(UnexpectedKey::from(s).at(&source.span(value))),
         }
     }
 }
@@ -109,11 +128,11 @@ impl<'a, R> Parser<R, on::InheritedSecret> for InheritedSecretParser<'a>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<on::InheritedSecret>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<on::InheritedSecret>
     where
         R: Repr,
     {
-        ObjectParser::new(InheritedSecretBuilder::default, self.0).parse_node(root)
+        ObjectParser::new(InheritedSecretBuilder::default, self.0).parse_node(root, source)
     }
 }
 
@@ -121,11 +140,11 @@ impl<'a, R> Parser<R, on::WorkflowOutput> for WorkflowOutputParser<'a>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<on::WorkflowOutput>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<on::WorkflowOutput>
     where
         R: Repr,
     {
-        ObjectParser::new(WorkflowOutputBuilder::default, self.0).parse_node(root)
+        ObjectParser::new(WorkflowOutputBuilder::default, self.0).parse_node(root, source)
     }
 }
 
@@ -146,6 +165,7 @@ impl Builder<on::InheritedSecret> for InheritedSecretBuilder {
         key: &str,
         value: &YamlNode<R>,
         pointer: &P,
+        source: Source,
         annotations: &'a mut Annotations,
     ) where
         P: AsDocumentPointer + 'a,
@@ -153,10 +173,10 @@ impl Builder<on::InheritedSecret> for InheritedSecretBuilder {
     {
         match key {
             "description" => {
-                self.secret.description = Some(StringParser.parse_node(value).at(value));
+                self.secret.description = Some(StringParser.parse_node(value, source).at(&source.span(value)));
             }
             "required" => {
-                self.secret.required = Some(BoolParser.parse_node(value).at(value));
+                self.secret.required = Some(BoolParser.parse_node(value, source).at(&source.span(value)));
             }
             unexpected @ _ => annotations.add(UnexpectedKey::from(unexpected).at(pointer)),
         }
@@ -179,6 +199,7 @@ impl Builder<on::WorkflowOutput> for WorkflowOutputBuilder {
         key: &str,
         value: &YamlNode<R>,
         pointer: &P,
+        source: Source,
         annotations: &'a mut Annotations,
     ) where
         P: AsDocumentPointer + 'a,
@@ -186,10 +207,10 @@ impl Builder<on::WorkflowOutput> for WorkflowOutputBuilder {
     {
         match key {
             "description" => {
-                self.output.description = Some(StringParser.parse_node(value).at(value));
+                self.output.description = Some(StringParser.parse_node(value, source).at(&source.span(value)));
             }
             "value" => {
-                self.output.value = Some(StringParser.parse_node(value).at(value));
+                self.output.value = Some(StringParser.parse_node(value, source).at(&source.span(value)));
             }
             unexpected @ _ => {
                 annotations.add(UnexpectedKey::from(unexpected).at(pointer));
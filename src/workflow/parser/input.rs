@@ -5,14 +5,14 @@ use crate::scavenge::parsers::{
     BoolParser, Builder, FlatMappableParser, NumberParser, ObjectParser, OrableParser, SeqParser,
     StringParser, TransformableParser,
 };
-use crate::scavenge::yaml::YamlKind;
+use crate::scavenge::yaml::{Source, YamlKind};
 use crate::scavenge::{Parser, UnexpectedKey};
 use crate::workflow::on::{self, BadInputType};
 use yaml_peg::repr::Repr;
 use yaml_peg::Node as YamlNode;
 
 pub struct InputParser<'a>(&'a mut Annotations);
-struct InputDefaultParser;
+pub struct InputDefaultParser;
 struct InputTypeParser;
 
 impl<'a> InputParser<'a> {
@@ -25,11 +25,11 @@ impl<'a, R> Parser<R, on::WorkflowInput> for InputParser<'a>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<on::WorkflowInput>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<on::WorkflowInput>
     where
         R: Repr,
     {
-        ObjectParser::new(InputBuilder::default, &mut self.0).parse_node(root)
+        ObjectParser::new(InputBuilder::default, &mut self.0).parse_node(root, source)
     }
 }
 
@@ -37,7 +37,7 @@ impl<R> Parser<R, on::WorkflowInputType> for InputTypeParser
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<on::WorkflowInputType>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<on::WorkflowInputType>
     where
         R: Repr,
     {
@@ -46,7 +46,7 @@ where
                 Ok(input_type) => PossumNodeKind::Value(input_type),
                 Err(_) => PossumNodeKind::Invalid(BadInputType::Unknown(s).to_string()),
             })
-            .parse_node(root)
+            .parse_node(root, source)
     }
 }
 
@@ -54,7 +54,7 @@ impl<R> Parser<R, on::WorkflowInputDefault> for InputDefaultParser
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<on::WorkflowInputDefault>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<on::WorkflowInputDefault>
     where
         R: Repr,
     {
@@ -72,7 +72,7 @@ where
                         .but_found(r),
                 )
             })
-            .parse_node(root)
+            .parse_node(root, source)
     }
 }
 
@@ -93,6 +93,7 @@ impl Builder<on::WorkflowInput> for InputBuilder {
         key: &str,
         value: &YamlNode<R>,
         pointer: &P,
+        source: Source,
         annotations: &'a mut crate::document::Annotations,
     ) where
         P: crate::document::AsDocumentPointer + 'a,
@@ -100,21 +101,55 @@ impl Builder<on::WorkflowInput> for InputBuilder {
     {
         match key.to_lowercase().as_str() {
             "description" => {
-                self.input.description = Some(StringParser.parse_node(value).at(value));
+                self.input.description = Some(StringParser.parse_node(value, source).at(&source.span(value)));
             }
             "default" => {
-                self.input.default = Some(InputDefaultParser.parse_node(value).at(value));
+                self.input.default = Some(InputDefaultParser.parse_node(value, source).at(&source.span(value)));
             }
             "required" => {
-                self.input.required = Some(BoolParser.parse_node(value).at(value));
+                self.input.required = Some(BoolParser.parse_node(value, source).at(&source.span(value)));
             }
             "type" => {
-                self.input.input_type = Some(InputTypeParser.parse_node(value).at(value));
+                self.input.input_type = Some(InputTypeParser.parse_node(value, source).at(&source.span(value)));
             }
-            "choices" => {
-                self.input.choices = Some(SeqParser::new(StringParser).parse_node(value).at(value));
+            "options" => {
+                self.input.choices = Some(SeqParser::new(StringParser).parse_node(value, source).at(&source.span(value)));
             }
             s @ _ => annotations.add(UnexpectedKey::from(s).at(pointer)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::workflow::on::WorkflowInputType;
+    use crate::workflow::parser::test_workflow;
+
+    #[test]
+    fn choice_inputs_list_their_options() {
+        let wf = test_workflow(
+            "
+on:
+  workflow_dispatch:
+    inputs:
+      level:
+        type: choice
+        options: [info, warning]
+",
+        );
+        let on = wf.on.as_ref().and_then(|on| on.value()).unwrap();
+        let (_, dispatch) = on.events().next().unwrap();
+        let inputs = dispatch.value().and_then(|e| e.inputs.as_ref()).and_then(|i| i.value()).unwrap();
+        let (_, level) = inputs.iter().next().unwrap();
+        let level = level.value().unwrap();
+
+        assert_eq!(level.input_type.as_ref().and_then(|t| t.value()), Some(&WorkflowInputType::Choice));
+        let options: Vec<_> = level
+            .choices
+            .as_ref()
+            .and_then(|c| c.value())
+            .map(|c| c.iter().filter_map(|o| o.value().cloned()).collect())
+            .unwrap_or_default();
+        assert_eq!(options, ["info", "warning"]);
+    }
+}
@@ -1,5 +1,8 @@
 use super::concurrency::ConcurrencyParser;
+use super::condition::ConditionParser;
+use super::defaults::DefaultsBuilder;
 use super::permissions::PermissionParser;
+use super::runs_on::RunsOnParser;
 use crate::document::{Annotations, AsDocumentPointer};
 use crate::scavenge::ast::PossumNodeKind;
 use crate::scavenge::extraction::{ExpectedYaml, Extract};
@@ -7,13 +10,14 @@ use crate::scavenge::parsers::{
     BoolParser, Builder, NumberParser, ObjectParser, OrableParser, PluralizableParser, SeqParser,
     StringMapParser, StringParser, TransformableParser,
 };
-use crate::scavenge::yaml::YamlKind;
+use crate::scavenge::yaml::{Source, YamlKind};
 use crate::scavenge::{Parser, UnexpectedKey};
 use crate::workflow::job::{self, Job};
 use crate::workflow::parser::step::StepParser;
 use crate::workflow::parser::strategy::StrategyBuilder;
 use yaml_peg::repr::Repr;
 use yaml_peg::Node as YamlNode;
+use yaml_peg::Yaml;
 
 pub struct JobParser<'a> {
     annotations: &'a mut Annotations,
@@ -23,12 +27,12 @@ impl<'a, R> Parser<R, Job> for JobParser<'a>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &yaml_peg::Node<R>) -> PossumNodeKind<Job>
+    fn parse_node(&mut self, root: &yaml_peg::Node<R>, source: Source) -> PossumNodeKind<Job>
     where
         R: Repr,
     {
         ObjectParser::new(JobBuilder::default, &mut self.annotations)
-            .parse_node(root)
+            .parse_node(root, source)
     }
 }
 
@@ -44,11 +48,11 @@ impl<R> Parser<R, job::Environment> for EnvStringParser
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<job::Environment>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<job::Environment>
     where
         R: Repr,
     {
-        StringParser.to(job::Environment::Bare).parse_node(root)
+        StringParser.to(job::Environment::Bare).parse_node(root, source)
     }
 }
 
@@ -64,7 +68,7 @@ impl<'a, R> Parser<R, job::Environment> for EnvMapParser<'a>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<job::Environment>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<job::Environment>
     where
         R: Repr,
     {
@@ -77,11 +81,11 @@ where
 
                 for (key, value) in m.iter() {
                     match key.extract_str() {
-                        Err(u) => self.0.add(u.at(key)),
+                        Err(u) => self.0.add(u.at(&source.span(key))),
                         Ok(s) => match s.to_lowercase().as_str() {
-                            "name" => env_name = Some(Value(s.to_owned()).at(value)),
-                            "url" => env_url = Some(Value(s.to_owned()).at(value)),
-                            _ => self.0.add(UnexpectedKey::from(s).at(value)),
+                            "name" => env_name = Some(Value(s.to_owned()).at(&source.span(value))),
+                            "url" => env_url = Some(Value(s.to_owned()).at(&source.span(value))),
+                            _ => self.0.add(UnexpectedKey::from(s).at(&source.span(value))),
                         },
                     }
                 }
@@ -95,6 +99,32 @@ where
     }
 }
 
+struct JobSecretsParser;
+
+impl<R> Parser<R, job::JobSecrets> for JobSecretsParser
+where
+    R: Repr,
+{
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<job::JobSecrets>
+    where
+        R: Repr,
+    {
+        use PossumNodeKind::*;
+        match root.yaml() {
+            Yaml::Str(s) if s == "inherit" => Value(job::JobSecrets::Inherit),
+            Yaml::Str(s) => Invalid(format!("Expected inherit or a map of secrets, but found {s}")),
+            Yaml::Map(_) => StringMapParser::new()
+                .parse_node(root, source)
+                .map(job::JobSecrets::Secrets),
+            u @ _ => Invalid(
+                ExpectedYaml::AnyOf(vec![YamlKind::Str, YamlKind::Map])
+                    .but_found(u)
+                    .to_string(),
+            ),
+        }
+    }
+}
+
 #[derive(Default)]
 struct JobBuilder {
     job: job::Job,
@@ -112,6 +142,7 @@ impl Builder<job::Job> for JobBuilder {
         key: &str,
         value: &YamlNode<R>,
         p: &P,
+        source: Source,
         annotations: &'a mut Annotations,
     ) where
         P: AsDocumentPointer + 'a,
@@ -122,28 +153,28 @@ impl Builder<job::Job> for JobBuilder {
             "permissions" => {
                 self.job.permissions = Some(
                     PermissionParser::new(annotations)
-                        .parse_node(value)
-                        .at(value),
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
                 );
             }
             "env" => {
-                self.job.env = Some(StringMapParser::new().parse_node(value).at(value));
+                self.job.env = Some(StringMapParser::new().parse_node(value, source).at(&source.span(value)));
             }
             "with" => {
-                self.job.with = Some(StringMapParser::new().parse_node(value).at(value));
+                self.job.with = Some(StringMapParser::new().parse_node(value, source).at(&source.span(value)));
             }
             "concurrency" => {
                 self.job.concurrency = Some(
                     ConcurrencyParser::new(annotations)
-                        .parse_node(value)
-                        .at(value),
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
                 );
             }
             "steps" => {
                 self.job.steps = Some(
                     SeqParser::new(StepParser::new(annotations))
-                        .parse_node(value)
-                        .at(value),
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
                 );
             }
             "environment" => {
@@ -155,12 +186,12 @@ impl Builder<job::Job> for JobBuilder {
                                     .but_found(r),
                             )
                         })
-                        .parse_node(value)
-                        .at(value),
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
                 );
             }
             "name" => {
-                self.job.name = Some(StringParser.parse_node(value).at(value));
+                self.job.name = Some(StringParser.parse_node(value, source).at(&source.span(value)));
             }
             "needs" => {
                 self.job.needs = Some(
@@ -173,42 +204,36 @@ impl Builder<job::Job> for JobBuilder {
                                     .to_string(),
                             )
                         })
-                        .parse_node(value)
-                        .at(value),
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
                 )
             }
             "if" => {
-                self.job.cond = Some(StringParser.parse_node(value).at(value));
+                self.job.cond = Some(ConditionParser.parse_node(value, source).at(&source.span(value)));
             }
             "runs-on" => {
-                self.job.runs_on = Some(
-                    StringParser
-                        .pluralize()
-                        .or(SeqParser::new(StringParser), |unexpected| {
-                            Invalid(
-                                ExpectedYaml::AnyOf(vec![YamlKind::Str, YamlKind::Seq])
-                                    .but_found(unexpected)
-                                    .to_string(),
-                            )
-                        })
-                        .parse_node(value)
-                        .at(value),
-                );
+                self.job.runs_on = Some(RunsOnParser::new(annotations).parse_node(value, source).at(&source.span(value)));
             }
             "outputs" => {
-                self.job.outputs = Some(StringMapParser::new().parse_node(value).at(value));
+                self.job.outputs = Some(StringMapParser::new().parse_node(value, source).at(&source.span(value)));
             }
             "timeout-minutes" => {
-                self.job.timeout_minutes = Some(NumberParser.parse_node(value).at(value));
+                self.job.timeout_minutes = Some(NumberParser.parse_node(value, source).at(&source.span(value)));
             }
             "continue-on-error" => {
-                self.job.continue_on_error = Some(BoolParser.parse_node(value).at(value));
+                self.job.continue_on_error = Some(BoolParser.parse_node(value, source).at(&source.span(value)));
             }
             "uses" => {
-                self.job.uses = Some(StringParser.parse_node(value).at(value));
+                self.job.uses = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+            }
+            "secrets" => {
+                self.job.secrets = Some(JobSecretsParser.parse_node(value, source).at(&source.span(value)));
             }
             "strategy" => {
-                self.job.strategy = Some(ObjectParser::new(StrategyBuilder::default, annotations).parse_node(value).at(value));
+                self.job.strategy = Some(ObjectParser::new(StrategyBuilder::default, annotations).parse_node(value, source).at(&source.span(value)));
+            }
+            "defaults" => {
+                self.job.defaults = Some(ObjectParser::new(DefaultsBuilder::default, annotations).parse_node(value, source).at(&source.span(value)));
             }
             s => annotations.add(UnexpectedKey::from(s).at(p)),
         }
@@ -1,11 +1,16 @@
 mod concurrency;
+mod condition;
+mod defaults;
 mod event;
 mod input;
 mod job;
 mod on;
 mod permissions;
+mod runs_on;
 mod step;
 mod strategy;
+pub use input::InputDefaultParser;
+pub use step::StepParser;
 use yaml_peg::repr::Repr;
 use yaml_peg::Node as YamlNode;
 
@@ -14,6 +19,7 @@ use crate::document::Annotations;
 use crate::scavenge::ast::PossumNodeKind;
 use crate::scavenge::parsers::{Builder, MapParser, ObjectParser, StringParser, StringMapParser};
 use crate::scavenge::{Parser, UnexpectedKey};
+use crate::scavenge::yaml::Source;
 
 pub struct WorkflowParser<'a> {
     annotations: &'a mut Annotations,
@@ -23,8 +29,8 @@ impl<'a, R> Parser<R, Workflow> for WorkflowParser<'a>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<Workflow> {
-        ObjectParser::new(WorkflowBuilder::new, self.annotations).parse_node(root)
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<Workflow> {
+        ObjectParser::new(WorkflowBuilder::new, self.annotations).parse_node(root, source)
     }
 }
 
@@ -50,6 +56,7 @@ impl Builder<Workflow> for WorkflowBuilder {
         key: &str,
         value: &YamlNode<R>,
         _: &P,
+        source: Source,
         annotations: &'a mut Annotations,
     ) where
         P: crate::document::AsDocumentPointer + 'a,
@@ -57,42 +64,49 @@ impl Builder<Workflow> for WorkflowBuilder {
     {
         match key {
             "name" => {
-                self.workflow.name = Some(StringParser.parse_node(value).at(value));
+                self.workflow.name = Some(StringParser.parse_node(value, source).at(&source.span(value)));
             }
-            "run_name" => {
-                self.workflow.run_name = Some(StringParser.parse_node(value).at(value));
+            "run-name" => {
+                self.workflow.run_name = Some(StringParser.parse_node(value, source).at(&source.span(value)));
             }
             "on" => {
-                let on = on::OnParser::new(annotations).parse_node(value);
-                self.workflow.on = Some(on.at(value));
+                let on = on::OnParser::new(annotations).parse_node(value, source);
+                self.workflow.on = Some(on.at(&source.span(value)));
             }
             "jobs" => {
                 self.workflow.jobs = Some(
                     MapParser::new(StringParser, job::JobParser::new(annotations))
-                        .parse_node(value)
-                        .at(value),
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
                 );
             }
             "permissions" => {
                 self.workflow.permissions = Some(
                     permissions::PermissionParser::new(annotations)
-                        .parse_node(value)
-                        .at(value),
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
                 );
             }
             "concurrency" => {
                 self.workflow.concurrency = Some(
                     concurrency::ConcurrencyParser::new(annotations)
-                        .parse_node(value)
-                        .at(value),
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
                 );
             }
             "env" => {
                 self.workflow.env = Some(
-                    StringMapParser::new().parse_node(value).at(value)
+                    StringMapParser::new().parse_node(value, source).at(&source.span(value))
                 );
             }
-            s @ _ => annotations.add(UnexpectedKey::from(s).at(value)),
+            "defaults" => {
+                self.workflow.defaults = Some(
+                    ObjectParser::new(defaults::DefaultsBuilder::default, annotations)
+                        .parse_node(value, source)
+                        .at(&source.span(value)),
+                );
+            }
+            s @ _ => annotations.add(UnexpectedKey::from(s).at(&source.span(value))),
         }
     }
 }
@@ -102,3 +116,103 @@ impl<'a> WorkflowParser<'a> {
         WorkflowParser { annotations: a }
     }
 }
+
+#[cfg(test)]
+pub(crate) fn test_workflow(raw: &str) -> Workflow {
+    let (root, source) = crate::scavenge::parser::test_document(raw);
+    match WorkflowParser::new(&mut Annotations::new()).parse_node(&root, source) {
+        PossumNodeKind::Value(wf) => wf,
+        _ => panic!("not parsed as a workflow"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_workflow;
+    use crate::scavenge::ast::{PossumNode, PossumNodeKind, PossumSeq};
+    use crate::workflow::job::RunsOn;
+    use crate::workflow::Workflow;
+
+    const JOBS: &str = "
+on:
+  pull_request:
+    types: opened
+  issues:
+    types: [opened, closed]
+  push:
+    types: {opened: true}
+jobs:
+  one:
+    runs-on: ubuntu-latest
+    needs: build
+  many:
+    runs-on: [self-hosted, linux]
+    needs: [build, test]
+  neither:
+    runs-on:
+      labels: {linux: true}
+    needs: {build: true}
+";
+
+    fn strings(node: Option<&PossumNode<PossumSeq<String>>>) -> Result<Vec<&str>, String> {
+        match node.map(PossumNode::kind) {
+            Some(PossumNodeKind::Value(seq)) => Ok(seq.iter().filter_map(|s| s.value()).map(String::as_str).collect()),
+            Some(PossumNodeKind::Invalid(msg)) => Err(msg.clone()),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    fn job<'a>(wf: &'a Workflow, name: &str) -> &'a crate::workflow::job::Job {
+        wf.jobs()
+            .find(|(n, _)| n.value().map(String::as_str) == Some(name))
+            .and_then(|(_, j)| j.value())
+            .unwrap()
+    }
+
+    #[test]
+    fn run_name() {
+        let run_name = |raw| test_workflow(raw).run_name.and_then(|n| n.value().cloned());
+        assert_eq!(run_name("run-name: Deploy ${{ inputs.env }}\n"), Some("Deploy ${{ inputs.env }}".to_owned()));
+        assert_eq!(run_name("run_name: Deploy\n"), None);
+    }
+
+    // a string or a list of strings, and anything else is invalid rather than
+    // a list holding one invalid item
+    #[test]
+    fn needs() {
+        let wf = test_workflow(JOBS);
+        assert_eq!(strings(job(&wf, "one").needs.as_ref()), Ok(vec!["build"]));
+        assert_eq!(strings(job(&wf, "many").needs.as_ref()), Ok(vec!["build", "test"]));
+        assert!(strings(job(&wf, "neither").needs.as_ref()).is_err());
+    }
+
+    #[test]
+    fn runs_on_labels() {
+        let wf = test_workflow(JOBS);
+        let labels = |name| match job(&wf, name).runs_on.as_ref().and_then(|r| r.value()) {
+            Some(RunsOn::Labels(seq)) => seq.iter().filter_map(|s| s.value().cloned()).collect::<Vec<_>>(),
+            other => panic!("unexpected {other:?}"),
+        };
+        assert_eq!(labels("one"), ["ubuntu-latest"]);
+        assert_eq!(labels("many"), ["self-hosted", "linux"]);
+
+        match job(&wf, "neither").runs_on.as_ref().and_then(|r| r.value()) {
+            Some(RunsOn::Group { labels, .. }) => assert!(strings(labels.as_ref()).is_err()),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn event_types() {
+        let wf = test_workflow(JOBS);
+        let on = wf.on.as_ref().and_then(|on| on.value()).unwrap();
+        let types: Vec<_> = on
+            .events()
+            .map(|(_, event)| strings(event.value().and_then(|e| e.types.as_ref())))
+            .collect();
+
+        assert_eq!(types[0], Ok(vec!["opened"]));
+        assert_eq!(types[1], Ok(vec!["opened", "closed"]));
+        assert!(types[2].is_err());
+    }
+}
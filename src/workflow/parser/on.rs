@@ -5,7 +5,7 @@ use crate::scavenge::extraction::ExpectedYaml;
 use crate::scavenge::parsers::{
     FlatMappableParser, MapParser, OrableParser, SeqParser, StringParser, TransformableParser,
 };
-use crate::scavenge::yaml::YamlKind;
+use crate::scavenge::yaml::{Source, YamlKind};
 use crate::scavenge::Parser;
 use crate::workflow::on::{self, BadEvent, EventKind};
 use yaml_peg::repr::Repr;
@@ -28,7 +28,7 @@ impl<R> Parser<R, on::EventKind> for EventKindParser
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<on::EventKind>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<on::EventKind>
     where
         R: Repr,
     {
@@ -37,7 +37,7 @@ where
                 Ok(ek) => PossumNodeKind::Value(ek),
                 Err(_) => PossumNodeKind::Invalid(BadEvent::Unknown(s).to_string()),
             })
-            .parse_node(root)
+            .parse_node(root, source)
     }
 }
 
@@ -45,11 +45,11 @@ impl<R> Parser<R, on::Trigger> for OnStringParser
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<on::Trigger>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<on::Trigger>
     where
         R: Repr,
     {
-        PossumNodeKind::Value(EventKindParser.parse_node(root).at(root).into())
+        PossumNodeKind::Value(EventKindParser.parse_node(root, source).at(&source.span(root)).into())
     }
 }
 
@@ -57,13 +57,13 @@ impl<R> Parser<R, on::Trigger> for OnArrayParser
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<on::Trigger>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<on::Trigger>
     where
         R: Repr,
     {
         SeqParser::new(EventKindParser)
             .to(Into::<on::Trigger>::into)
-            .parse_node(root)
+            .parse_node(root, source)
     }
 }
 
@@ -71,12 +71,12 @@ impl<'a, R> Parser<R, on::Trigger> for OnMapParser<'a>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<on::Trigger>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<on::Trigger>
     where
         R: Repr,
     {
         MapParser::new(EventKindParser, EventParser::new(self.0))
-            .parse_node(root)
+            .parse_node(root, source)
             .map(Into::<on::Trigger>::into)
     }
 }
@@ -85,7 +85,7 @@ impl<'a, R> Parser<R, on::Trigger> for OnParser<'a>
 where
     R: Repr + 'a,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<on::Trigger>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<on::Trigger>
     where
         R: Repr,
     {
@@ -104,6 +104,6 @@ where
                         .to_string(),
                 )
             })
-            .parse_node(root)
+            .parse_node(root, source)
     }
 }
@@ -1,7 +1,7 @@
 use crate::document::{Annotation, Annotations};
 use crate::scavenge::ast::{PossumMap, PossumNodeKind};
 use crate::scavenge::extraction::{ExpectedYaml, Extract};
-use crate::scavenge::yaml::YamlKind;
+use crate::scavenge::yaml::{Source, YamlKind};
 use crate::scavenge::Parser;
 use crate::workflow::{Grant, Permission};
 use yaml_peg::repr::Repr;
@@ -38,7 +38,7 @@ impl<'a, R> Parser<R, Permission> for PermissionParser<'a>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &yaml_peg::Node<R>) -> PossumNodeKind<Permission>
+    fn parse_node(&mut self, root: &yaml_peg::Node<R>, source: Source) -> PossumNodeKind<Permission>
     where
         R: Repr,
     {
@@ -54,12 +54,12 @@ where
 
                 for (key, value) in each.iter() {
                     match key.extract_str() {
-                        Err(u) => self.annotate(u.at(key)),
+                        Err(u) => self.annotate(u.at(&source.span(key))),
                         Ok(k) => {
                             let grant: PossumNodeKind<Grant> = value
                                 .extract_str()
                                 .map_or_else(|u| Invalid(u.to_string()), parse_individual_grant);
-                            perms.insert(Value(k.to_owned()).at(key), grant.at(value));
+                            perms.insert(Value(k.to_owned()).at(&source.span(key)), grant.at(&source.span(value)));
                         }
                     }
                 }
@@ -0,0 +1,78 @@
+use crate::document::Annotations;
+use crate::scavenge::ast::{PossumNodeKind, PossumSeq};
+use crate::scavenge::extraction::{ExpectedYaml, Extract};
+use crate::scavenge::parsers::{OrableParser, PluralizableParser, SeqParser, StringParser};
+use crate::scavenge::yaml::{Source, YamlKind};
+use crate::scavenge::{Parser, UnexpectedKey};
+use crate::workflow::job::RunsOn;
+use yaml_peg::repr::Repr;
+use yaml_peg::{Node as YamlNode, Yaml};
+
+pub struct RunsOnParser<'a>(&'a mut Annotations);
+
+impl<'a> RunsOnParser<'a> {
+    pub fn new(annotations: &'a mut Annotations) -> RunsOnParser<'a> {
+        RunsOnParser(annotations)
+    }
+}
+
+// a label or a list of them
+fn parse_labels<R: Repr>(root: &YamlNode<R>, source: Source) -> PossumNodeKind<PossumSeq<String>> {
+    StringParser
+        .pluralize()
+        .or(SeqParser::new(StringParser), |unexpected| {
+            PossumNodeKind::invalid(
+                ExpectedYaml::AnyOf(vec![YamlKind::Str, YamlKind::Seq]).but_found(unexpected),
+            )
+        })
+        .parse_node(root, source)
+}
+
+impl<'a, R> Parser<R, RunsOn> for RunsOnParser<'a>
+where
+    R: Repr,
+{
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<RunsOn>
+    where
+        R: Repr,
+    {
+        use PossumNodeKind::*;
+        match root.yaml() {
+            // the whole of runs-on can come from an expression, eg. a matrix
+            // of operating systems or a label computed by an earlier job
+            Yaml::Str(s) if is_expression(s) => Value(RunsOn::Expression(s.to_owned())),
+            Yaml::Str(_) | Yaml::Seq(_) => parse_labels(root, source).map(RunsOn::Labels),
+            Yaml::Map(m) => {
+                let mut group = None;
+                let mut labels = None;
+
+                for (key, value) in m.iter() {
+                    match key.extract_str() {
+                        Err(u) => self.0.add(u.at(&source.span(key))),
+                        Ok(k) => match k {
+                            "group" => {
+                                let g: PossumNodeKind<String> =
+                                    value.extract_str().map(ToOwned::to_owned).into();
+                                group = Some(g.at(&source.span(value)));
+                            }
+                            "labels" => labels = Some(parse_labels(value, source).at(&source.span(value))),
+                            u @ _ => self.0.add(UnexpectedKey::from(u).at(&source.span(key))),
+                        },
+                    }
+                }
+
+                Value(RunsOn::Group { group, labels })
+            }
+            u @ _ => Invalid(
+                ExpectedYaml::AnyOf(vec![YamlKind::Str, YamlKind::Seq, YamlKind::Map])
+                    .but_found(u)
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+fn is_expression(s: &str) -> bool {
+    let s = s.trim();
+    s.starts_with("${{") && s.ends_with("}}")
+}
@@ -7,12 +7,15 @@ use crate::{
         ast::PossumNodeKind,
         extraction::Extract,
         parser::Parser,
-        parsers::{StringMapParser, StringParser},
+        parsers::{BoolParser, NumberParser, StringMapParser, StringParser},
+        yaml::Source,
         Fallible, UnexpectedKey,
     },
     workflow::job,
 };
 
+use super::condition::ConditionParser;
+
 struct StepBuilder {
     step: job::Step,
 }
@@ -24,54 +27,74 @@ impl StepBuilder {
         }
     }
 
-    fn build<'a, R>(&mut self, key: &'a YamlNode<R>, value: &'a YamlNode<R>) -> Fallible<Annotation>
+    fn build<'a, R>(
+        &mut self,
+        key: &'a YamlNode<R>,
+        value: &'a YamlNode<R>,
+        source: Source,
+    ) -> Fallible<Annotation>
     where
         R: Repr + 'a,
     {
         match key.extract_str() {
-            Err(u) => Fallible::Failure(u.at(key)),
+            Err(u) => Fallible::Failure(u.at(&source.span(key))),
             Ok(s) => match s.to_lowercase().as_str() {
                 "id" => {
-                    self.step.id = Some(StringParser.parse_node(value).at(value));
+                    self.step.id = Some(StringParser.parse_node(value, source).at(&source.span(value)));
                     Fallible::Success
                 }
 
                 "if" => {
-                    self.step.cond = Some(StringParser.parse_node(value).at(value));
+                    self.step.cond = Some(ConditionParser.parse_node(value, source).at(&source.span(value)));
                     Fallible::Success
                 }
 
                 "name" => {
-                    self.step.name = Some(StringParser.parse_node(value).at(value));
+                    self.step.name = Some(StringParser.parse_node(value, source).at(&source.span(value)));
                     Fallible::Success
                 }
 
                 "uses" => {
-                    self.step.uses = Some(StringParser.parse_node(value).at(value));
+                    self.step.uses = Some(StringParser.parse_node(value, source).at(&source.span(value)));
                     Fallible::Success
                 }
 
                 "run" => {
-                    self.step.run = Some(StringParser.parse_node(value).at(value));
+                    self.step.run = Some(StringParser.parse_node(value, source).at(&source.span(value)));
                     Fallible::Success
                 }
                 "shell" => {
-                    self.step.shell = Some(StringParser.parse_node(value).at(value));
+                    self.step.shell = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+                    Fallible::Success
+                }
+
+                "working-directory" => {
+                    self.step.working_directory = Some(StringParser.parse_node(value, source).at(&source.span(value)));
+                    Fallible::Success
+                }
+
+                "continue-on-error" => {
+                    self.step.continue_on_error = Some(BoolParser.parse_node(value, source).at(&source.span(value)));
+                    Fallible::Success
+                }
+
+                "timeout-minutes" => {
+                    self.step.timeout_minutes = Some(NumberParser.parse_node(value, source).at(&source.span(value)));
                     Fallible::Success
                 }
 
                 "with" => {
-                    self.step.with = Some(StringMapParser::new().parse_node(value).at(value));
+                    self.step.with = Some(StringMapParser::new().parse_node(value, source).at(&source.span(value)));
                     Fallible::Success
                 }
 
                 "env" => {
-                    self.step.env = Some(StringMapParser::new().parse_node(value).at(value));
+                    self.step.env = Some(StringMapParser::new().parse_node(value, source).at(&source.span(value)));
 
                     Fallible::Success
                 }
 
-                k @ _ => Fallible::Failure(UnexpectedKey::from(k).at(key)),
+                k @ _ => Fallible::Failure(UnexpectedKey::from(k).at(&source.span(key))),
             },
         }
     }
@@ -91,7 +114,7 @@ impl<'a, R> Parser<R, job::Step> for StepParser<'a>
 where
     R: Repr,
 {
-    fn parse_node(&mut self, root: &YamlNode<R>) -> PossumNodeKind<job::Step>
+    fn parse_node(&mut self, root: &YamlNode<R>, source: Source) -> PossumNodeKind<job::Step>
     where
         R: Repr,
     {
@@ -102,7 +125,7 @@ where
                 let mut builder = StepBuilder::empty();
 
                 for (key, value) in m.iter() {
-                    match builder.build(key, value) {
+                    match builder.build(key, value, source) {
                         Fallible::Success => {}
                         Fallible::Failure(a) => self.annotate(a),
                     }
@@ -5,7 +5,7 @@ use crate::scavenge::parsers::{
     BoolParser, Builder, MapParser, MaybeExprParser, NumberParser, ObjectParser, OrParser,
     OrableParser, SeqParser, StringParser, TransformableParser,
 };
-use crate::scavenge::yaml::YamlKind;
+use crate::scavenge::yaml::{Source, YamlKind};
 use crate::scavenge::{Parser, UnexpectedKey};
 use crate::workflow::job::{self, MatrixInput};
 use yaml_peg::repr::Repr;
@@ -29,6 +29,7 @@ impl Builder<job::Strategy> for StrategyBuilder {
         key: &str,
         value: &YamlNode<R>,
         pointer: &P,
+        source: Source,
         annotations: &'a mut Annotations,
     ) where
         P: AsDocumentPointer + 'a,
@@ -36,10 +37,10 @@ impl Builder<job::Strategy> for StrategyBuilder {
     {
         match key.to_lowercase().as_str() {
             "fail-fast" => {
-                self.strategy.fail_fast = Some(BoolParser.parse_node(value).at(value));
+                self.strategy.fail_fast = Some(BoolParser.parse_node(value, source).at(&source.span(value)));
             }
             "max-parallel" => {
-                self.strategy.max_parallel = Some(NumberParser.parse_node(value).at(value));
+                self.strategy.max_parallel = Some(NumberParser.parse_node(value, source).at(&source.span(value)));
             }
             "matrix" => {
                 self.strategy.matrix = Some(
@@ -47,8 +48,8 @@ impl Builder<job::Strategy> for StrategyBuilder {
                         MatrixBuilder::default,
                         annotations,
                     )
-                    .parse_node(value)
-                    .at(value),
+                    .parse_node(value, source)
+                    .at(&source.span(value)),
                 );
             }
             s => annotations.add(UnexpectedKey::from(s).at(pointer)),
@@ -73,6 +74,7 @@ impl Builder<job::Matrix> for MatrixBuilder {
         key: &str,
         value: &YamlNode<R>,
         pointer: &P,
+        source: Source,
         _: &'a mut Annotations,
     ) where
         P: AsDocumentPointer + 'a,
@@ -100,7 +102,7 @@ impl Builder<job::Matrix> for MatrixBuilder {
                     )
                 });
 
-                let parsed = parser.parse_node(value).at(value);
+                let parsed = parser.parse_node(value, source).at(&source.span(value));
 
                 self.matrix.include = Some(parsed);
             }
@@ -110,9 +112,9 @@ impl Builder<job::Matrix> for MatrixBuilder {
                 let mut parser =
                     MaybeExprParser::new(parser, |_| crate::scavenge::ast::PossumNodeKind::Empty);
 
-                let parsed = parser.parse_node(value).at(value);
+                let parsed = parser.parse_node(value, source).at(&source.span(value));
 
-                self.matrix.include = Some(parsed);
+                self.matrix.exclude = Some(parsed);
             }
             s @ _ => {
                 let parser = SeqParser::new(parser);
@@ -127,7 +129,7 @@ impl Builder<job::Matrix> for MatrixBuilder {
                     )
                 });
 
-                let parsed = parser.parse_node(value).at(value);
+                let parsed = parser.parse_node(value, source).at(&source.span(value));
 
                 self.matrix
                     .entries
@@ -136,3 +138,40 @@ impl Builder<job::Matrix> for MatrixBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::workflow::parser::test_workflow;
+
+    #[test]
+    fn include_and_exclude_are_kept_apart() {
+        let wf = test_workflow(
+            "
+jobs:
+  test:
+    strategy:
+      matrix:
+        os: [ubuntu-latest, windows-latest]
+        node: [18, 20]
+        include:
+          - os: macos-latest
+            node: 20
+        exclude:
+          - os: windows-latest
+            node: 18
+          - os: ubuntu-latest
+            node: 18
+",
+        );
+        let (_, job) = wf.jobs().next().unwrap();
+        let strategy = job.value().and_then(|j| j.strategy.as_ref()).and_then(|s| s.value()).unwrap();
+        let matrix = strategy.matrix.as_ref().and_then(|m| m.value()).unwrap();
+
+        let include = matrix.include.as_ref().and_then(|i| i.value()).map(|i| i.len());
+        let exclude = matrix.exclude.as_ref().and_then(|e| e.value()).map(|e| e.len());
+        assert_eq!((include, exclude), (Some(1), Some(2)));
+
+        let keys: Vec<_> = matrix.entries.iter().filter_map(|(k, _)| k.value().cloned()).collect();
+        assert_eq!(keys, ["os", "node"]);
+    }
+}
@@ -0,0 +1,131 @@
+use super::job::Job;
+use super::{Grant, Permission, Scope, Workflow};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+
+// What GITHUB_TOKEN can actually do in a job. Job permissions replace the
+// workflow's entirely rather than merging with them, and once any scope is
+// listed every scope that isn't is none.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EffectivePermissions {
+    // nothing was set, the repository or organisation setting decides
+    Default,
+    Granted {
+        from: GrantedBy,
+        scopes: BTreeMap<Scope, Grant>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum GrantedBy {
+    Workflow,
+    Job,
+}
+
+impl EffectivePermissions {
+    pub fn of(workflow: &Workflow, job: &Job) -> EffectivePermissions {
+        let (from, permission) = match (job.permissions.as_ref(), workflow.permissions.as_ref()) {
+            (Some(p), _) => (GrantedBy::Job, p),
+            (None, Some(p)) => (GrantedBy::Workflow, p),
+            (None, None) => return EffectivePermissions::Default,
+        };
+
+        // a permissions block that didn't parse gets nothing rather than
+        // guessing at what was meant
+        let scopes = match permission.value() {
+            Some(Permission::GlobalGrant(grant)) => Scope::iter().map(|s| (s, *grant)).collect(),
+            Some(Permission::IndividualGrants(grants)) => grants
+                .iter()
+                .filter_map(|(scope, grant)| {
+                    let scope = Scope::from_str(scope.value()?).ok()?;
+                    Some((scope, *grant.value()?))
+                })
+                .collect(),
+            None => BTreeMap::new(),
+        };
+
+        EffectivePermissions::Granted { from, scopes }
+    }
+
+    // Deny for unlisted scopes, None when the default token is used
+    pub fn grant(&self, scope: Scope) -> Option<Grant> {
+        match self {
+            EffectivePermissions::Default => None,
+            EffectivePermissions::Granted { scopes, .. } => {
+                Some(scopes.get(&scope).copied().unwrap_or(Grant::Deny))
+            }
+        }
+    }
+
+    // scopes with anything more than none
+    pub fn granted(&self) -> impl Iterator<Item = (Scope, Grant)> + '_ {
+        let scopes = match self {
+            EffectivePermissions::Default => None,
+            EffectivePermissions::Granted { scopes, .. } => Some(scopes),
+        };
+
+        scopes
+            .into_iter()
+            .flat_map(|s| s.iter())
+            .filter(|(_, g)| **g != Grant::Deny)
+            .map(|(s, g)| (*s, *g))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EffectivePermissions, GrantedBy};
+    use crate::workflow::parser::test_workflow;
+    use crate::workflow::{Grant, Scope};
+    use strum::IntoEnumIterator;
+
+    // the permissions of each job, in order
+    fn effective(raw: &str) -> Vec<EffectivePermissions> {
+        let wf = test_workflow(raw);
+        wf.jobs()
+            .map(|(_, job)| EffectivePermissions::of(&wf, job.value().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn job_permissions_replace_the_workflows() {
+        let raw = "permissions:\n  contents: write\n  issues: write\njobs:\n  own:\n    permissions:\n      pull-requests: read\n  inherits: {}\n";
+        let [own, inherits] = &effective(raw)[..] else { panic!() };
+
+        assert!(matches!(own, EffectivePermissions::Granted { from: GrantedBy::Job, .. }));
+        assert_eq!(own.granted().collect::<Vec<_>>(), [(Scope::PullRequests, Grant::Read)]);
+        assert_eq!(own.grant(Scope::Contents), Some(Grant::Deny));
+
+        assert!(matches!(inherits, EffectivePermissions::Granted { from: GrantedBy::Workflow, .. }));
+        assert_eq!(
+            inherits.granted().collect::<Vec<_>>(),
+            [(Scope::Contents, Grant::Write), (Scope::Issues, Grant::Write)]
+        );
+    }
+
+    #[test]
+    fn empty_permissions_grant_nothing() {
+        let [job] = &effective("permissions: {}\njobs:\n  build: {}\n")[..] else { panic!() };
+        assert_eq!(job.granted().count(), 0);
+        assert!(Scope::iter().all(|s| job.grant(s) == Some(Grant::Deny)));
+    }
+
+    #[test]
+    fn read_all_and_write_all_cover_every_scope() {
+        let raw = "jobs:\n  reads:\n    permissions: read-all\n  writes:\n    permissions: write-all\n";
+        let [reads, writes] = &effective(raw)[..] else { panic!() };
+        assert!(Scope::iter().all(|s| reads.grant(s) == Some(Grant::Read)));
+        assert!(Scope::iter().all(|s| writes.grant(s) == Some(Grant::Write)));
+        assert_eq!(writes.granted().count(), Scope::iter().count());
+    }
+
+    #[test]
+    fn the_repository_default_applies_without_any_permissions() {
+        let [job] = &effective("jobs:\n  build: {}\n")[..] else { panic!() };
+        assert_eq!(*job, EffectivePermissions::Default);
+        assert_eq!(job.grant(Scope::Contents), None);
+        assert_eq!(job.granted().count(), 0);
+    }
+}
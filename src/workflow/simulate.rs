@@ -0,0 +1,344 @@
+use super::filter::{included, FilterPattern};
+use super::on::{Event, EventKind, Globbed, Trigger};
+use crate::expression::eval::Context;
+use crate::expression::value::Value;
+use crate::scavenge::ast::{PossumNode, PossumSeq};
+use std::fmt::Display;
+
+// pull_request and pull_request_target only run for these unless types is set
+const DEFAULT_PULL_REQUEST_TYPES: &[&str] = &["opened", "synchronize", "reopened"];
+
+// An event described on the command line rather than sent by GitHub
+#[derive(Debug, Clone)]
+pub struct SyntheticEvent {
+    pub kind: EventKind,
+    pub activity: Option<String>,
+    pub git_ref: Option<GitRef>,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum GitRef {
+    Branch(String),
+    Tag(String),
+}
+
+#[derive(Debug)]
+pub enum Verdict {
+    Runs(Vec<String>),
+    Skipped(String),
+}
+
+impl Display for GitRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitRef::Branch(b) => write!(f, "branch {b}"),
+            GitRef::Tag(t) => write!(f, "tag {t}"),
+        }
+    }
+}
+
+impl SyntheticEvent {
+    // the given contexts, filled in with what is known about the event
+    pub fn context(&self, given: &Context) -> Context {
+        let mut context = given.clone();
+        let text = |s: &str| Value::String(s.to_owned());
+
+        context.set_default(&["github", "event_name"], text(&self.kind.to_string()));
+        if let Some(activity) = self.activity.as_ref() {
+            context.set_default(&["github", "event", "action"], text(activity));
+        }
+
+        if let Some(git_ref) = self.git_ref.as_ref() {
+            let (full, name, kind) = match git_ref {
+                GitRef::Branch(b) => (format!("refs/heads/{b}"), b, "branch"),
+                GitRef::Tag(t) => (format!("refs/tags/{t}"), t, "tag"),
+            };
+
+            // a pull request's ref is its merge ref, the branch is its base
+            if matches!(self.kind, EventKind::PullRequest | EventKind::PullRequestTarget) {
+                context.set_default(&["github", "base_ref"], text(name));
+                context.set_default(&["github", "event", "pull_request", "base", "ref"], text(name));
+            } else {
+                context.set_default(&["github", "ref"], text(&full));
+                context.set_default(&["github", "ref_name"], text(name));
+                context.set_default(&["github", "ref_type"], text(kind));
+            }
+        }
+
+        context
+    }
+
+    // whether a workflow triggered like this would be queued, and why
+    pub fn evaluate(&self, trigger: &Trigger) -> Verdict {
+        let event = match trigger.events().find(|(k, _)| k.value() == Some(&self.kind)) {
+            Some((_, event)) => event,
+            None => return Verdict::Skipped(format!("not triggered by {}", self.kind)),
+        };
+
+        let mut reasons = vec![format!("triggered by {}", self.kind)];
+
+        // `on: pull_request` with no filters still only runs for some types
+        let unfiltered = Event::new();
+        let event = event.value().unwrap_or(&unfiltered);
+
+        let checks = [
+            self.types(event),
+            self.refs(event),
+            self.changed_paths(event),
+        ];
+
+        for check in checks {
+            match check {
+                Ok(Some(reason)) => reasons.push(reason),
+                Ok(None) => {}
+                Err(skipped) => return Verdict::Skipped(skipped),
+            }
+        }
+
+        Verdict::Runs(reasons)
+    }
+
+    fn types(&self, event: &Event) -> Result<Option<String>, String> {
+        let activity = match self.activity.as_ref() {
+            Some(activity) => activity,
+            None => return Ok(None),
+        };
+
+        let types: Vec<&str> = match strings(&event.types) {
+            Some(types) => types,
+            None if matches!(self.kind, EventKind::PullRequest | EventKind::PullRequestTarget) => {
+                DEFAULT_PULL_REQUEST_TYPES.to_vec()
+            }
+            None => return Ok(None),
+        };
+
+        if types.iter().any(|t| t == activity) {
+            Ok(Some(format!("{activity} is in types [{}]", types.join(", "))))
+        } else {
+            Err(format!("{activity} is not in types [{}]", types.join(", ")))
+        }
+    }
+
+    // pushes only look at the filter for the kind of ref that was pushed, but
+    // filtering on one kind alone skips pushes of the other
+    fn refs(&self, event: &Event) -> Result<Option<String>, String> {
+        let git_ref = match self.git_ref.as_ref() {
+            Some(git_ref) => git_ref,
+            None => return Ok(None),
+        };
+
+        let branch_filtered = event.branches.is_some() || event.branches_ignore.is_some();
+        let tag_filtered = event.tags.is_some() || event.tags_ignore.is_some();
+
+        let (name, patterns, other_filtered) = match git_ref {
+            GitRef::Branch(b) => {
+                let patterns = Patterns::of("branches", &event.branches, &event.branches_ignore)?;
+                (b, patterns, tag_filtered)
+            }
+            GitRef::Tag(t) => {
+                let patterns = Patterns::of("tags", &event.tags, &event.tags_ignore)?;
+                (t, patterns, branch_filtered)
+            }
+        };
+
+        let patterns = match patterns {
+            Some(patterns) => patterns,
+            None if other_filtered && self.kind == EventKind::Push => {
+                let only = match git_ref {
+                    GitRef::Branch(_) => "tags",
+                    GitRef::Tag(_) => "branches",
+                };
+                return Err(format!("{git_ref} is skipped, the event only filters {only}"));
+            }
+            None => return Ok(None),
+        };
+
+        patterns.check(git_ref, name).map(Some)
+    }
+
+    // paths only have to match one changed file, paths-ignore has to match
+    // all of them
+    fn changed_paths(&self, event: &Event) -> Result<Option<String>, String> {
+        let patterns = match Patterns::of("paths", &event.paths, &event.paths_ignore)? {
+            Some(patterns) => patterns,
+            None => return Ok(None),
+        };
+
+        if matches!(self.git_ref, Some(GitRef::Tag(_))) {
+            return Ok(Some("paths filters aren't checked for tag pushes".to_owned()));
+        }
+
+        if self.paths.is_empty() {
+            return Ok(Some("paths filters weren't checked, no changed paths were given".to_owned()));
+        }
+
+        let results: Vec<_> = self.paths.iter().map(|p| patterns.check(p, p)).collect();
+        match results.iter().find_map(|r| r.as_ref().ok()) {
+            Some(reason) => Ok(Some(reason.clone())),
+            None => Err(results
+                .iter()
+                .filter_map(|r| r.as_ref().err().cloned())
+                .collect::<Vec<_>>()
+                .join(", ")),
+        }
+    }
+}
+
+type Filter = Option<PossumNode<PossumSeq<Globbed>>>;
+
+// the include or ignore filter of one kind, as GitHub reads it
+struct Patterns {
+    parsed: Vec<FilterPattern>,
+    raw: String,
+    ignore: bool,
+}
+
+impl Patterns {
+    // None when neither is set. GitHub refuses to run a workflow with both
+    // or with a pattern it can't read, so those skip it.
+    fn of(name: &str, include: &Filter, ignore: &Filter) -> Result<Option<Patterns>, String> {
+        let (patterns, ignore) = match (globs(include), globs(ignore)) {
+            (Some(_), Some(_)) => {
+                return Err(format!("{name} and {name}-ignore can't both be used, the workflow is invalid"))
+            }
+            (Some(patterns), None) => (patterns, false),
+            (None, Some(patterns)) => (patterns, true),
+            (None, None) => return Ok(None),
+        };
+
+        let parsed = patterns
+            .iter()
+            .map(|g| {
+                g.pattern()
+                    .map_err(|e| format!("{} isn't a valid {name} pattern, {e}", g.raw()))
+            })
+            .collect::<Result<_, _>>()?;
+        let raw = patterns.iter().map(|g| g.raw()).collect::<Vec<_>>().join(", ");
+
+        Ok(Some(Patterns { parsed, raw, ignore }))
+    }
+
+    // Ok when value lets the workflow run, subject is how it's described
+    fn check(&self, subject: impl Display, value: &str) -> Result<String, String> {
+        let raw = &self.raw;
+        match (included(&self.parsed, value), self.ignore) {
+            (true, false) => Ok(format!("{subject} matches [{raw}]")),
+            (false, false) => Err(format!("{subject} doesn't match [{raw}]")),
+            (true, true) => Err(format!("{subject} is ignored by [{raw}]")),
+            (false, true) => Ok(format!("{subject} isn't ignored by [{raw}]")),
+        }
+    }
+}
+
+fn globs(filter: &Filter) -> Option<Vec<&Globbed>> {
+    filter
+        .as_ref()
+        .and_then(|f| f.value())
+        .map(|f| f.iter().filter_map(|g| g.value()).collect())
+}
+
+fn strings(seq: &Option<PossumNode<PossumSeq<String>>>) -> Option<Vec<&str>> {
+    seq.as_ref()
+        .and_then(|s| s.value())
+        .map(|s| s.iter().filter_map(|v| v.value()).map(String::as_str).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GitRef, SyntheticEvent, Verdict};
+    use crate::workflow::on::EventKind;
+    use crate::workflow::parser::test_workflow;
+
+    fn event(kind: EventKind, activity: Option<&str>, git_ref: Option<GitRef>, paths: &[&str]) -> SyntheticEvent {
+        SyntheticEvent {
+            kind,
+            activity: activity.map(str::to_owned),
+            git_ref,
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    fn branch(name: &str) -> Option<GitRef> {
+        Some(GitRef::Branch(name.to_owned()))
+    }
+
+    fn tag(name: &str) -> Option<GitRef> {
+        Some(GitRef::Tag(name.to_owned()))
+    }
+
+    // whether the workflow triggered by on runs, with the last reason given
+    fn verdict(on: &str, event: &SyntheticEvent) -> (bool, String) {
+        let wf = test_workflow(&format!("on:\n{on}jobs: {{}}\n"));
+        match event.evaluate(wf.on.as_ref().and_then(|on| on.value()).unwrap()) {
+            Verdict::Runs(reasons) => (true, reasons.last().cloned().unwrap_or_default()),
+            Verdict::Skipped(reason) => (false, reason),
+        }
+    }
+
+    fn check(cases: &[(&str, SyntheticEvent, bool, &str)]) {
+        for (on, event, runs, reason) in cases {
+            assert_eq!(verdict(on, event), (*runs, reason.to_string()), "{on}");
+        }
+    }
+
+    #[test]
+    fn pull_requests_default_to_three_types() {
+        use EventKind::PullRequest;
+        check(&[
+            ("  pull_request:\n", event(PullRequest, Some("opened"), None, &[]), true, "opened is in types [opened, synchronize, reopened]"),
+            ("  pull_request:\n", event(PullRequest, Some("closed"), None, &[]), false, "closed is not in types [opened, synchronize, reopened]"),
+            ("  pull_request:\n    types: [closed]\n", event(PullRequest, Some("closed"), None, &[]), true, "closed is in types [closed]"),
+            ("  issues:\n", event(EventKind::Issues, Some("closed"), None, &[]), true, "triggered by issues"),
+        ]);
+    }
+
+    #[test]
+    fn pushes_check_the_filter_for_their_kind_of_ref() {
+        use EventKind::Push;
+        let branches = "  push:\n    branches: [main, 'releases/**']\n";
+        check(&[
+            (branches, event(Push, None, branch("releases/v1/fix"), &[]), true, "branch releases/v1/fix matches [main, releases/**]"),
+            (branches, event(Push, None, branch("dev"), &[]), false, "branch dev doesn't match [main, releases/**]"),
+            (branches, event(Push, None, tag("v1"), &[]), false, "tag v1 is skipped, the event only filters branches"),
+            ("  push:\n    tags-ignore: ['v*']\n", event(Push, None, tag("v1"), &[]), false, "tag v1 is ignored by [v*]"),
+            ("  push:\n", event(Push, None, tag("v1"), &[]), true, "triggered by push"),
+        ]);
+    }
+
+    #[test]
+    fn pull_requests_match_their_base_branch() {
+        use EventKind::PullRequest;
+        let on = "  pull_request:\n    branches-ignore: ['dependabot/**']\n";
+        check(&[
+            (on, event(PullRequest, None, branch("main"), &[]), true, "branch main isn't ignored by [dependabot/**]"),
+            (on, event(PullRequest, None, branch("dependabot/npm"), &[]), false, "branch dependabot/npm is ignored by [dependabot/**]"),
+            // pull requests don't look at tags at all
+            ("  pull_request:\n    tags: [v1]\n", event(PullRequest, None, branch("main"), &[]), true, "triggered by pull_request"),
+        ]);
+    }
+
+    #[test]
+    fn paths_need_one_file_and_paths_ignore_all_of_them() {
+        use EventKind::Push;
+        let paths = "  push:\n    paths: ['src/**']\n";
+        let ignored = "  push:\n    paths-ignore: ['docs/**']\n";
+        check(&[
+            (paths, event(Push, None, None, &["README.md", "src/main.rs"]), true, "src/main.rs matches [src/**]"),
+            (paths, event(Push, None, None, &["README.md", "docs/a.md"]), false, "README.md doesn't match [src/**], docs/a.md doesn't match [src/**]"),
+            (ignored, event(Push, None, None, &["docs/a.md", "src/main.rs"]), true, "src/main.rs isn't ignored by [docs/**]"),
+            (ignored, event(Push, None, None, &["docs/a.md", "docs/b.md"]), false, "docs/a.md is ignored by [docs/**], docs/b.md is ignored by [docs/**]"),
+            (paths, event(Push, None, None, &[]), true, "paths filters weren't checked, no changed paths were given"),
+            (paths, event(Push, None, tag("v1"), &["README.md"]), true, "paths filters aren't checked for tag pushes"),
+        ]);
+    }
+
+    #[test]
+    fn invalid_filters_are_reported() {
+        use EventKind::Push;
+        check(&[
+            ("  push:\n    branches: ['[main']\n", event(Push, None, branch("main"), &[]), false, "[main isn't a valid branches pattern, [ is never closed"),
+            ("  push:\n    paths: [src]\n    paths-ignore: [docs]\n", event(Push, None, None, &["src"]), false, "paths and paths-ignore can't both be used, the workflow is invalid"),
+        ]);
+    }
+}